@@ -42,6 +42,7 @@ impl ChainBencher {
             let best_block_id = *startup_info.get_master();
             TxPool::start(
                 node_config.tx_pool.clone(),
+                node_config.time_service(),
                 storage.clone(),
                 best_block_id,
                 bus.clone(),
@@ -104,6 +105,7 @@ impl ChainBencher {
                     Some(self.account.get_auth_key().prefix().to_vec()),
                     parent,
                     txn_vec,
+                    None,
                 )
                 .unwrap();
             let block =
@@ -52,7 +52,10 @@ impl SyncBencher {
                     .await
                     .unwrap();
             let chain_2_clone = chain_2.clone();
-            let downloader = Arc::new(Downloader::new(chain_2_clone));
+            let downloader = Arc::new(Downloader::new(
+                Arc::new(NodeConfig::random_for_test()),
+                chain_2_clone,
+            ));
             for i in 0..3 {
                 SyncBencher::sync_block_inner(downloader.clone(), network_2.clone())
                     .await
@@ -169,6 +172,7 @@ async fn create_node(
         let best_block_id = *genesis_startup_info.get_master();
         TxPool::start(
             node_config.tx_pool.clone(),
+            node_config.time_service(),
             storage.clone(),
             best_block_id,
             bus.clone(),
@@ -239,6 +243,7 @@ async fn create_node(
                     Some(miner_account.get_auth_key().prefix().to_vec()),
                     None,
                     txn_vec,
+                    None,
                 )
                 .await
                 .unwrap()
@@ -4,11 +4,7 @@ use logger::prelude::*;
 use starcoin_accumulator::{node::AccumulatorStoreType, Accumulator, MerkleAccumulator};
 use starcoin_state_api::{ChainStateReader, ChainStateWriter};
 use starcoin_statedb::ChainStateDB;
-use std::{
-    convert::TryInto,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::{convert::TryInto, sync::Arc};
 use storage::Store;
 use traits::ExcludedTxns;
 use types::{
@@ -37,9 +33,12 @@ pub struct OpenedBlock {
 }
 
 impl OpenedBlock {
+    /// `block_timestamp` is the timestamp of the new block in seconds, it is read from the
+    /// `TimeService` of the node by the caller.
     pub fn new<S>(
         storage: Arc<S>,
         previous_header: BlockHeader,
+        block_timestamp: u64,
         block_gas_limit: u64,
         author: AccountAddress,
         auth_key_prefix: Option<Vec<u8>>,
@@ -60,7 +59,6 @@ impl OpenedBlock {
             AccumulatorStoreType::Transaction,
             storage.clone(),
         )?;
-        let block_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
         let chain_state = ChainStateDB::new(storage, Some(previous_header.state_root()));
         let block_meta =
@@ -12,10 +12,11 @@ use starcoin_open_block::OpenedBlock;
 use starcoin_state_api::{ChainState, ChainStateReader, ChainStateWriter};
 use starcoin_statedb::ChainStateDB;
 use std::iter::Extend;
-use std::time::{SystemTime, UNIX_EPOCH};
 use std::{convert::TryInto, marker::PhantomData, sync::Arc};
-use storage::Store;
-use traits::{ChainReader, ChainWriter, Consensus, ExcludedTxns};
+use storage::{AtomicWriteStore, Store};
+use traits::{
+    BlockVerifyError, ChainHeaderReader, ChainReader, ChainWriter, Consensus, ExcludedTxns,
+};
 use types::{
     account_address::AccountAddress,
     accumulator_info::AccumulatorInfo,
@@ -23,11 +24,18 @@ use types::{
         Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate,
         ALLOWED_FUTURE_BLOCKTIME,
     },
+    contract_event::ContractEventInfo,
     error::BlockExecutorError,
+    filter::Filter,
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
     U256,
 };
 
+/// Max blocks a get_events query without event keys may scan.
+pub const MAX_EVENT_QUERY_BLOCKS: u64 = 1000;
+/// Max events a get_events query may return.
+pub const MAX_EVENT_QUERY_LIMIT: usize = 1000;
+
 pub struct BlockChain<C, S>
 where
     C: Consensus,
@@ -98,6 +106,21 @@ where
             .get_block_info(block_id)?
             .ok_or_else(|| format_err!("Can not find block info by hash {}", block_id))?)
     }
+    /// The min timestamp of a child of block `parent`, the median timestamp of recent blocks
+    /// from `median_time_past_number`, the timestamp of the parent before it.
+    fn min_child_timestamp(&self, parent: &BlockHeader) -> Result<u64> {
+        if self
+            .config
+            .net()
+            .get_config()
+            .is_median_time_past(parent.number() + 1)
+        {
+            self.median_timestamp(parent.id())
+        } else {
+            Ok(parent.timestamp())
+        }
+    }
+
     pub fn save_block_info(&self, block_info: BlockInfo) {
         let block_id = *block_info.block_id();
         if let Err(e) = self.storage.save_block_info(block_info) {
@@ -111,10 +134,21 @@ where
         auth_key_prefix: Option<Vec<u8>>,
         previous_header: BlockHeader,
         user_txns: Vec<SignedUserTransaction>,
+        block_timestamp: Option<u64>,
     ) -> Result<(BlockTemplate, ExcludedTxns)> {
+        let block_timestamp = match block_timestamp {
+            Some(block_timestamp) => block_timestamp,
+            // never older than the min timestamp, even if the local clock is behind.
+            None => self
+                .config
+                .time_service()
+                .now_secs()
+                .max(self.min_child_timestamp(&previous_header)?),
+        };
         let mut opened_block = OpenedBlock::new(
             self.storage.clone(),
             previous_header,
+            block_timestamp,
             self.config.miner.block_gas_limit,
             author,
             auth_key_prefix,
@@ -178,6 +212,20 @@ where
     }
 }
 
+impl<C, S> ChainHeaderReader for BlockChain<C, S>
+where
+    C: Consensus,
+    S: Store,
+{
+    fn head_header(&self) -> BlockHeader {
+        self.current_header()
+    }
+
+    fn find_header(&self, hash: HashValue) -> Result<Option<BlockHeader>> {
+        self.get_header(hash)
+    }
+}
+
 impl<C, S> ChainReader for BlockChain<C, S>
 where
     C: Consensus,
@@ -276,7 +324,7 @@ where
         let previous_header = self
             .get_header(block_id)?
             .ok_or_else(|| format_err!("Can find block header by {:?}", block_id))?;
-        self.create_block_template_inner(author, auth_key_prefix, previous_header, user_txns)
+        self.create_block_template_inner(author, auth_key_prefix, previous_header, user_txns, None)
     }
 
     fn chain_state_reader(&self) -> &dyn ChainStateReader {
@@ -304,6 +352,35 @@ where
         }
         false
     }
+
+    fn get_events(&self, filter: &Filter) -> Result<Vec<ContractEventInfo>> {
+        let limit = filter.limit.unwrap_or(MAX_EVENT_QUERY_LIMIT);
+        ensure!(
+            limit <= MAX_EVENT_QUERY_LIMIT,
+            "Events limit {} exceeds max {}.",
+            limit,
+            MAX_EVENT_QUERY_LIMIT
+        );
+        let head_number = self.current_header().number();
+        if filter.from_block > head_number || limit == 0 {
+            return Ok(vec![]);
+        }
+        let to_block = std::cmp::min(filter.to_block, head_number);
+        let mut events = if filter.event_keys.is_empty() {
+            ensure!(
+                to_block - filter.from_block < MAX_EVENT_QUERY_BLOCKS,
+                "Block range [{}, {}] exceeds max {} blocks, please set event keys or narrow the range.",
+                filter.from_block,
+                to_block,
+                MAX_EVENT_QUERY_BLOCKS
+            );
+            self.get_events_by_block(filter, to_block, limit)?
+        } else {
+            self.get_events_by_key(filter, to_block, limit)?
+        };
+        events.reverse();
+        Ok(events)
+    }
 }
 
 impl<C, S> BlockChain<C, S>
@@ -311,9 +388,85 @@ where
     C: Consensus,
     S: Store,
 {
+    /// Scan the master blocks from `to_block` down to `filter.from_block`, newest event first.
+    fn get_events_by_block(
+        &self,
+        filter: &Filter,
+        to_block: BlockNumber,
+        limit: usize,
+    ) -> Result<Vec<ContractEventInfo>> {
+        let mut events = vec![];
+        let mut number = to_block;
+        while events.len() < limit {
+            let block_id = self.find_block_by_number(number)?;
+            if let Some(block_events) = self.storage.get_contract_events_by_block(block_id)? {
+                events.extend(
+                    block_events
+                        .into_iter()
+                        .rev()
+                        .filter(|e| filter.matching(number, &e.event))
+                        .take(limit - events.len()),
+                );
+            }
+            if number <= filter.from_block {
+                break;
+            }
+            number -= 1;
+        }
+        Ok(events)
+    }
+
+    /// Look up the events of `filter.event_keys` by the event index, newest event first.
+    /// The index only holds events of master blocks, events of blocks which are not on this
+    /// chain are skipped.
+    fn get_events_by_key(
+        &self,
+        filter: &Filter,
+        to_block: BlockNumber,
+        limit: usize,
+    ) -> Result<Vec<ContractEventInfo>> {
+        let mut events = vec![];
+        for event_key in &filter.event_keys {
+            let mut key_events = 0;
+            let mut before_seq = None;
+            'key: loop {
+                let page = self
+                    .storage
+                    .get_contract_events_by_key(*event_key, before_seq, limit)?;
+                let page_len = page.len();
+                for event_info in page {
+                    before_seq = Some(event_info.event.sequence_number());
+                    if event_info.block_number < filter.from_block {
+                        break 'key;
+                    }
+                    if event_info.block_number > to_block
+                        || self.find_block_by_number(event_info.block_number)?
+                            != event_info.block_hash
+                    {
+                        continue;
+                    }
+                    events.push(event_info);
+                    key_events += 1;
+                    if key_events >= limit {
+                        break 'key;
+                    }
+                }
+                if page_len < limit {
+                    break;
+                }
+            }
+        }
+        events.sort_by(|a, b| {
+            (b.block_number, b.transaction_index).cmp(&(a.block_number, a.transaction_index))
+        });
+        events.truncate(limit);
+        Ok(events)
+    }
+
     fn save(
         &mut self,
         block_id: HashValue,
+        block_number: BlockNumber,
         transactions: Vec<Transaction>,
         txn_infos: Vec<TransactionInfo>,
     ) -> Result<()> {
@@ -326,6 +479,14 @@ where
             .cloned()
             .map(|user_txn| user_txn.id())
             .collect::<Vec<HashValue>>();
+        // save block's events
+        let mut events = vec![];
+        for (idx, (txn_id, txn_info)) in txn_id_vec.iter().zip(txn_infos.iter()).enumerate() {
+            events.extend(txn_info.events().iter().cloned().map(|event| {
+                ContractEventInfo::new(block_id, block_number, *txn_id, idx as u64, event)
+            }));
+        }
+        self.storage.save_contract_events(block_id, events)?;
         // save block's transactions
         self.storage.save_block_transactions(block_id, txn_id_vec)?;
         // save transactions
@@ -339,35 +500,96 @@ where
     }
 }
 
-impl<C, S> ChainWriter for BlockChain<C, S>
+impl<C, S> BlockChain<C, S>
 where
     C: Consensus,
     S: Store,
 {
-    fn apply(&mut self, block: Block) -> Result<bool> {
+    /// Run `f` in one storage atomic write, so a crash can not leave a half written block.
+    /// The accumulators and the chain state write to the atomic write while `f` runs, and are
+    /// rebuilt from the storage after it.
+    fn atomic_write<R, F>(&mut self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        let storage = self.storage.clone();
+        let result = storage.atomic_write(|staged| {
+            self.storage = staged;
+            self.reload_head_state()?;
+            f(self)
+        });
+        self.storage = storage;
+        if result.is_err() {
+            if let Err(reload_err) = self.reload_head_state() {
+                error!("reload chain state of head failed: {:?}", reload_err);
+            }
+        }
+        result
+    }
+
+    /// Rebuild the accumulators and the chain state of head from storage.
+    fn reload_head_state(&mut self) -> Result<()> {
+        let block_info = self.get_block_info(self.head.id())?;
+        self.txn_accumulator = info_2_accumulator(
+            block_info.get_txn_accumulator_info(),
+            AccumulatorStoreType::Transaction,
+            self.storage.clone(),
+        )?;
+        self.block_accumulator = info_2_accumulator(
+            block_info.get_block_accumulator_info().clone(),
+            AccumulatorStoreType::Block,
+            self.storage.clone(),
+        )?;
+        self.chain_state =
+            ChainStateDB::new(self.storage.clone(), Some(self.head.header().state_root()));
+        Ok(())
+    }
+
+    /// Switch head to `block` after its data is committed.
+    fn set_head(&mut self, block: Block) -> Result<()> {
+        self.head = block;
+        self.reload_head_state()
+    }
+
+    fn apply_inner(&mut self, block: &Block) -> Result<()> {
         let header = block.header();
         let pre_hash = header.parent_hash();
-        assert_eq!(self.head.header().id(), pre_hash);
+        ensure!(
+            self.head.header().id() == pre_hash,
+            BlockVerifyError::ParentMismatch {
+                expect: self.head.header().id(),
+                got: pre_hash,
+            }
+        );
         // do not check genesis block timestamp check
-        if let Some(pre_block) = self.get_block(pre_hash)? {
+        if let Some(pre_header) = self.get_header(pre_hash)? {
+            let min = self.min_child_timestamp(&pre_header)?;
             ensure!(
-                pre_block.header().timestamp() <= header.timestamp(),
-                "Invalid block: block timestamp too old"
+                min <= header.timestamp(),
+                BlockVerifyError::TimestampTooOld {
+                    min,
+                    got: header.timestamp(),
+                }
             );
-            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let now = self.config.time_service().now_secs();
             ensure!(
                 header.timestamp() <= ALLOWED_FUTURE_BLOCKTIME + now,
-                "Invalid block: block timestamp too new"
+                BlockVerifyError::TimestampTooNew {
+                    now,
+                    got: header.timestamp(),
+                }
             );
         }
 
         ensure!(
-            block.header().gas_used() <= block.header().gas_limit(),
-            "invalid block: gas_used should not greater than gas_limit"
+            header.gas_used() <= header.gas_limit(),
+            BlockVerifyError::GasUsedExceedsLimit {
+                gas_used: header.gas_used(),
+                gas_limit: header.gas_limit(),
+            }
         );
         if let Err(e) = C::verify(self.config.clone(), self, header) {
-            error!("verify header failed : {:?}", e);
-            return Ok(false);
+            return Err(BlockVerifyError::Consensus(e.to_string()).into());
         }
 
         let txns = {
@@ -384,26 +606,33 @@ where
         };
 
         let (state_root, vec_transaction_info) =
-            executor::block_execute(&self.chain_state, txns.clone(), block.header().gas_limit())?;
+            executor::block_execute(&self.chain_state, txns.clone(), header.gas_limit())?;
 
-        assert_eq!(
-            block.header().state_root(),
-            state_root,
-            "verify block:{:?} state_root fail.",
-            block.header().id()
+        ensure!(
+            header.state_root() == state_root,
+            BlockVerifyError::StateRootMismatch {
+                expect: state_root,
+                got: header.state_root(),
+            }
         );
 
         let block_gas_used = vec_transaction_info
             .iter()
             .fold(0u64, |acc, i| acc + i.gas_used());
         ensure!(
-            block_gas_used == block.header().gas_used(),
-            "invalid block: gas_used is not match"
+            block_gas_used == header.gas_used(),
+            BlockVerifyError::GasUsedMismatch {
+                expect: block_gas_used,
+                got: header.gas_used(),
+            }
         );
 
         ensure!(
             vec_transaction_info.len() == txns.len(),
-            "invalid txn num in the block"
+            BlockVerifyError::TxnCountMismatch {
+                expect: txns.len(),
+                got: vec_transaction_info.len(),
+            }
         );
 
         // txn accumulator verify.
@@ -417,8 +646,11 @@ where
             accumulator_root
         };
         ensure!(
-            executed_accumulator_root == block.header().accumulator_root(),
-            "verify block: txn accumulator root mismatch"
+            executed_accumulator_root == header.accumulator_root(),
+            BlockVerifyError::AccumulatorRootMismatch {
+                expect: executed_accumulator_root,
+                got: header.accumulator_root(),
+            }
         );
 
         // If chain state is matched, and accumulator is matched,
@@ -429,6 +661,15 @@ where
         self.chain_state
             .flush()
             .map_err(BlockExecutorError::BlockChainStateErr)?;
+        // record stale and new state nodes of the block, the pruner releases the stale nodes
+        // of a master block, or the new nodes of a side block, when it is out of the prune window.
+        let node_changes = self.chain_state.take_node_changes();
+        if !self.config.storage.archive() {
+            self.storage
+                .save_stale_state_nodes(header.id(), node_changes.stale_nodes)?;
+            self.storage
+                .save_new_state_nodes(header.id(), node_changes.new_nodes)?;
+        }
 
         let total_difficulty = {
             let pre_total_difficulty = self
@@ -448,9 +689,31 @@ where
             total_difficulty,
         );
         // save block's transaction relationship and save transaction
-        self.save(header.id(), txns, vec_transaction_info)?;
-        self.commit(block.clone(), block_info, BlockState::Executed)?;
-        Ok(true)
+        self.save(header.id(), header.number(), txns, vec_transaction_info)?;
+        self.commit_inner(block, block_info, BlockState::Executed)
+    }
+
+    fn commit_inner(
+        &mut self,
+        block: &Block,
+        block_info: BlockInfo,
+        block_state: BlockState,
+    ) -> Result<()> {
+        self.storage.commit_block(block.clone(), block_state)?;
+        self.storage.save_block_info(block_info)?;
+        debug!("save block {:?} succ.", block.id());
+        Ok(())
+    }
+}
+
+impl<C, S> ChainWriter for BlockChain<C, S>
+where
+    C: Consensus,
+    S: Store,
+{
+    fn apply(&mut self, block: Block) -> Result<()> {
+        self.atomic_write(|chain| chain.apply_inner(&block))?;
+        self.set_head(block)
     }
 
     fn commit(
@@ -459,14 +722,8 @@ where
         block_info: BlockInfo,
         block_state: BlockState,
     ) -> Result<()> {
-        let block_id = block.id();
-        self.save_block(&block, block_state);
-        self.head = block;
-        self.save_block_info(block_info);
-        self.chain_state =
-            ChainStateDB::new(self.storage.clone(), Some(self.head.header().state_root()));
-        debug!("save block {:?} succ.", block_id);
-        Ok(())
+        self.atomic_write(|chain| chain.commit_inner(&block, block_info, block_state))?;
+        self.set_head(block)
     }
 
     fn chain_state(&mut self) -> &dyn ChainState {
@@ -1,23 +1,39 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{chain::BlockChain, chain_metrics::CHAIN_METRICS};
+use crate::{
+    chain::{info_2_accumulator, BlockChain},
+    chain_metrics::CHAIN_METRICS,
+    pruner::StatePruner,
+};
 use actix::Addr;
-use anyhow::{format_err, Error, Result};
+use anyhow::{ensure, format_err, Error, Result};
 use bus::{Broadcast, BusActor};
 use config::NodeConfig;
 use crypto::HashValue;
 use logger::prelude::*;
+use starcoin_accumulator::{node::AccumulatorStoreType, Accumulator};
 use starcoin_statedb::ChainStateDB;
 use starcoin_txpool_api::TxPoolSyncService;
+use std::collections::HashSet;
 use std::sync::Arc;
-use storage::Store;
-use traits::{ChainReader, ChainService, ChainWriter, ConnectBlockError, ConnectResult, Consensus};
+use storage::{
+    account_transaction::block_account_transactions, recovery::is_block_complete, AtomicWriteStore,
+    Store,
+};
+use traits::{
+    BlockVerifyError, ChainReader, ChainService, ChainWriter, ConnectBlockError, ConnectResult,
+    Consensus,
+};
 use types::{
     account_address::AccountAddress,
+    account_transaction::AccountTransactionInfo,
     block::{Block, BlockDetail, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate},
+    contract_event::ContractEventInfo,
+    filter::Filter,
+    proof::{BlockProof, TransactionProof},
     startup_info::StartupInfo,
-    system_events::NewHeadBlock,
+    system_events::{ChainReorganized, NewHeadBlock},
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
 };
 
@@ -33,6 +49,7 @@ where
     storage: Arc<S>,
     txpool: P,
     bus: Addr<BusActor>,
+    pruner: Option<StatePruner<S>>,
 }
 
 impl<C, S, P> ChainServiceImpl<C, S, P>
@@ -49,6 +66,18 @@ where
         bus: Addr<BusActor>,
     ) -> Result<Self> {
         let master = BlockChain::new(config.clone(), startup_info.master, storage.clone())?;
+        let pruner = if config.storage.archive() {
+            None
+        } else {
+            info!(
+                "State pruning enabled, keep state of latest {} blocks.",
+                config.storage.state_prune_window()
+            );
+            Some(StatePruner::new(
+                config.storage.state_prune_window(),
+                storage.clone(),
+            )?)
+        };
         Ok(Self {
             config,
             startup_info,
@@ -56,6 +85,7 @@ where
             storage,
             txpool,
             bus,
+            pruner,
         })
     }
 
@@ -89,6 +119,73 @@ where
         }
     }
 
+    /// Reject the block if it conflicts with a checkpoint of the `ChainConfig`, or its branch
+    /// forks from master deeper than the max reorg depth.
+    /// Return a `BlockVerifyError` if the branch is rejected.
+    fn verify_fork(&self, header: &BlockHeader) -> Result<()> {
+        let chain_config = self.config.net().get_config();
+        if let Some(checkpoint) = chain_config.checkpoint(header.number()) {
+            ensure!(
+                header.id() == checkpoint,
+                BlockVerifyError::CheckpointMismatch {
+                    number: header.number(),
+                    expect: checkpoint,
+                    got: header.id(),
+                }
+            );
+        }
+        let master_header = self.get_master().current_header();
+        if header.parent_hash() == master_header.id() {
+            return Ok(());
+        }
+        let ancestor = self
+            .storage
+            .get_common_ancestor(header.parent_hash(), master_header.id())?
+            .ok_or_else(|| {
+                format_err!(
+                    "Can not find ancestor with {:?} and {:?}.",
+                    header.parent_hash(),
+                    master_header.id()
+                )
+            })?;
+        let fork_number = self
+            .storage
+            .get_block_header_by_hash(ancestor)?
+            .ok_or_else(|| format_err!("Can not find block header {:?}.", ancestor))?
+            .number();
+        if let Some((checkpoint, _)) = chain_config
+            .checkpoints
+            .iter()
+            .find(|(number, _)| *number > fork_number && *number <= master_header.number())
+        {
+            return Err(BlockVerifyError::CheckpointConflict {
+                checkpoint: *checkpoint,
+                fork_number,
+            }
+            .into());
+        }
+        let depth = master_header.number() - fork_number;
+        let max = chain_config.max_reorg_depth;
+        ensure!(depth <= max, BlockVerifyError::ReorgTooDeep { depth, max });
+        Ok(())
+    }
+
+    /// Convert the error of connecting block `block_id` to a `ConnectBlockError`, so an invalid
+    /// block is reported to the caller instead of failing the chain service.
+    fn connect_error(block_id: HashValue, e: Error) -> ConnectBlockError {
+        match e.downcast::<BlockVerifyError>() {
+            Ok(verify_err) => {
+                warn!("Verify block {:?} failed: {}", block_id, verify_err);
+                CHAIN_METRICS.verify_fail_count.inc();
+                ConnectBlockError::VerifyFailed(verify_err)
+            }
+            Err(e) => {
+                warn!("Connect block {:?} failed: {:?}", block_id, e);
+                ConnectBlockError::Other(e.to_string())
+            }
+        }
+    }
+
     pub fn state_at(&self, _root: HashValue) -> ChainStateDB {
         unimplemented!()
     }
@@ -113,24 +210,54 @@ where
 
             debug_assert!(!enacted_blocks.is_empty());
             debug_assert_eq!(enacted_blocks.last().unwrap(), &block);
-            self.update_master(new_branch);
+            let mut startup_info = self.startup_info.clone();
+            startup_info.update_master(block_header);
+            self.commit_master(&enacted_blocks, &retracted_blocks, startup_info.clone())?;
+            self.update_master(new_branch, startup_info);
+            if !retracted_blocks.is_empty() {
+                self.broadcast_reorg_2_bus(enacted_blocks.clone(), retracted_blocks.clone());
+            }
             self.commit_2_txpool(enacted_blocks, retracted_blocks);
             CHAIN_METRICS.broadcast_head_count.inc();
             self.broadcast_2_bus(BlockDetail::new(block, total_difficulty));
+            self.prune_state();
         } else {
             self.insert_branch(block_header);
+            self.save_startup()?;
         }
 
         CHAIN_METRICS
             .branch_total_count
             .set(self.startup_info.branches.len() as i64);
-        self.save_startup()
+        Ok(())
+    }
+
+    /// Write the indexes of the new master and its startup info in one atomic write, so a
+    /// crash never leaves the indexes and the persisted head out of sync.
+    fn commit_master(
+        &self,
+        enacted: &[Block],
+        retracted: &[Block],
+        startup_info: StartupInfo,
+    ) -> Result<()> {
+        self.storage.atomic_write(|storage| {
+            self.index_account_transactions(storage.as_ref(), enacted, retracted)?;
+            self.index_contract_events(storage.as_ref(), enacted, retracted)?;
+            storage.save_startup_info(startup_info)
+        })
     }
 
-    fn update_master(&mut self, new_master: BlockChain<C, S>) {
-        let header = new_master.current_header();
+    fn prune_state(&mut self) {
+        if let Some(pruner) = self.pruner.as_mut() {
+            if let Err(e) = pruner.schedule(&self.master) {
+                error!("Schedule state pruning error: {:?}", e);
+            }
+        }
+    }
+
+    fn update_master(&mut self, new_master: BlockChain<C, S>, startup_info: StartupInfo) {
         self.master = new_master;
-        self.startup_info.update_master(&header);
+        self.startup_info = startup_info;
     }
 
     fn insert_branch(&mut self, new_block_header: &BlockHeader) {
@@ -148,6 +275,67 @@ where
         }
     }
 
+    /// Keep account transaction history consistent with master: revert retracted blocks
+    /// from newest to oldest, then append enacted blocks.
+    fn index_account_transactions(
+        &self,
+        storage: &S,
+        enacted: &[Block],
+        retracted: &[Block],
+    ) -> Result<()> {
+        for block in retracted.iter().rev() {
+            let addresses = self
+                .block_account_transactions(storage, block)?
+                .into_iter()
+                .map(|(address, _)| address)
+                .collect::<HashSet<_>>();
+            storage.revert_account_transactions(block.id(), addresses.into_iter().collect())?;
+        }
+        for block in enacted {
+            let transactions = self.block_account_transactions(storage, block)?;
+            storage.append_account_transactions(transactions)?;
+        }
+        Ok(())
+    }
+
+    /// Only events of master blocks are indexed by event key and sequence number, which
+    /// retracted blocks share with enacted ones, so replace the events of retracted blocks.
+    fn index_contract_events(
+        &self,
+        storage: &S,
+        enacted: &[Block],
+        retracted: &[Block],
+    ) -> Result<()> {
+        let block_events = |blocks: &[Block]| -> Result<Vec<ContractEventInfo>> {
+            let mut events = vec![];
+            for block in blocks {
+                events.extend(
+                    storage
+                        .get_contract_events_by_block(block.id())?
+                        .unwrap_or_default(),
+                );
+            }
+            Ok(events)
+        };
+        storage.index_contract_events(block_events(retracted)?, block_events(enacted)?)
+    }
+
+    fn block_account_transactions(
+        &self,
+        storage: &S,
+        block: &Block,
+    ) -> Result<Vec<(AccountAddress, AccountTransactionInfo)>> {
+        let events = storage
+            .get_contract_events_by_block(block.id())?
+            .unwrap_or_default();
+        Ok(block_account_transactions(
+            block.id(),
+            block.header().number(),
+            block.transactions(),
+            &events,
+        ))
+    }
+
     fn find_ancestors(&self, new_branch: &BlockChain<C, S>) -> Result<(Vec<Block>, Vec<Block>)> {
         let block_enacted = new_branch.current_header().id();
         let block_retracted = self.get_master().current_header().id();
@@ -198,6 +386,20 @@ where
             msg: NewHeadBlock(Arc::new(block)),
         });
     }
+
+    fn broadcast_reorg_2_bus(&self, enacted: Vec<Block>, retracted: Vec<Block>) {
+        info!(
+            "Chain reorganized, enacted {} blocks, retracted {} blocks.",
+            enacted.len(),
+            retracted.len()
+        );
+        self.bus.do_send(Broadcast {
+            msg: ChainReorganized {
+                enacted: Arc::new(enacted),
+                retracted: Arc::new(retracted),
+            },
+        });
+    }
 }
 
 impl<C, S, P> ChainService for ChainServiceImpl<C, S, P>
@@ -217,15 +419,16 @@ where
                 .exe_block_time
                 .with_label_values(&["time"])
                 .start_timer();
-            let connected = branch.apply(block.clone())?;
+            let applied = self
+                .verify_fork(block.header())
+                .and_then(|_| branch.apply(block.clone()));
             timer.observe_duration();
-            if !connected {
-                debug!("connected failed {:?}", block.header().id());
-                CHAIN_METRICS.verify_fail_count.inc();
-                Ok(ConnectResult::Err(ConnectBlockError::VerifyFailed))
-            } else {
-                self.select_head(branch)?;
-                Ok(ConnectResult::Ok(()))
+            match applied {
+                Ok(()) => {
+                    self.select_head(branch)?;
+                    Ok(ConnectResult::Ok(()))
+                }
+                Err(e) => Ok(ConnectResult::Err(Self::connect_error(block.id(), e))),
             }
         } else {
             Ok(ConnectResult::Err(ConnectBlockError::FutureBlock))
@@ -242,14 +445,26 @@ where
             CHAIN_METRICS.duplicate_conn_count.inc();
             Ok(ConnectResult::Err(ConnectBlockError::DuplicateConn))
         } else if let Some(mut branch) = fork {
-            if C::verify(self.config.clone(), &branch, block.header()).is_ok() {
-                // 2. commit block
-                branch.append_block(block.id(), block_info.get_block_accumulator_info().clone())?;
-                branch.commit(block, block_info, BlockState::Verified)?;
-                self.select_head(branch)?;
-                Ok(ConnectResult::Ok(()))
-            } else {
-                Ok(ConnectResult::Err(ConnectBlockError::VerifyFailed))
+            if let Err(e) = self.verify_fork(block.header()) {
+                return Ok(ConnectResult::Err(Self::connect_error(block.id(), e)));
+            }
+            match C::verify(self.config.clone(), &branch, block.header()) {
+                Ok(()) => {
+                    // 2. commit block
+                    branch.append_block(
+                        block.id(),
+                        block_info.get_block_accumulator_info().clone(),
+                    )?;
+                    branch.commit(block, block_info, BlockState::Verified)?;
+                    self.select_head(branch)?;
+                    Ok(ConnectResult::Ok(()))
+                }
+                Err(e) => {
+                    CHAIN_METRICS.verify_fail_count.inc();
+                    Ok(ConnectResult::Err(ConnectBlockError::VerifyFailed(
+                        BlockVerifyError::Consensus(e.to_string()),
+                    )))
+                }
             }
         } else {
             Ok(ConnectResult::Err(ConnectBlockError::FutureBlock))
@@ -288,6 +503,89 @@ where
             .get_transaction_info_by_block_and_index(block_id, idx)
     }
 
+    fn get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> Result<Option<TransactionProof>> {
+        let block_info = match self.storage.get_block_info(block_id)? {
+            Some(block_info) => block_info,
+            None => return Ok(None),
+        };
+        let txn_info_ids = self.storage.get_block_txn_info_ids(block_id)?;
+        let txn_info_id = match txn_info_ids.get(txn_index as usize) {
+            Some(txn_info_id) => *txn_info_id,
+            None => return Ok(None),
+        };
+        let txn_info = self
+            .storage
+            .get_transaction_info(txn_info_id)?
+            .ok_or_else(|| format_err!("Can not find txn info {:?}.", txn_info_id))?;
+        let first_txn_info = self
+            .storage
+            .get_transaction_info(txn_info_ids[0])?
+            .ok_or_else(|| format_err!("Can not find txn info {:?}.", txn_info_ids[0]))?;
+        let txn_accumulator_info = block_info.get_txn_accumulator_info().clone();
+        let accumulator_root = *txn_accumulator_info.get_accumulator_root();
+        // txn infos of the block are the last leaves of its txn accumulator.
+        let first_leaf_index = txn_accumulator_info.get_num_leaves() - txn_info_ids.len() as u64;
+        let leaf_index = first_leaf_index + txn_index;
+        let accumulator = info_2_accumulator(
+            txn_accumulator_info,
+            AccumulatorStoreType::Transaction,
+            self.storage.clone(),
+        )?;
+        let proof = accumulator.get_proof(leaf_index)?.ok_or_else(|| {
+            format_err!("Can not get proof of txn accumulator leaf {}.", leaf_index)
+        })?;
+        let first_txn_proof = accumulator.get_proof(first_leaf_index)?.ok_or_else(|| {
+            format_err!(
+                "Can not get proof of txn accumulator leaf {}.",
+                first_leaf_index
+            )
+        })?;
+        Ok(Some(TransactionProof {
+            block_id,
+            txn_index,
+            leaf_index,
+            accumulator_root,
+            txn_info,
+            proof,
+            first_txn_info,
+            first_txn_proof,
+        }))
+    }
+
+    fn master_block_proof(&self, number: BlockNumber) -> Result<Option<BlockProof>> {
+        let block_id = match self.get_master().get_header_by_number(number)? {
+            Some(header) => header.id(),
+            None => return Ok(None),
+        };
+        let anchor_block_id = self.get_master().current_header().id();
+        let block_accumulator_info = self
+            .storage
+            .get_block_info(anchor_block_id)?
+            .ok_or_else(|| format_err!("Can not find block info {:?}.", anchor_block_id))?
+            .get_block_accumulator_info()
+            .clone();
+        let accumulator_root = *block_accumulator_info.get_accumulator_root();
+        let accumulator = info_2_accumulator(
+            block_accumulator_info,
+            AccumulatorStoreType::Block,
+            self.storage.clone(),
+        )?;
+        let proof = accumulator.get_proof(number)?.ok_or_else(|| {
+            format_err!("Can not get proof of block accumulator leaf {}.", number)
+        })?;
+        Ok(Some(BlockProof {
+            block_id,
+            number,
+            anchor_block_id,
+            accumulator_root,
+            proof,
+        }))
+    }
+
     fn master_head_header(&self) -> BlockHeader {
         self.get_master().current_header()
     }
@@ -315,23 +613,103 @@ where
         self.get_master().get_blocks_by_number(number, count)
     }
 
+    fn master_events(&self, filter: Filter) -> Result<Vec<ContractEventInfo>> {
+        self.get_master().get_events(&filter)
+    }
+
+    fn master_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>> {
+        self.storage.get_account_transactions(address, start, limit)
+    }
+
+    fn rewind(&mut self, block_id: HashValue) -> Result<BlockHeader> {
+        let master_header = self.get_master().current_header();
+        let header = self
+            .storage
+            .get_block_header_by_hash(block_id)?
+            .ok_or_else(|| format_err!("Can not find block header {:?}.", block_id))?;
+        let on_master =
+            self.get_master().get_header_by_number(header.number())? == Some(header.clone());
+        ensure!(
+            header.number() < master_header.number() && on_master,
+            "Block {:?}({}) is not an ancestor of master head {:?}({}).",
+            block_id,
+            header.number(),
+            master_header.id(),
+            master_header.number()
+        );
+        ensure!(
+            is_block_complete(self.storage.as_ref(), block_id)?,
+            "Data of block {:?} is incomplete, can not rewind to it.",
+            block_id
+        );
+        // the state of blocks before the pruned number is incomplete, or is being pruned.
+        let pruned = match &self.pruner {
+            Some(pruner) => pruner.pruned_number()?,
+            None => self.storage.get_state_prune_progress()?,
+        };
+        if let Some(pruned) = pruned {
+            ensure!(
+                header.number() >= pruned,
+                "State of block {:?}({}) is pruned, can not rewind before block {}.",
+                block_id,
+                header.number(),
+                pruned
+            );
+        }
+        let retracted = self.find_blocks_until(master_header.id(), block_id)?;
+        // rebuild the accumulators and the state of the new head.
+        let new_master = BlockChain::new(self.config.clone(), block_id, self.storage.clone())?;
+        let total_difficulty = new_master.get_total_difficulty()?;
+        let mut startup_info = self.startup_info.clone();
+        startup_info.master = block_id;
+        startup_info.insert_branch(&master_header);
+        self.commit_master(&[], &retracted, startup_info.clone())?;
+        self.update_master(new_master, startup_info);
+        warn!(
+            "Rewind master from {:?}({}) to {:?}({}).",
+            master_header.id(),
+            master_header.number(),
+            block_id,
+            header.number()
+        );
+        self.broadcast_reorg_2_bus(vec![], retracted.clone());
+        self.commit_2_txpool(vec![], retracted);
+        self.broadcast_2_bus(BlockDetail::new(
+            self.get_master().head_block(),
+            total_difficulty,
+        ));
+        Ok(header)
+    }
+
     fn create_block_template(
         &self,
         author: AccountAddress,
         auth_key_prefix: Option<Vec<u8>>,
         parent_hash: Option<HashValue>,
         user_txns: Vec<SignedUserTransaction>,
+        block_timestamp: Option<u64>,
     ) -> Result<BlockTemplate> {
         let block_id = match parent_hash {
             Some(hash) => hash,
             None => self.get_master().current_header().id(),
         };
 
-        if let Ok(Some(_)) = self.get_block_by_hash(block_id) {
+        if let Ok(Some(block)) = self.get_block_by_hash(block_id) {
             //TODO ensure is need create a new chain?
             let block_chain = self.get_master().new_chain(block_id)?;
             block_chain
-                .create_block_template(author, auth_key_prefix, Some(block_id), user_txns)
+                .create_block_template_inner(
+                    author,
+                    auth_key_prefix,
+                    block.header().clone(),
+                    user_txns,
+                    block_timestamp,
+                )
                 .map(|t| t.0)
         } else {
             Err(format_err!("Block {:?} not exist.", block_id))
@@ -3,15 +3,17 @@
 
 mod chain;
 
-pub use chain::BlockChain;
+pub use chain::{BlockChain, MAX_EVENT_QUERY_BLOCKS, MAX_EVENT_QUERY_LIMIT};
 
 mod chain_metrics;
 pub mod chain_service;
 pub mod message;
 pub mod mock;
+pub mod pruner;
 pub mod test_helper;
 
 pub use chain_service::ChainServiceImpl;
+pub use pruner::StatePruner;
 
 use crate::message::ChainResponse;
 use actix::prelude::*;
@@ -27,7 +29,11 @@ use traits::{ChainAsyncService, ChainService, ConnectResult, Consensus};
 use txpool::TxPoolService;
 use types::{
     account_address::AccountAddress,
+    account_transaction::AccountTransactionInfo,
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate},
+    contract_event::ContractEventInfo,
+    filter::Filter,
+    proof::{BlockProof, TransactionProof},
     startup_info::{ChainInfo, StartupInfo},
     system_events::MinedBlock,
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
@@ -115,14 +121,21 @@ where
                         })?,
                 ))))
             }
-            ChainRequest::CreateBlockTemplate(author, auth_key_prefix, parent_hash, txs) => Ok(
-                ChainResponse::BlockTemplate(Box::new(self.service.create_block_template(
+            ChainRequest::CreateBlockTemplate(
+                author,
+                auth_key_prefix,
+                parent_hash,
+                txs,
+                block_timestamp,
+            ) => Ok(ChainResponse::BlockTemplate(Box::new(
+                self.service.create_block_template(
                     author,
                     auth_key_prefix,
                     parent_hash,
                     txs,
-                )?)),
-            ),
+                    block_timestamp,
+                )?,
+            ))),
             ChainRequest::GetBlockByHash(hash) => Ok(ChainResponse::OptionBlock(
                 if let Some(block) = self.service.get_block_by_hash(hash)? {
                     Some(Box::new(block))
@@ -176,6 +189,29 @@ where
                         .get_txn_info_by_block_and_index(block_id, txn_idx)?,
                 ))
             }
+            ChainRequest::GetEvents(filter) => {
+                Ok(ChainResponse::Events(self.service.master_events(filter)?))
+            }
+            ChainRequest::GetTransactionsByAccount {
+                address,
+                start,
+                limit,
+            } => Ok(ChainResponse::AccountTransactions(
+                self.service
+                    .master_transactions_by_account(address, start, limit)?,
+            )),
+            ChainRequest::Rewind(block_id) => Ok(ChainResponse::BlockHeader(Box::new(Some(
+                self.service.rewind(block_id)?,
+            )))),
+            ChainRequest::GetTransactionProof {
+                block_id,
+                txn_index,
+            } => Ok(ChainResponse::TransactionProof(Box::new(
+                self.service.get_transaction_proof(block_id, txn_index)?,
+            ))),
+            ChainRequest::GetBlockProof(number) => Ok(ChainResponse::BlockProof(Box::new(
+                self.service.master_block_proof(number)?,
+            ))),
         }
     }
 }
@@ -445,12 +481,94 @@ where
         }
     }
 
+    async fn master_events(self, filter: Filter) -> Result<Vec<ContractEventInfo>> {
+        let response = self
+            .address
+            .send(ChainRequest::GetEvents(filter))
+            .await
+            .map_err(Into::<Error>::into)??;
+        if let ChainResponse::Events(events) = response {
+            Ok(events)
+        } else {
+            bail!("get chain events error.")
+        }
+    }
+
+    async fn master_transactions_by_account(
+        self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>> {
+        let response = self
+            .address
+            .send(ChainRequest::GetTransactionsByAccount {
+                address,
+                start,
+                limit,
+            })
+            .await
+            .map_err(Into::<Error>::into)??;
+        if let ChainResponse::AccountTransactions(txns) = response {
+            Ok(txns)
+        } else {
+            bail!("get account transactions error.")
+        }
+    }
+
+    async fn get_transaction_proof(
+        self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> Result<Option<TransactionProof>> {
+        let response = self
+            .address
+            .send(ChainRequest::GetTransactionProof {
+                block_id,
+                txn_index,
+            })
+            .await
+            .map_err(Into::<Error>::into)??;
+        if let ChainResponse::TransactionProof(proof) = response {
+            Ok(*proof)
+        } else {
+            bail!("get transaction proof error.")
+        }
+    }
+
+    async fn master_block_proof(self, number: BlockNumber) -> Result<Option<BlockProof>> {
+        let response = self
+            .address
+            .send(ChainRequest::GetBlockProof(number))
+            .await
+            .map_err(Into::<Error>::into)??;
+        if let ChainResponse::BlockProof(proof) = response {
+            Ok(*proof)
+        } else {
+            bail!("get block proof error.")
+        }
+    }
+
+    async fn rewind(self, block_id: HashValue) -> Result<BlockHeader> {
+        let response = self
+            .address
+            .send(ChainRequest::Rewind(block_id))
+            .await
+            .map_err(Into::<Error>::into)??;
+        if let ChainResponse::BlockHeader(header) = response {
+            header.ok_or_else(|| format_err!("rewind response header is none."))
+        } else {
+            bail!("rewind chain error.")
+        }
+    }
+
     async fn create_block_template(
         self,
         author: AccountAddress,
         auth_key_prefix: Option<Vec<u8>>,
         parent_hash: Option<HashValue>,
         txs: Vec<SignedUserTransaction>,
+        block_timestamp: Option<u64>,
     ) -> Result<Option<BlockTemplate>> {
         let address = self.address.clone();
         drop(self);
@@ -460,6 +578,7 @@ where
                 auth_key_prefix,
                 parent_hash,
                 txs,
+                block_timestamp,
             ))
             .await??
         {
@@ -5,7 +5,11 @@ use crypto::HashValue;
 use traits::ConnectResult;
 use types::{
     account_address::AccountAddress,
+    account_transaction::AccountTransactionInfo,
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate},
+    contract_event::ContractEventInfo,
+    filter::Filter,
+    proof::{BlockProof, TransactionProof},
     startup_info::{ChainInfo, StartupInfo},
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
 };
@@ -22,6 +26,7 @@ pub enum ChainRequest {
         Option<Vec<u8>>,
         Option<HashValue>,
         Vec<SignedUserTransaction>,
+        Option<u64>,
     ),
     // just fot test
     GetBlockByHash(HashValue),
@@ -37,6 +42,18 @@ pub enum ChainRequest {
     },
     GetBlocksByNumber(Option<BlockNumber>, u64),
     GetBlockStateByHash(HashValue),
+    GetEvents(Filter),
+    GetTransactionsByAccount {
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    },
+    Rewind(HashValue),
+    GetTransactionProof {
+        block_id: HashValue,
+        txn_index: u64,
+    },
+    GetBlockProof(BlockNumber),
 }
 
 impl Message for ChainRequest {
@@ -59,4 +76,8 @@ pub enum ChainResponse {
     None,
     Conn(ConnectResult<()>),
     BlockState(Option<Box<BlockState>>),
+    Events(Vec<ContractEventInfo>),
+    AccountTransactions(Vec<AccountTransactionInfo>),
+    TransactionProof(Box<Option<TransactionProof>>),
+    BlockProof(Box<Option<BlockProof>>),
 }
@@ -6,7 +6,11 @@ use anyhow::{Error, Result};
 use crypto::HashValue;
 use types::{
     account_address::AccountAddress,
+    account_transaction::AccountTransactionInfo,
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate},
+    contract_event::ContractEventInfo,
+    filter::Filter,
+    proof::{BlockProof, TransactionProof},
     startup_info::{ChainInfo, StartupInfo},
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
 };
@@ -93,12 +97,42 @@ impl ChainAsyncService for MockChainService {
         unimplemented!()
     }
 
+    async fn master_events(self, _filter: Filter) -> Result<Vec<ContractEventInfo>> {
+        unimplemented!()
+    }
+
+    async fn master_transactions_by_account(
+        self,
+        _address: AccountAddress,
+        _start: Option<u64>,
+        _limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>> {
+        unimplemented!()
+    }
+
+    async fn rewind(self, _block_id: HashValue) -> Result<BlockHeader> {
+        unimplemented!()
+    }
+
+    async fn get_transaction_proof(
+        self,
+        _block_id: HashValue,
+        _txn_index: u64,
+    ) -> Result<Option<TransactionProof>> {
+        unimplemented!()
+    }
+
+    async fn master_block_proof(self, _number: BlockNumber) -> Result<Option<BlockProof>> {
+        unimplemented!()
+    }
+
     async fn create_block_template(
         self,
         _author: AccountAddress,
         _auth_key_prefix: Option<Vec<u8>>,
         _parent_hash: Option<HashValue>,
         _txs: Vec<SignedUserTransaction>,
+        _block_timestamp: Option<u64>,
     ) -> Result<Option<BlockTemplate>> {
         unimplemented!()
     }
@@ -0,0 +1,153 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::BlockChain;
+use anyhow::Result;
+use crypto::HashValue;
+use logger::prelude::*;
+use parking_lot::Mutex;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use storage::{AtomicWriteStore, Store};
+use traits::{ChainReader, Consensus};
+use types::block::{BlockHeader, BlockNumber};
+
+/// Max blocks scheduled to prune on one master head, so that a node which turns off archive
+/// mode catches up gradually instead of flooding the prune thread.
+pub const MAX_PRUNE_BLOCKS_PER_HEAD: u64 = 16;
+
+/// Delete state nodes which are only reachable from state roots older than
+/// `state_prune_window` blocks behind master head.
+/// The chain service schedules master blocks out of the window on every new head, and a
+/// background thread prunes them, each block in one atomic write of the storage, so pruning
+/// never interleaves with the reference counting of state nodes written by block execution.
+/// Side blocks forked from a pruned block can never be master, because the window is not less
+/// than the max reorg depth, so the pruner deletes the state written by them too.
+pub struct StatePruner<S>
+where
+    S: Store + 'static,
+{
+    window: u64,
+    storage: Arc<S>,
+    /// the max block number scheduled to the prune thread.
+    scheduled: Option<BlockNumber>,
+    sender: Mutex<Sender<Vec<BlockHeader>>>,
+}
+
+impl<S> StatePruner<S>
+where
+    S: Store + 'static,
+{
+    /// Create the pruner and start the prune thread, the thread exits when the pruner drops.
+    pub fn new(window: u64, storage: Arc<S>) -> Result<Self> {
+        let scheduled = storage.get_state_prune_progress()?;
+        let (sender, receiver) = channel();
+        let thread_storage = storage.clone();
+        thread::Builder::new()
+            .name("state-pruner".to_string())
+            .spawn(move || prune_loop(thread_storage, receiver))?;
+        Ok(Self {
+            window,
+            storage,
+            scheduled,
+            sender: Mutex::new(sender),
+        })
+    }
+
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// The state of blocks before this number may be pruned, or is being pruned.
+    pub fn pruned_number(&self) -> Result<Option<BlockNumber>> {
+        let progress = self.storage.get_state_prune_progress()?;
+        Ok(std::cmp::max(progress, self.scheduled))
+    }
+
+    /// Schedule master blocks out of the window to the prune thread, at most
+    /// `MAX_PRUNE_BLOCKS_PER_HEAD` blocks each call.
+    pub fn schedule<C>(&mut self, master: &BlockChain<C, S>) -> Result<()>
+    where
+        C: Consensus,
+    {
+        let head_number = master.current_header().number();
+        if head_number <= self.window {
+            return Ok(());
+        }
+        let prune_to = head_number - self.window;
+        // the genesis block has no stale state node.
+        let prune_from = match self.scheduled {
+            Some(number) => number + 1,
+            None => 1,
+        };
+        if prune_from > prune_to {
+            return Ok(());
+        }
+        let prune_to = std::cmp::min(prune_to, prune_from + MAX_PRUNE_BLOCKS_PER_HEAD - 1);
+        let mut headers = vec![];
+        for number in prune_from..=prune_to {
+            match master.get_header_by_number(number)? {
+                Some(header) => headers.push(header),
+                None => break,
+            }
+        }
+        if let Some(header) = headers.last() {
+            let number = header.number();
+            self.sender.lock().send(headers)?;
+            self.scheduled = Some(number);
+        }
+        Ok(())
+    }
+}
+
+fn prune_loop<S>(storage: Arc<S>, receiver: Receiver<Vec<BlockHeader>>)
+where
+    S: Store,
+{
+    for headers in receiver {
+        for header in headers {
+            match prune_block(storage.as_ref(), &header) {
+                Ok(pruned) => debug!(
+                    "Prune state of block {:?}({}), {} nodes deleted.",
+                    header.id(),
+                    header.number(),
+                    pruned
+                ),
+                Err(e) => {
+                    // the progress is not saved, the block is pruned again after restart.
+                    error!(
+                        "Prune state of block {:?}({}) error: {:?}",
+                        header.id(),
+                        header.number(),
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+    }
+    info!("State pruner exit.");
+}
+
+/// Prune the stale state nodes of master block `header`, and the state nodes written by the
+/// side blocks forked from its parent, and their descendants.
+fn prune_block<S>(storage: &S, header: &BlockHeader) -> Result<u64>
+where
+    S: Store,
+{
+    storage.atomic_write(|storage| {
+        let mut pruned = storage.prune_stale_state_nodes(header.id())?;
+        let mut side_blocks: Vec<HashValue> = storage
+            .get_block_sons(header.parent_hash())?
+            .into_iter()
+            .filter(|block_id| *block_id != header.id())
+            .collect();
+        while let Some(block_id) = side_blocks.pop() {
+            pruned += storage.prune_side_state_nodes(block_id)?;
+            side_blocks.extend(storage.get_block_sons(block_id)?);
+        }
+        storage.save_state_prune_progress(header.number())?;
+        Ok(pruned)
+    })
+}
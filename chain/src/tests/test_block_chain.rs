@@ -1,16 +1,40 @@
-use crate::{test_helper, BlockChain, ChainActor, ChainActorRef, ChainAsyncService};
+use crate::{
+    test_helper, BlockChain, ChainActor, ChainActorRef, ChainAsyncService, ChainServiceImpl,
+    MAX_EVENT_QUERY_LIMIT,
+};
+use actix::Addr;
 use anyhow::Result;
-use bus::BusActor;
+use bus::{Bus, BusActor};
 use config::NodeConfig;
 use consensus::dev::{DevConsensus, DummyHeader};
+use crypto::HashValue;
+use futures::StreamExt;
 use futures_timer::Delay;
 use logger::prelude::*;
 use starcoin_genesis::Genesis;
+use starcoin_time_service::MockTimeService;
 use starcoin_wallet_api::WalletAccount;
 use std::{sync::Arc, time::Duration};
-use storage::{cache_storage::CacheStorage, storage::StorageInstance, Storage};
-use traits::{ChainReader, ChainWriter, Consensus};
-use txpool::TxPool;
+use storage::{
+    account_transaction::{block_account_transactions, AccountTransactionStore},
+    cache_storage::CacheStorage,
+    contract_event::ContractEventStore,
+    state_node::StaleStateNodeStore,
+    storage::StorageInstance,
+    Storage,
+};
+use traits::{
+    BlockVerifyError, ChainReader, ChainService, ChainWriter, ConnectBlockError, Consensus,
+};
+use txpool::{TxPool, TxPoolService};
+use types::account_address::AccountAddress;
+use types::account_config;
+use types::account_transaction::AccountTransactionKind;
+use types::block::Block;
+use types::contract_event::ContractEventInfo;
+use types::filter::Filter;
+use types::system_events::ChainReorganized;
+use types::transaction::SignedUserTransaction;
 
 async fn gen_master_chain(
     times: u64,
@@ -27,6 +51,7 @@ async fn gen_master_chain(
         let best_block_id = *startup_info.get_master();
         TxPool::start(
             node_config.tx_pool.clone(),
+            node_config.time_service(),
             storage.clone(),
             best_block_id,
             bus.clone(),
@@ -58,6 +83,7 @@ async fn gen_master_chain(
                     Some(miner_account.get_auth_key().prefix().to_vec()),
                     None,
                     Vec::new(),
+                    None,
                 )
                 .await
                 .unwrap()
@@ -104,6 +130,7 @@ async fn test_block_chain_forks() {
                     Some(miner_account.get_auth_key().prefix().to_vec()),
                     Some(parent_hash),
                     Vec::new(),
+                    None,
                 )
                 .await
                 .unwrap()
@@ -155,3 +182,505 @@ async fn test_chain_apply() -> Result<()> {
     // assert_ne!(header.state_root(), header1.state_root());
     Ok(())
 }
+
+#[stest::test]
+async fn test_chain_get_events() -> Result<()> {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let mut block_chain = test_helper::gen_blockchain_for_test::<DevConsensus>(config.clone())?;
+    let miner_account = WalletAccount::random();
+    for _i in 0..3 {
+        let (block_template, _) = block_chain.create_block_template(
+            *miner_account.address(),
+            Some(miner_account.get_auth_key().prefix().to_vec()),
+            None,
+            vec![],
+        )?;
+        let block = DevConsensus::create_block(config.clone(), &block_chain, block_template)?;
+        block_chain.apply(block)?;
+    }
+    let filter = Filter {
+        from_block: 0,
+        to_block: std::u64::MAX,
+        event_keys: vec![],
+        limit: None,
+    };
+    let events = block_chain.get_events(&filter)?;
+    let mut last_number = 0;
+    for event in &events {
+        assert!(event.block_number >= last_number);
+        assert!(event.block_number <= 3);
+        last_number = event.block_number;
+    }
+
+    let limited_events = block_chain.get_events(&Filter {
+        limit: Some(1),
+        ..filter.clone()
+    })?;
+    assert_eq!(
+        limited_events,
+        events.last().cloned().into_iter().collect::<Vec<_>>()
+    );
+
+    let event_key = *events.first().expect("chain should has events").event.key();
+    let key_events = block_chain.get_events(&Filter {
+        event_keys: vec![event_key],
+        ..filter.clone()
+    })?;
+    assert!(!key_events.is_empty());
+    assert_eq!(
+        key_events,
+        events
+            .iter()
+            .filter(|e| *e.event.key() == event_key)
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+
+    assert!(block_chain
+        .get_events(&Filter {
+            limit: Some(MAX_EVENT_QUERY_LIMIT + 1),
+            ..filter
+        })
+        .is_err());
+    Ok(())
+}
+
+fn gen_chain_service(
+    bus: Addr<BusActor>,
+) -> Result<ChainServiceImpl<DevConsensus, Storage, TxPoolService>> {
+    gen_chain_service_with_config(Arc::new(NodeConfig::random_for_test()), bus)
+}
+
+fn gen_chain_service_with_config(
+    config: Arc<NodeConfig>,
+    bus: Addr<BusActor>,
+) -> Result<ChainServiceImpl<DevConsensus, Storage, TxPoolService>> {
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ))?);
+    gen_chain_service_with_storage(config, storage, bus)
+}
+
+fn gen_chain_service_with_storage(
+    config: Arc<NodeConfig>,
+    storage: Arc<Storage>,
+    bus: Addr<BusActor>,
+) -> Result<ChainServiceImpl<DevConsensus, Storage, TxPoolService>> {
+    let startup_info = Genesis::load(config.net())?.execute(storage.clone())?;
+    let txpool_service = TxPool::start(
+        config.tx_pool.clone(),
+        config.time_service(),
+        storage.clone(),
+        startup_info.master,
+        bus.clone(),
+    )
+    .get_service();
+    ChainServiceImpl::new(config, startup_info, storage, txpool_service, bus)
+}
+
+fn gen_block(
+    chain: &ChainServiceImpl<DevConsensus, Storage, TxPoolService>,
+    parent_hash: HashValue,
+) -> Result<Block> {
+    gen_block_with_txns(chain, parent_hash, vec![])
+}
+
+fn gen_block_with_txns(
+    chain: &ChainServiceImpl<DevConsensus, Storage, TxPoolService>,
+    parent_hash: HashValue,
+    user_txns: Vec<SignedUserTransaction>,
+) -> Result<Block> {
+    let miner_account = WalletAccount::random();
+    Ok(chain
+        .create_block_template(
+            *miner_account.address(),
+            None,
+            Some(parent_hash),
+            user_txns,
+            None,
+        )?
+        .into_block(DummyHeader {}, 1.into()))
+}
+
+#[stest::test]
+async fn test_chain_reorganized_event() -> Result<()> {
+    let bus = BusActor::launch();
+    let mut chain = gen_chain_service(bus.clone())?;
+    let mut reorgs = bus.channel::<ChainReorganized>().await?;
+    let genesis_id = chain.master_head_header().id();
+
+    // master: genesis <- a1, fork: genesis <- b1 <- b2
+    let a1 = gen_block(&chain, genesis_id)?;
+    chain.try_connect(a1.clone())?.unwrap();
+    let b1 = gen_block(&chain, genesis_id)?;
+    chain.try_connect(b1.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), a1.id());
+    let b2 = gen_block(&chain, b1.id())?;
+    chain.try_connect(b2.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), b2.id());
+
+    let reorg = reorgs.next().await.unwrap();
+    assert_eq!(reorg.enacted.as_slice(), &[b1, b2]);
+    assert_eq!(reorg.retracted.as_slice(), &[a1]);
+    Ok(())
+}
+
+#[stest::test]
+async fn test_fork_block_events_not_indexed() -> Result<()> {
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ))?);
+    let mut chain = gen_chain_service_with_storage(
+        Arc::new(NodeConfig::random_for_test()),
+        storage.clone(),
+        BusActor::launch(),
+    )?;
+    let genesis_id = chain.master_head_header().id();
+    let receiver = WalletAccount::random();
+    let txn = executor::peer_to_peer_txn_sent_as_association(
+        *receiver.address(),
+        receiver.get_auth_key().prefix().to_vec(),
+        0,
+        10000,
+    );
+
+    // master: genesis <- a1, fork: genesis <- b1, both with the same txn.
+    let a1 = gen_block_with_txns(&chain, genesis_id, vec![txn.clone()])?;
+    chain.try_connect(a1.clone())?.unwrap();
+    let b1 = gen_block_with_txns(&chain, genesis_id, vec![txn])?;
+    chain.try_connect(b1.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), a1.id());
+
+    let txn_event = |block: &Block| -> Result<ContractEventInfo> {
+        Ok(storage
+            .get_contract_events_by_block(block.id())?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|event_info| event_info.transaction_index == 1)
+            .expect("the transfer txn should emit events"))
+    };
+    let a1_event = txn_event(&a1)?;
+    let b1_event = txn_event(&b1)?;
+    assert_eq!(a1_event.event.key(), b1_event.event.key());
+    assert_eq!(
+        a1_event.event.sequence_number(),
+        b1_event.event.sequence_number()
+    );
+
+    let events = chain.master_events(Filter {
+        from_block: 0,
+        to_block: std::u64::MAX,
+        event_keys: vec![*a1_event.event.key()],
+        limit: None,
+    })?;
+    assert!(events.contains(&a1_event));
+    assert!(events
+        .iter()
+        .all(|event_info| event_info.block_hash == a1.id()));
+    Ok(())
+}
+
+#[stest::test]
+async fn test_account_transactions_index() -> Result<()> {
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ))?);
+    let mut chain = gen_chain_service_with_storage(
+        Arc::new(NodeConfig::random_for_test()),
+        storage.clone(),
+        BusActor::launch(),
+    )?;
+    let genesis_id = chain.master_head_header().id();
+    let sender = account_config::association_address();
+    let receiver = WalletAccount::random();
+    let receiver_address = *receiver.address();
+    let transfer = |seq_num: u64| {
+        executor::peer_to_peer_txn_sent_as_association(
+            receiver_address,
+            receiver.get_auth_key().prefix().to_vec(),
+            seq_num,
+            10000,
+        )
+    };
+    let count = |address: AccountAddress| storage.get_account_transaction_count(address);
+
+    // master: genesis <- a1 <- a2, each with a transfer.
+    let a1 = gen_block_with_txns(&chain, genesis_id, vec![transfer(0)])?;
+    chain.try_connect(a1.clone())?.unwrap();
+    let a2 = gen_block_with_txns(&chain, a1.id(), vec![transfer(1)])?;
+    chain.try_connect(a2.clone())?.unwrap();
+
+    let a1_events = storage
+        .get_contract_events_by_block(a1.id())?
+        .unwrap_or_default();
+    let a1_transactions =
+        block_account_transactions(a1.id(), a1.header().number(), a1.transactions(), &a1_events);
+    let kinds_of = |address: AccountAddress| {
+        a1_transactions
+            .iter()
+            .filter(|(txn_address, txn_info)| {
+                *txn_address == address && txn_info.transaction_index == 1
+            })
+            .map(|(_, txn_info)| txn_info.kind)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(kinds_of(sender), vec![AccountTransactionKind::Sent]);
+    assert_eq!(
+        kinds_of(receiver_address),
+        vec![AccountTransactionKind::Received]
+    );
+
+    assert_eq!(count(sender)?, 2);
+    assert_eq!(count(receiver_address)?, 2);
+    let history = chain.master_transactions_by_account(receiver_address, None, 10)?;
+    assert_eq!(
+        history
+            .iter()
+            .map(|txn_info| (txn_info.index, txn_info.block_hash, txn_info.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, a2.id(), AccountTransactionKind::Received),
+            (0, a1.id(), AccountTransactionKind::Received),
+        ]
+    );
+    assert!(chain
+        .master_transactions_by_account(sender, None, 10)?
+        .iter()
+        .all(|txn_info| txn_info.kind == AccountTransactionKind::Sent));
+    // pages from newest to oldest.
+    assert_eq!(
+        chain.master_transactions_by_account(receiver_address, None, 1)?,
+        vec![history[0].clone()]
+    );
+    assert_eq!(
+        chain.master_transactions_by_account(receiver_address, Some(0), 1)?,
+        vec![history[1].clone()]
+    );
+    assert!(chain
+        .master_transactions_by_account(receiver_address, Some(5), 1)?
+        .is_empty());
+
+    // reorg to genesis <- a1 <- b2 <- b3 <- b4, b4 includes the second transfer again.
+    let b2 = gen_block(&chain, a1.id())?;
+    chain.try_connect(b2.clone())?.unwrap();
+    let b3 = gen_block(&chain, b2.id())?;
+    chain.try_connect(b3.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), b3.id());
+    assert_eq!(count(sender)?, 1);
+    assert_eq!(count(receiver_address)?, 1);
+    assert_eq!(
+        chain.master_transactions_by_account(receiver_address, None, 10)?,
+        vec![history[1].clone()]
+    );
+    let b4 = gen_block_with_txns(&chain, b3.id(), vec![transfer(1)])?;
+    chain.try_connect(b4.clone())?.unwrap();
+    assert_eq!(count(receiver_address)?, 2);
+    let newest = chain.master_transactions_by_account(receiver_address, None, 1)?;
+    assert_eq!(newest.len(), 1);
+    assert_eq!(newest[0].index, 1);
+    assert_eq!(newest[0].block_hash, b4.id());
+
+    // rewind to b3 reverts b4.
+    chain.rewind(b3.id())?;
+    assert_eq!(count(sender)?, 1);
+    assert_eq!(count(receiver_address)?, 1);
+    assert_eq!(
+        chain.master_transactions_by_account(receiver_address, None, 10)?,
+        vec![history[1].clone()]
+    );
+    Ok(())
+}
+
+#[stest::test]
+async fn test_connect_invalid_block() -> Result<()> {
+    let mut chain = gen_chain_service(BusActor::launch())?;
+    let genesis_id = chain.master_head_header().id();
+    let mut block = gen_block(&chain, genesis_id)?;
+    let expect_state_root = block.header().state_root();
+    let invalid_state_root = HashValue::random();
+    block.header.state_root = invalid_state_root;
+    match chain.try_connect(block)? {
+        Err(ConnectBlockError::VerifyFailed(verify_err)) => assert_eq!(
+            verify_err,
+            BlockVerifyError::StateRootMismatch {
+                expect: expect_state_root,
+                got: invalid_state_root,
+            }
+        ),
+        result => panic!("Unexpected connect result: {:?}", result),
+    }
+    assert_eq!(chain.master_head_header().id(), genesis_id);
+
+    // the chain still works after rejecting the invalid block.
+    let block = gen_block(&chain, genesis_id)?;
+    chain.try_connect(block.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), block.id());
+    Ok(())
+}
+
+#[stest::test]
+async fn test_block_timestamp() -> Result<()> {
+    let time_service = Arc::new(MockTimeService::new_with_real_time());
+    let mut config = NodeConfig::random_for_test();
+    config.set_time_service(time_service.clone());
+    let mut chain = gen_chain_service_with_config(Arc::new(config), BusActor::launch())?;
+    let genesis_id = chain.master_head_header().id();
+    let a1 = gen_block(&chain, genesis_id)?;
+    chain.try_connect(a1.clone())?.unwrap();
+
+    // older than the median of recent blocks.
+    let mut block = gen_block(&chain, a1.id())?;
+    block.header.timestamp = 0;
+    match chain.try_connect(block)? {
+        Err(ConnectBlockError::VerifyFailed(BlockVerifyError::TimestampTooOld { got, .. })) => {
+            assert_eq!(got, 0)
+        }
+        result => panic!("Unexpected connect result: {:?}", result),
+    }
+
+    // the template never goes below the median, even if the local clock is behind.
+    time_service.set((a1.header().timestamp() - 100) * 1000);
+    let block = gen_block(&chain, a1.id())?;
+    assert_eq!(block.header().timestamp(), a1.header().timestamp());
+    // but it is too far in the future for this clock.
+    match chain.try_connect(block.clone())? {
+        Err(ConnectBlockError::VerifyFailed(BlockVerifyError::TimestampTooNew { .. })) => {}
+        result => panic!("Unexpected connect result: {:?}", result),
+    }
+    time_service.increase(100_000);
+    chain.try_connect(block.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), block.id());
+    Ok(())
+}
+
+#[stest::test]
+async fn test_reject_too_deep_reorg() -> Result<()> {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let max = config.net().get_config().max_reorg_depth;
+    let mut chain = gen_chain_service_with_config(config, BusActor::launch())?;
+    let genesis_id = chain.master_head_header().id();
+
+    // master: genesis <- a1 <- ... <- a(max + 1)
+    let mut master = vec![genesis_id];
+    for _ in 0..=max {
+        let block = gen_block(&chain, *master.last().unwrap())?;
+        chain.try_connect(block.clone())?.unwrap();
+        master.push(block.id());
+    }
+
+    // fork from genesis retracts max + 1 blocks.
+    let b1 = gen_block(&chain, genesis_id)?;
+    match chain.try_connect(b1)? {
+        Err(ConnectBlockError::VerifyFailed(verify_err)) => assert_eq!(
+            verify_err,
+            BlockVerifyError::ReorgTooDeep {
+                depth: max + 1,
+                max
+            }
+        ),
+        result => panic!("Unexpected connect result: {:?}", result),
+    }
+
+    // fork from a1 retracts max blocks.
+    let c2 = gen_block(&chain, master[1])?;
+    chain.try_connect(c2)?.unwrap();
+    assert_eq!(chain.master_head_header().id(), *master.last().unwrap());
+    Ok(())
+}
+
+#[stest::test]
+async fn test_rewind() -> Result<()> {
+    let bus = BusActor::launch();
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ))?);
+    let mut chain = gen_chain_service_with_storage(
+        Arc::new(NodeConfig::random_for_test()),
+        storage.clone(),
+        bus.clone(),
+    )?;
+    let mut reorgs = bus.channel::<ChainReorganized>().await?;
+    let genesis_id = chain.master_head_header().id();
+
+    // master: genesis <- a1 <- a2
+    let a1 = gen_block(&chain, genesis_id)?;
+    chain.try_connect(a1.clone())?.unwrap();
+    let a2 = gen_block(&chain, a1.id())?;
+    chain.try_connect(a2.clone())?.unwrap();
+    assert!(chain.rewind(a2.id()).is_err());
+
+    let head = chain.rewind(a1.id())?;
+    assert_eq!(head.id(), a1.id());
+    assert_eq!(chain.master_head_header().id(), a1.id());
+    let startup_info = chain.master_startup_info();
+    assert_eq!(startup_info.master, a1.id());
+    assert!(startup_info.branches.contains(&a2.id()));
+    let reorg = reorgs.next().await.unwrap();
+    assert!(reorg.enacted.is_empty());
+    assert_eq!(reorg.retracted.as_slice(), &[a2]);
+
+    // the chain grows from the new head.
+    let b2 = gen_block(&chain, a1.id())?;
+    chain.try_connect(b2.clone())?.unwrap();
+    assert_eq!(chain.master_head_header().id(), b2.id());
+
+    // the state of genesis is pruned.
+    storage.save_state_prune_progress(1)?;
+    assert!(chain.rewind(genesis_id).is_err());
+    assert_eq!(chain.master_head_header().id(), b2.id());
+    chain.rewind(a1.id())?;
+    assert_eq!(chain.master_head_header().id(), a1.id());
+    Ok(())
+}
+
+#[stest::test]
+async fn test_transaction_and_block_proof() -> Result<()> {
+    let mut chain = gen_chain_service(BusActor::launch())?;
+    let genesis_id = chain.master_head_header().id();
+    let a1 = gen_block(&chain, genesis_id)?;
+    chain.try_connect(a1.clone())?.unwrap();
+    let a2 = gen_block(&chain, a1.id())?;
+    chain.try_connect(a2.clone())?.unwrap();
+
+    // the block metadata txn of a1.
+    let txn_proof = chain.get_transaction_proof(a1.id(), 0)?.unwrap();
+    assert_eq!(
+        Some(txn_proof.txn_info.clone()),
+        chain.get_txn_info_by_block_and_index(a1.id(), 0)?
+    );
+    assert_eq!(txn_proof.accumulator_root, a1.header().accumulator_root());
+    assert_eq!(txn_proof.txn_index, 0);
+    assert_eq!(txn_proof.first_txn_info, txn_proof.txn_info);
+    txn_proof.first_txn_proof.verify(
+        txn_proof.accumulator_root,
+        txn_proof.first_txn_info.id(),
+        txn_proof.leaf_index,
+    )?;
+    txn_proof.proof.verify(
+        a1.header().accumulator_root(),
+        txn_proof.txn_info.id(),
+        txn_proof.leaf_index,
+    )?;
+    assert!(txn_proof
+        .proof
+        .verify(
+            HashValue::random(),
+            txn_proof.txn_info.id(),
+            txn_proof.leaf_index
+        )
+        .is_err());
+    assert!(chain.get_transaction_proof(a1.id(), 1)?.is_none());
+
+    let block_proof = chain.master_block_proof(1)?.unwrap();
+    assert_eq!(block_proof.block_id, a1.id());
+    assert_eq!(block_proof.anchor_block_id, a2.id());
+    block_proof
+        .proof
+        .verify(block_proof.accumulator_root, a1.id(), 1)?;
+    assert!(block_proof
+        .proof
+        .verify(block_proof.accumulator_root, a2.id(), 1)
+        .is_err());
+    assert!(chain.master_block_proof(3)?.is_none());
+    Ok(())
+}
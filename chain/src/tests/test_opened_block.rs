@@ -16,7 +16,7 @@ pub fn test_open_block() -> Result<()> {
     // uncomment this to see debug message.
     // let _ = logger::init_for_test();
     let config = Arc::new(NodeConfig::random_for_test());
-    let chain = test_helper::gen_blockchain_for_test::<DevConsensus>(config)?;
+    let chain = test_helper::gen_blockchain_for_test::<DevConsensus>(config.clone())?;
     let header = chain.current_header();
     let block_gas_limit = 1_000_000; // 100w
 
@@ -25,6 +25,7 @@ pub fn test_open_block() -> Result<()> {
         OpenedBlock::new(
             chain.get_storage(),
             header,
+            config.time_service().now_secs(),
             block_gas_limit,
             miner_account.address,
             Some(miner_account.get_auth_key().prefix().to_vec()),
@@ -0,0 +1,160 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Block archive file format: the magic bytes, followed by a length-prefixed SCS encoded
+//! `BlockArchiveHeader`, followed by length-prefixed SCS encoded `Block`s in ascending order.
+//! Every length prefix is a big-endian u32.
+
+use anyhow::{bail, ensure, Result};
+use scs::SCSCodec;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+use starcoin_types::block::{Block, BlockNumber};
+use std::io::{ErrorKind, Read, Write};
+use std::mem::size_of;
+
+pub const BLOCK_ARCHIVE_MAGIC: [u8; 8] = *b"STCBLKS\0";
+pub const BLOCK_ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockArchiveHeader {
+    pub version: u32,
+    /// Name of the `ChainNetwork` which the blocks belong to.
+    pub net: String,
+    pub genesis_hash: HashValue,
+    /// Number of the first block in the archive.
+    pub from: BlockNumber,
+    /// Number of the last block in the archive.
+    pub to: BlockNumber,
+}
+
+impl BlockArchiveHeader {
+    pub fn new(net: String, genesis_hash: HashValue, from: BlockNumber, to: BlockNumber) -> Self {
+        Self {
+            version: BLOCK_ARCHIVE_VERSION,
+            net,
+            genesis_hash,
+            from,
+            to,
+        }
+    }
+}
+
+pub struct BlockArchiveWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> BlockArchiveWriter<W> {
+    pub fn new(mut writer: W, header: &BlockArchiveHeader) -> Result<Self> {
+        writer.write_all(&BLOCK_ARCHIVE_MAGIC)?;
+        write_frame(&mut writer, header.encode()?.as_slice())?;
+        Ok(Self { writer })
+    }
+
+    pub fn append(&mut self, block: &Block) -> Result<()> {
+        write_frame(&mut self.writer, block.encode()?.as_slice())
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+pub struct BlockArchiveReader<R: Read> {
+    reader: R,
+    header: BlockArchiveHeader,
+}
+
+impl<R: Read> BlockArchiveReader<R> {
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        ensure!(magic == BLOCK_ARCHIVE_MAGIC, "Invalid block archive magic.");
+        let header = match read_frame(&mut reader)? {
+            Some(data) => BlockArchiveHeader::decode(data.as_slice())?,
+            None => bail!("Block archive header is missing."),
+        };
+        ensure!(
+            header.version == BLOCK_ARCHIVE_VERSION,
+            "Unsupported block archive version {}, expected {}.",
+            header.version,
+            BLOCK_ARCHIVE_VERSION
+        );
+        Ok(Self { reader, header })
+    }
+
+    pub fn header(&self) -> &BlockArchiveHeader {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for BlockArchiveReader<R> {
+    type Item = Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match read_frame(&mut self.reader) {
+            Ok(Some(data)) => Some(Block::decode(data.as_slice())),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+fn write_frame<W: Write>(writer: &mut W, data: &[u8]) -> Result<()> {
+    ensure!(
+        data.len() <= u32::max_value() as usize,
+        "Frame of {} bytes is too large.",
+        data.len()
+    );
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(data)?;
+    Ok(())
+}
+
+/// Read a length-prefixed frame, return None at the end of the stream.
+fn read_frame<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    let mut len_bytes = [0u8; size_of::<u32>()];
+    let mut read = 0;
+    while read < len_bytes.len() {
+        match reader.read(&mut len_bytes[read..]) {
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => bail!("Block archive is truncated."),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let mut data = vec![0u8; u32::from_be_bytes(len_bytes) as usize];
+    reader.read_exact(&mut data)?;
+    Ok(Some(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_types::block::{BlockBody, BlockHeader};
+
+    #[test]
+    fn test_block_archive() -> Result<()> {
+        let header = BlockArchiveHeader::new("dev".to_string(), HashValue::random(), 1, 2);
+        let blocks = vec![
+            Block::new(BlockHeader::random(), BlockBody::default()),
+            Block::new(BlockHeader::random(), BlockBody::default()),
+        ];
+        let mut writer = BlockArchiveWriter::new(vec![], &header)?;
+        for block in &blocks {
+            writer.append(block)?;
+        }
+        let data = writer.finish()?;
+
+        let reader = BlockArchiveReader::new(data.as_slice())?;
+        assert_eq!(reader.header(), &header);
+        assert_eq!(reader.collect::<Result<Vec<_>>>()?, blocks);
+
+        let truncated = &data[..data.len() - 1];
+        let reader = BlockArchiveReader::new(truncated)?;
+        assert!(reader.collect::<Result<Vec<_>>>().is_err());
+        Ok(())
+    }
+}
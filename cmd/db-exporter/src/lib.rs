@@ -0,0 +1,240 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::archive::{BlockArchiveHeader, BlockArchiveReader, BlockArchiveWriter};
+use actix::Addr;
+use anyhow::{bail, ensure, format_err, Result};
+use starcoin_bus::BusActor;
+use starcoin_chain::ChainServiceImpl;
+use starcoin_config::{ChainNetwork, NodeConfig};
+use starcoin_genesis::Genesis;
+use starcoin_logger::prelude::*;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage, Store};
+use starcoin_traits::{ChainService, ConnectBlockError, Consensus};
+use starcoin_txpool_mock_service::MockTxPoolService;
+use starcoin_types::block::BlockNumber;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+pub mod archive;
+pub mod verify;
+
+/// Open the db storage of the node, the db can be opened in readonly mode while the node
+/// is running.
+pub fn open_storage(config: &NodeConfig, readonly: bool) -> Result<Arc<Storage>> {
+    let db = DBStorage::open_with_config(
+        config.storage.dir().join("starcoindb"),
+        readonly,
+        &config.storage,
+    )?;
+    Ok(Arc::new(Storage::new(StorageInstance::new_db_instance(
+        Arc::new(db),
+    ))?))
+}
+
+/// Export master chain blocks in [from, to] to `output`, `to` defaults to the master head.
+/// Return the number of exported blocks.
+pub fn export_blocks<W: Write>(
+    storage: &dyn Store,
+    net: ChainNetwork,
+    from: BlockNumber,
+    to: Option<BlockNumber>,
+    output: W,
+) -> Result<u64> {
+    let startup_info = storage
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Startup info is missing, the db is not initialized."))?;
+    let head = storage
+        .get_block_header_by_hash(startup_info.master)?
+        .ok_or_else(|| format_err!("Can not find master head {:?}", startup_info.master))?;
+    let to = to.unwrap_or_else(|| head.number());
+    ensure!(
+        from <= to && to <= head.number(),
+        "Invalid block range [{}, {}], the master head number is {}.",
+        from,
+        to,
+        head.number()
+    );
+
+    // the number index may point to fork blocks, so walk back from the master head.
+    let mut block_ids = Vec::with_capacity((to - from + 1) as usize);
+    let mut current = head;
+    loop {
+        if current.number() <= to {
+            block_ids.push(current.id());
+        }
+        if current.number() == from {
+            break;
+        }
+        current = storage
+            .get_block_header_by_hash(current.parent_hash())?
+            .ok_or_else(|| format_err!("Can not find block header {:?}", current.parent_hash()))?;
+    }
+    block_ids.reverse();
+    let genesis_hash = storage
+        .get_block_header_by_number(0)?
+        .ok_or_else(|| format_err!("Can not find genesis block header."))?
+        .id();
+
+    let header = BlockArchiveHeader::new(net.to_string(), genesis_hash, from, to);
+    let mut writer = BlockArchiveWriter::new(output, &header)?;
+    for block_id in &block_ids {
+        let block = storage
+            .get_block(*block_id)?
+            .ok_or_else(|| format_err!("Can not find block {:?}", block_id))?;
+        writer.append(&block)?;
+    }
+    writer.finish()?;
+    Ok(block_ids.len() as u64)
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ImportSummary {
+    /// Blocks applied to the chain.
+    pub imported: u64,
+    /// Blocks which already exist, skipped when resuming an interrupted import.
+    pub skipped: u64,
+}
+
+/// Import blocks from the archive `input`, every block is fully verified and executed by
+/// `ChainServiceImpl::try_connect`. Blocks already in the db are skipped, so an interrupted
+/// import can be resumed by importing the same archive again.
+pub fn import_blocks<C, R>(
+    config: Arc<NodeConfig>,
+    storage: Arc<Storage>,
+    bus: Addr<BusActor>,
+    input: R,
+) -> Result<ImportSummary>
+where
+    C: Consensus,
+    R: Read,
+{
+    let reader = BlockArchiveReader::new(input)?;
+    let header = reader.header().clone();
+    let net: ChainNetwork = header.net.parse()?;
+    ensure!(
+        net == config.net(),
+        "Block archive is exported from network {}, but the node is on {}.",
+        net,
+        config.net()
+    );
+    let startup_info = match storage.get_startup_info()? {
+        Some(startup_info) => {
+            let genesis_hash = storage
+                .get_block_header_by_number(0)?
+                .ok_or_else(|| format_err!("Can not find genesis block header."))?
+                .id();
+            ensure!(
+                genesis_hash == header.genesis_hash,
+                "Genesis mismatch, db: {:?}, block archive: {:?}",
+                genesis_hash,
+                header.genesis_hash
+            );
+            startup_info
+        }
+        None => {
+            let genesis = Genesis::load(net)?;
+            ensure!(
+                genesis.block().id() == header.genesis_hash,
+                "Genesis mismatch, network {}: {:?}, block archive: {:?}",
+                net,
+                genesis.block().id(),
+                header.genesis_hash
+            );
+            genesis.execute(storage.clone())?
+        }
+    };
+    info!(
+        "Import blocks [{}, {}] with startup info: {}",
+        header.from, header.to, startup_info
+    );
+
+    let mut chain = ChainServiceImpl::<C, Storage, MockTxPoolService>::new(
+        config,
+        startup_info,
+        storage,
+        MockTxPoolService::new(),
+        bus,
+    )?;
+    let mut summary = ImportSummary::default();
+    for block in reader {
+        let block = block?;
+        let (block_id, number) = (block.id(), block.header().number());
+        match chain.try_connect(block)? {
+            Ok(()) => summary.imported += 1,
+            Err(ConnectBlockError::DuplicateConn) => summary.skipped += 1,
+            Err(e) => bail!("Import block {:?}({}) failed: {}", block_id, number, e),
+        }
+        if (summary.imported + summary.skipped) % 1000 == 0 {
+            info!("Import blocks progress: {}, {:?}", number, summary);
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_consensus::dev::DevConsensus;
+    use starcoin_storage::cache_storage::CacheStorage;
+    use starcoin_types::account_config::association_address;
+
+    fn new_storage() -> Arc<Storage> {
+        Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap())
+    }
+
+    #[stest::test]
+    async fn test_export_import_blocks() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let bus = BusActor::launch();
+        let storage = new_storage();
+        let startup_info = Genesis::load(config.net())?.execute(storage.clone())?;
+        let mut chain = ChainServiceImpl::<DevConsensus, Storage, MockTxPoolService>::new(
+            config.clone(),
+            startup_info,
+            storage.clone(),
+            MockTxPoolService::new(),
+            bus.clone(),
+        )?;
+        for _ in 0..3 {
+            let template =
+                chain.create_block_template(association_address(), None, None, vec![], None)?;
+            let block = DevConsensus::create_block(config.clone(), chain.get_master(), template)?;
+            chain.try_connect(block)?.unwrap();
+        }
+        let head = chain.master_head_header();
+
+        let mut archive = vec![];
+        let count = export_blocks(storage.as_ref(), config.net(), 0, None, &mut archive)?;
+        assert_eq!(count, 4);
+
+        let target = new_storage();
+        let summary = import_blocks::<DevConsensus, _>(
+            config.clone(),
+            target.clone(),
+            bus.clone(),
+            archive.as_slice(),
+        )?;
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 3,
+                skipped: 1
+            }
+        );
+        assert_eq!(target.get_startup_info()?.unwrap().master, head.id());
+
+        // import again to resume, all blocks exist.
+        let summary = import_blocks::<DevConsensus, _>(config, target, bus, archive.as_slice())?;
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 0,
+                skipped: 4
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use starcoin_bus::BusActor;
+use starcoin_config::{ChainNetwork, NodeConfig, StarcoinOpt};
+use starcoin_consensus::{argon::ArgonConsensus, dev::DevConsensus};
+use starcoin_db_exporter::verify::DBVerifier;
+use starcoin_db_exporter::{export_blocks, import_blocks, open_storage, ImportSummary};
+use starcoin_logger::prelude::*;
+use starcoin_storage::Storage;
+use starcoin_traits::Consensus;
+use starcoin_types::block::BlockNumber;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+#[derive(Debug, Clone, StructOpt)]
+#[structopt(name = "starcoin_db_exporter", about = "offline tools of starcoin db")]
+pub struct DbExporterOpt {
+    #[structopt(long, short = "d", parse(from_os_str))]
+    /// Path to data dir of the node
+    pub data_dir: Option<PathBuf>,
+
+    #[structopt(long, short = "n")]
+    /// Chain Network
+    pub net: Option<ChainNetwork>,
+
+    #[structopt(subcommand)]
+    pub cmd: Cmd,
+}
+
+#[derive(Debug, Clone, StructOpt)]
+pub enum Cmd {
+    /// Export master chain blocks to a block archive file, can run while the node is running.
+    ExportBlocks {
+        #[structopt(long, default_value = "0")]
+        /// Number of the first block to export
+        from: BlockNumber,
+        #[structopt(long)]
+        /// Number of the last block to export, default is the master head
+        to: Option<BlockNumber>,
+        #[structopt(long, short = "o", parse(from_os_str))]
+        /// Output block archive file
+        output: PathBuf,
+    },
+    /// Import blocks from a block archive file, the node must be stopped.
+    ImportBlocks {
+        #[structopt(long, short = "i", parse(from_os_str))]
+        /// Input block archive file
+        input: PathBuf,
+    },
+    /// Verify the integrity of master chain data, can run while the node is running.
+    Verify {
+        #[structopt(long)]
+        /// Skip the check of state trees, which is the most time consuming part
+        skip_state: bool,
+    },
+}
+
+fn import<C: Consensus>(
+    config: Arc<NodeConfig>,
+    storage: Arc<Storage>,
+    input: PathBuf,
+) -> Result<ImportSummary> {
+    let input = BufReader::new(File::open(input)?);
+    let mut system = actix::System::new("starcoin-db-exporter");
+    system.block_on(async move {
+        let bus = BusActor::launch();
+        import_blocks::<C, _>(config, storage, bus, input)
+    })
+}
+
+fn run() -> Result<()> {
+    let opt = DbExporterOpt::from_args();
+    let config = Arc::new(NodeConfig::load_with_opt(&StarcoinOpt {
+        data_dir: opt.data_dir.clone(),
+        net: opt.net,
+        ..Default::default()
+    })?);
+    match opt.cmd {
+        Cmd::ExportBlocks { from, to, output } => {
+            let storage = open_storage(&config, true)?;
+            let output = BufWriter::new(File::create(&output)?);
+            let count = export_blocks(storage.as_ref(), config.net(), from, to, output)?;
+            info!("Exported {} blocks.", count);
+        }
+        Cmd::ImportBlocks { input } => {
+            let storage = open_storage(&config, false)?;
+            let summary = match config.net() {
+                ChainNetwork::Dev => import::<DevConsensus>(config, storage, input)?,
+                _ => import::<ArgonConsensus>(config, storage, input)?,
+            };
+            info!(
+                "Imported {} blocks, skipped {} existing blocks.",
+                summary.imported, summary.skipped
+            );
+        }
+        Cmd::Verify { skip_state } => {
+            let storage = open_storage(&config, true)?;
+            let report = DBVerifier::new(storage, !skip_state).verify();
+            for issue in &report.issues {
+                println!("{}", issue);
+            }
+            info!(
+                "Verified {} blocks and {} state nodes, found {} issues, {} block numbers indexed to fork blocks.",
+                report.blocks,
+                report.state_nodes,
+                report.issues.len(),
+                report.forked_numbers
+            );
+            if !report.is_ok() {
+                bail!("Db verification failed.");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let _logger_handle = starcoin_logger::init();
+    if let Err(e) = run() {
+        error!("{:?}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,505 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use forkable_jellyfish_merkle::node_type::Node;
+use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use starcoin_accumulator::node::{AccumulatorStoreType, ACCUMULATOR_PLACEHOLDER_HASH};
+use starcoin_accumulator::{Accumulator, AccumulatorReader, MerkleAccumulator};
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_state_store_api::StateNodeStore;
+use starcoin_storage::block_info::BlockInfoStore;
+use starcoin_storage::state_node::StaleStateNodeStore;
+use starcoin_storage::{
+    BlockStore, ColumnFamilyName, Storage, TransactionInfoStore, ACCUMULATOR_NODE_PREFIX_NAME,
+    BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME,
+    BLOCK_NUM_PREFIX_NAME, BLOCK_PREFIX_NAME, BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+    DEFAULT_PREFIX_NAME, STARTUP_INFO_PREFIX_NAME, STATE_NODE_PREFIX_NAME,
+    TRANSACTION_INFO_PREFIX_NAME,
+};
+use starcoin_types::account_state::AccountState;
+use starcoin_types::accumulator_info::AccumulatorInfo;
+use starcoin_types::block::{BlockInfo, BlockNumber};
+use starcoin_types::startup_info::StartupInfo;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::Arc;
+
+/// A corrupt or missing db entry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyIssue {
+    pub cf: ColumnFamilyName,
+    pub key: String,
+    pub reason: String,
+}
+
+impl fmt::Display for VerifyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.cf, self.key, self.reason)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VerifyReport {
+    /// Number of verified master blocks.
+    pub blocks: u64,
+    /// Number of verified distinct state nodes.
+    pub state_nodes: u64,
+    /// Number of block number index entries which point to a fork block of the same number.
+    /// The index is written for every saved block, so this is expected after forks.
+    pub forked_numbers: u64,
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Walk the master chain back from the master head of startup info by parent hash, then check
+/// from genesis that every block's data, accumulators and state tree are complete and consistent.
+/// The state of blocks before the state prune progress is pruned, so it is not checked.
+pub struct DBVerifier {
+    storage: Arc<Storage>,
+    verify_state: bool,
+    verified_state_nodes: HashSet<HashValue>,
+    report: VerifyReport,
+}
+
+impl DBVerifier {
+    pub fn new(storage: Arc<Storage>, verify_state: bool) -> Self {
+        Self {
+            storage,
+            verify_state,
+            verified_state_nodes: HashSet::new(),
+            report: VerifyReport::default(),
+        }
+    }
+
+    pub fn verify(mut self) -> VerifyReport {
+        let startup_info_result = self.storage.get_startup_info();
+        let startup_info = match self.check(
+            STARTUP_INFO_PREFIX_NAME,
+            STARTUP_INFO_PREFIX_NAME,
+            startup_info_result,
+        ) {
+            Some(startup_info) => startup_info,
+            None => return self.report,
+        };
+        let state_pruned_before = match self.storage.get_state_prune_progress() {
+            Ok(progress) => progress.unwrap_or(0),
+            Err(e) => {
+                self.issue(
+                    DEFAULT_PREFIX_NAME,
+                    "state_prune_progress",
+                    format!("corrupt: {}", e),
+                );
+                0
+            }
+        };
+        let mut parent: Option<(HashValue, BlockInfo)> = None;
+        for (number, block_id) in self.master_blocks(startup_info.master) {
+            self.verify_number_index(number, block_id);
+            let verify_state = self.verify_state && number >= state_pruned_before;
+            let block_info = self.verify_block(number, block_id, parent.as_ref(), verify_state);
+            parent = block_info.map(|block_info| (block_id, block_info));
+            self.report.blocks += 1;
+            if self.report.blocks % 1000 == 0 {
+                info!(
+                    "Verified {} blocks, found {} issues.",
+                    self.report.blocks,
+                    self.report.issues.len()
+                );
+            }
+        }
+        self.verify_startup_info(startup_info);
+        self.report.state_nodes = self.verified_state_nodes.len() as u64;
+        self.report
+    }
+
+    /// Numbers and ids of master blocks from genesis to `head`, walked back by parent hash.
+    /// If a header is missing, the blocks after it are returned.
+    fn master_blocks(&mut self, head: HashValue) -> Vec<(BlockNumber, HashValue)> {
+        let mut blocks = vec![];
+        let mut block_id = head;
+        loop {
+            let header = match self.storage.get_block_header_by_hash(block_id) {
+                Ok(Some(header)) => header,
+                // an unknown master head is reported by `verify_startup_info`.
+                Ok(None) if block_id == head => break,
+                result => {
+                    let key = format!("{:?}", block_id);
+                    self.check(BLOCK_HEADER_PREFIX_NAME, key, result);
+                    break;
+                }
+            };
+            blocks.push((header.number(), block_id));
+            if header.number() == 0 {
+                break;
+            }
+            block_id = header.parent_hash();
+        }
+        blocks.reverse();
+        blocks
+    }
+
+    /// Cross-check the block number index with the master block of `number`.
+    fn verify_number_index(&mut self, number: BlockNumber, block_id: HashValue) {
+        let indexed_result = self.storage.get_number(number);
+        let indexed = match self.check(BLOCK_NUM_PREFIX_NAME, number, indexed_result) {
+            Some(indexed) => indexed,
+            None => return,
+        };
+        if indexed == block_id {
+            return;
+        }
+        match self.storage.get_block_header_by_hash(indexed) {
+            Ok(Some(header)) if header.number() == number => self.report.forked_numbers += 1,
+            Ok(Some(header)) => self.issue(
+                BLOCK_NUM_PREFIX_NAME,
+                number,
+                format!(
+                    "points to block {:?} with number {}",
+                    indexed,
+                    header.number()
+                ),
+            ),
+            Ok(None) => self.issue(
+                BLOCK_NUM_PREFIX_NAME,
+                number,
+                format!("points to unknown block {:?}", indexed),
+            ),
+            Err(e) => self.issue(
+                BLOCK_HEADER_PREFIX_NAME,
+                format!("{:?}", indexed),
+                format!("corrupt: {}", e),
+            ),
+        }
+    }
+
+    fn issue<K: fmt::Display, R: Into<String>>(&mut self, cf: ColumnFamilyName, key: K, reason: R) {
+        let issue = VerifyIssue {
+            cf,
+            key: key.to_string(),
+            reason: reason.into(),
+        };
+        warn!("{}", issue);
+        self.report.issues.push(issue);
+    }
+
+    /// Report the entry if it is missing or can not be decoded.
+    fn check<T, K: fmt::Display>(
+        &mut self,
+        cf: ColumnFamilyName,
+        key: K,
+        result: Result<Option<T>>,
+    ) -> Option<T> {
+        match result {
+            Ok(Some(value)) => Some(value),
+            Ok(None) => {
+                self.issue(cf, key, "missing");
+                None
+            }
+            Err(e) => {
+                self.issue(cf, key, format!("corrupt: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Verify the block, return its block info if the block info is available.
+    fn verify_block(
+        &mut self,
+        number: BlockNumber,
+        block_id: HashValue,
+        parent: Option<&(HashValue, BlockInfo)>,
+        verify_state: bool,
+    ) -> Option<BlockInfo> {
+        let key = format!("{:?}", block_id);
+        let header_result = self.storage.get_block_header_by_hash(block_id);
+        let header = self.check(BLOCK_HEADER_PREFIX_NAME, &key, header_result);
+        let body_result = self.storage.get_body(block_id);
+        self.check(BLOCK_BODY_PREFIX_NAME, &key, body_result);
+        let block_result = self.storage.get_block(block_id);
+        self.check(BLOCK_PREFIX_NAME, &key, block_result);
+        let block_info_result = self.storage.get_block_info(block_id);
+        let block_info = self.check(BLOCK_INFO_PREFIX_NAME, &key, block_info_result);
+
+        // the genesis block does not save transaction info ids.
+        let txn_info_ids = if number > 0 {
+            match self.storage.get_block_txn_info_ids(block_id) {
+                Ok(txn_info_ids) => {
+                    for txn_info_id in &txn_info_ids {
+                        let txn_info_result = self.storage.get_transaction_info(*txn_info_id);
+                        self.check(
+                            TRANSACTION_INFO_PREFIX_NAME,
+                            format!("{:?}", txn_info_id),
+                            txn_info_result,
+                        );
+                    }
+                    Some(txn_info_ids)
+                }
+                Err(e) => {
+                    self.issue(BLOCK_TRANSACTION_INFOS_PREFIX_NAME, &key, e.to_string());
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        if let Some(header) = &header {
+            if let Some(block_info) = &block_info {
+                if header.accumulator_root()
+                    != *block_info.get_txn_accumulator_info().get_accumulator_root()
+                {
+                    self.issue(
+                        BLOCK_INFO_PREFIX_NAME,
+                        &key,
+                        "txn accumulator root does not match block header",
+                    );
+                }
+            }
+            if verify_state {
+                self.verify_state_tree(header.state_root());
+            }
+        }
+
+        if let Some(block_info) = &block_info {
+            let parent_accumulators = match parent {
+                Some((_, parent_info)) => Some((
+                    parent_info.get_block_accumulator_info().clone(),
+                    Some(parent_info.get_txn_accumulator_info()),
+                )),
+                None if number == 0 => Some((
+                    AccumulatorInfo::new(*ACCUMULATOR_PLACEHOLDER_HASH, vec![], 0, 0),
+                    None,
+                )),
+                // the parent block info is broken, which has been reported.
+                None => None,
+            };
+            if let Some((parent_block_acc, parent_txn_acc)) = parent_accumulators {
+                if let Some(header) = &header {
+                    if header.parent_block_accumulator_root()
+                        != *parent_block_acc.get_accumulator_root()
+                    {
+                        self.issue(
+                            BLOCK_HEADER_PREFIX_NAME,
+                            &key,
+                            "parent block accumulator root does not match parent block info",
+                        );
+                    }
+                }
+                self.verify_accumulator(
+                    &key,
+                    AccumulatorStoreType::Block,
+                    parent_block_acc,
+                    &[block_id],
+                    block_info.get_block_accumulator_info(),
+                );
+                if let (Some(parent_txn_acc), Some(txn_info_ids)) = (parent_txn_acc, txn_info_ids) {
+                    self.verify_accumulator(
+                        &key,
+                        AccumulatorStoreType::Transaction,
+                        parent_txn_acc,
+                        txn_info_ids.as_slice(),
+                        &block_info.get_txn_accumulator_info(),
+                    );
+                }
+            }
+        }
+        block_info
+    }
+
+    /// Recompute the accumulator by appending `leaves` to `parent_info` with the nodes in
+    /// `AccumulatorStorage`, and compare it with the saved `info`.
+    fn verify_accumulator(
+        &mut self,
+        key: &str,
+        store_type: AccumulatorStoreType,
+        parent_info: AccumulatorInfo,
+        leaves: &[HashValue],
+        info: &AccumulatorInfo,
+    ) {
+        let root = *info.get_accumulator_root();
+        let node_result = self.storage.get_node(store_type.clone(), root);
+        self.check(
+            ACCUMULATOR_NODE_PREFIX_NAME,
+            format!("{:?} {:?}", store_type, root),
+            node_result,
+        );
+        let recomputed = MerkleAccumulator::new(
+            *parent_info.get_accumulator_root(),
+            parent_info.get_frozen_subtree_roots().clone(),
+            parent_info.get_num_leaves(),
+            parent_info.get_num_nodes(),
+            store_type.clone(),
+            self.storage.clone(),
+        )
+        .and_then(|accumulator| accumulator.append(leaves).map(|(root, _)| root));
+        match recomputed {
+            Ok(recomputed_root) => {
+                if recomputed_root != root
+                    || info.get_num_leaves() != parent_info.get_num_leaves() + leaves.len() as u64
+                {
+                    self.issue(
+                        BLOCK_INFO_PREFIX_NAME,
+                        key,
+                        format!(
+                            "{:?} accumulator mismatch, recomputed root {:?}, saved root {:?}",
+                            store_type, recomputed_root, root
+                        ),
+                    );
+                }
+            }
+            Err(e) => self.issue(
+                ACCUMULATOR_NODE_PREFIX_NAME,
+                format!("{:?} {:?}", store_type, parent_info.get_accumulator_root()),
+                format!("can not recompute accumulator of block {}: {}", key, e),
+            ),
+        }
+    }
+
+    /// Check all nodes of the state tree and the account storage trees are available.
+    /// The nodes are content addressed, so nodes shared with verified trees are skipped.
+    fn verify_state_tree(&mut self, state_root: HashValue) {
+        let mut pending = vec![(state_root, true)];
+        while let Some((hash, is_global)) = pending.pop() {
+            if hash == *SPARSE_MERKLE_PLACEHOLDER_HASH || !self.verified_state_nodes.insert(hash) {
+                continue;
+            }
+            let key = format!("{:?}", hash);
+            let node_result = self.storage.get(&hash);
+            let node = match self.check(STATE_NODE_PREFIX_NAME, &key, node_result) {
+                Some(node) => node,
+                None => continue,
+            };
+            if node.inner().hash() != hash {
+                self.issue(STATE_NODE_PREFIX_NAME, &key, "node hash mismatch");
+                continue;
+            }
+            match node.inner() {
+                Node::Internal(internal) => pending.extend(
+                    internal
+                        .all_child()
+                        .into_iter()
+                        .map(|child| (child, is_global)),
+                ),
+                Node::Leaf(leaf) if is_global => {
+                    match AccountState::try_from(leaf.blob().as_ref()) {
+                        Ok(account_state) => pending.extend(
+                            account_state
+                                .storage_roots()
+                                .iter()
+                                .filter_map(|root| root.map(|root| (root, false))),
+                        ),
+                        Err(e) => self.issue(
+                            STATE_NODE_PREFIX_NAME,
+                            &key,
+                            format!("invalid account state: {}", e),
+                        ),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn verify_startup_info(&mut self, startup_info: StartupInfo) {
+        let key = STARTUP_INFO_PREFIX_NAME;
+        let heads = std::iter::once(startup_info.master).chain(startup_info.branches);
+        for head in heads {
+            match self.storage.get_block_info(head) {
+                Ok(Some(_)) => {}
+                Ok(None) => self.issue(
+                    STARTUP_INFO_PREFIX_NAME,
+                    key,
+                    format!("points to unknown head {:?}", head),
+                ),
+                Err(e) => self.issue(
+                    BLOCK_INFO_PREFIX_NAME,
+                    format!("{:?}", head),
+                    format!("corrupt: {}", e),
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_bus::BusActor;
+    use starcoin_chain::ChainServiceImpl;
+    use starcoin_config::NodeConfig;
+    use starcoin_consensus::dev::{DevConsensus, DummyHeader};
+    use starcoin_genesis::Genesis;
+    use starcoin_storage::cache_storage::CacheStorage;
+    use starcoin_storage::storage::StorageInstance;
+    use starcoin_traits::{ChainReader, ChainService, Consensus};
+    use starcoin_txpool_mock_service::MockTxPoolService;
+    use starcoin_types::account_address::AccountAddress;
+    use starcoin_types::account_config::association_address;
+
+    #[stest::test]
+    async fn test_verify_db() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+            CacheStorage::new(),
+        ))?);
+        let startup_info = Genesis::load(config.net())?.execute(storage.clone())?;
+        let mut chain = ChainServiceImpl::<DevConsensus, Storage, MockTxPoolService>::new(
+            config.clone(),
+            startup_info,
+            storage.clone(),
+            MockTxPoolService::new(),
+            BusActor::launch(),
+        )?;
+        for _ in 0..3 {
+            let template =
+                chain.create_block_template(association_address(), None, None, vec![], None)?;
+            let block = DevConsensus::create_block(config.clone(), chain.get_master(), template)?;
+            chain.try_connect(block)?.unwrap();
+        }
+
+        let report = DBVerifier::new(storage.clone(), true).verify();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.blocks, 4);
+        assert!(report.state_nodes > 0);
+        assert_eq!(report.forked_numbers, 0);
+
+        // a fork block overwrites the number index, which is not an issue of master.
+        let genesis_id = chain.get_master().get_header_by_number(0)?.unwrap().id();
+        let fork_block = chain
+            .create_block_template(
+                AccountAddress::random(),
+                None,
+                Some(genesis_id),
+                vec![],
+                None,
+            )?
+            .into_block(DummyHeader {}, 1.into());
+        chain.try_connect(fork_block.clone())?.unwrap();
+        assert_eq!(storage.get_number(1)?, Some(fork_block.id()));
+        let report = DBVerifier::new(storage.clone(), false).verify();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.blocks, 4);
+        assert_eq!(report.forked_numbers, 1);
+
+        // the state of blocks before the prune progress is not verified.
+        storage.save_state_prune_progress(4)?;
+        let report = DBVerifier::new(storage.clone(), true).verify();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.state_nodes, 0);
+
+        let unknown_head = HashValue::random();
+        storage.save_startup_info(StartupInfo::new(unknown_head, vec![]))?;
+        let report = DBVerifier::new(storage, false).verify();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].cf, STARTUP_INFO_PREFIX_NAME);
+        Ok(())
+    }
+}
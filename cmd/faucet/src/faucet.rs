@@ -1,5 +1,7 @@
 use anyhow::{format_err, Result};
 
+use starcoin_executor::TXN_RESERVED;
+use starcoin_rpc_api::txpool::DryRunTransaction;
 use starcoin_rpc_client::{RemoteStateReader, RpcClient};
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_address::AccountAddress;
@@ -10,9 +12,6 @@ pub struct Faucet {
     faucet_account: WalletAccount,
 }
 
-const DEFAULT_GAS_PRICE: u64 = 1;
-const MAX_GAS: u64 = 50_000_000;
-
 impl Faucet {
     pub fn new(client: RpcClient, faucet_account: WalletAccount) -> Self {
         Faucet {
@@ -38,15 +37,23 @@ impl Faucet {
                 )
             })?;
 
-        let raw_tx = starcoin_executor::build_transfer_txn(
-            self.faucet_account.address,
-            receiver,
-            auth_key,
-            account_resource.sequence_number(),
-            amount,
-            DEFAULT_GAS_PRICE,
-            MAX_GAS,
-        );
+        let gas_price = self.client.gas_price()?;
+        let build_txn = |max_gas| {
+            starcoin_executor::build_transfer_txn(
+                self.faucet_account.address,
+                receiver,
+                auth_key.clone(),
+                account_resource.sequence_number(),
+                amount,
+                gas_price,
+                max_gas,
+            )
+        };
+        let max_gas = self.client.estimate_gas(DryRunTransaction::Unsigned {
+            raw_txn: build_txn(TXN_RESERVED),
+            public_key: self.faucet_account.public_key.clone(),
+        })?;
+        let raw_tx = build_txn(max_gas);
         let signed_tx = self.client.wallet_sign_txn(raw_tx)?;
         let ret = self.client.submit_transaction(signed_tx)?;
         Ok(ret)
@@ -100,7 +100,8 @@ impl StratumClient {
                 .unwrap()
                 .to_string()
                 .parse()?;
-            let header = values.pop().unwrap().as_str().unwrap().as_bytes().to_vec();
+            // the job carries the hex of the pow header, which ends with the nonce placeholder.
+            let header = hex::decode(values.pop().unwrap().as_str().unwrap())?;
             return Ok((header, difficulty));
         }
         Err(anyhow::anyhow!("mining.notify with bad params"))
@@ -14,7 +14,7 @@ use starcoin_miner::{
 };
 use std::sync::Arc;
 use std::time::Duration;
-use types::block::{Block, BlockBody, BlockHeader, BlockTemplate};
+use types::block::{Block, BlockBody, BlockHeader, BlockTemplate, PowVersion};
 use types::U256;
 
 #[test]
@@ -38,7 +38,7 @@ fn test_stratum_client() {
             let block = Block::new(header, body);
             let block_template = BlockTemplate::from_block(block);
             let difficulty: U256 = 1.into();
-            MineCtx::new(block_template, difficulty)
+            MineCtx::new(block_template, difficulty, PowVersion::HeaderBlob)
         };
         let _addr = MinerClientActor::new(miner_config).start();
         miner.set_mint_job(mine_ctx);
@@ -0,0 +1,41 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_transaction::AccountTransactionInfo;
+use structopt::StructOpt;
+
+/// List latest `count` transactions sent or received by `address`, from history index `start`.
+/// if `start` is absent, start from the newest transaction.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "list-txn")]
+pub struct ListTxnOpt {
+    #[structopt(name = "address", long, short = "a")]
+    address: AccountAddress,
+    #[structopt(name = "start", long, short = "s")]
+    start: Option<u64>,
+    #[structopt(name = "count", long, short = "c", default_value = "10")]
+    count: u64,
+}
+
+pub struct ListTxnCommand;
+
+impl CommandAction for ListTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ListTxnOpt;
+    type ReturnItem = Vec<AccountTransactionInfo>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let opt = ctx.opt();
+        client.chain_get_transactions_by_account(opt.address, opt.start, opt.count)
+    }
+}
@@ -8,6 +8,7 @@ mod get_txn_by_block_cmd;
 mod get_txn_cmd;
 mod get_txn_info_cmd;
 mod list_block_cmd;
+mod list_txn_cmd;
 mod show_cmd;
 
 pub use branchs_cmd::*;
@@ -17,4 +18,5 @@ pub use get_txn_by_block_cmd::*;
 pub use get_txn_cmd::*;
 pub use get_txn_info_cmd::*;
 pub use list_block_cmd::*;
+pub use list_txn_cmd::*;
 pub use show_cmd::*;
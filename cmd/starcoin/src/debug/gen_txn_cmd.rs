@@ -7,6 +7,7 @@ use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
 use serde::{Deserialize, Serialize};
 use starcoin_executor::TXN_RESERVED;
+use starcoin_rpc_api::txpool::DryRunTransaction;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_address::AccountAddress;
@@ -107,6 +108,21 @@ impl CommandAction for GenTxnCommand {
                 )
             })?;
         let sequence_number = account_resource.sequence_number();
+        // the first txn to a receiver creates the account, so it is the most expensive one.
+        let gas_price = client.gas_price()?;
+        let (to, to_auth_key_prefix) = account_provider.as_ref()();
+        let max_gas = client.estimate_gas(DryRunTransaction::Unsigned {
+            raw_txn: starcoin_executor::build_transfer_txn(
+                sender.address,
+                to,
+                to_auth_key_prefix,
+                sequence_number,
+                opt.amount,
+                gas_price,
+                TXN_RESERVED,
+            ),
+            public_key: sender.public_key.clone(),
+        })?;
         let mut gen_result = GenerateResult::default();
         gen_result.count = opt.count;
         for i in 0..opt.count {
@@ -118,8 +134,8 @@ impl CommandAction for GenTxnCommand {
                 to_auth_key_prefix,
                 sequence_number + i as u64,
                 opt.amount,
-                1,
-                TXN_RESERVED,
+                gas_price,
+                max_gas,
             );
             gen_result.total_amount += opt.amount;
             let txn = client.wallet_sign_txn(raw_txn)?;
@@ -4,7 +4,9 @@
 mod gen_txn_cmd;
 mod log_cmd;
 mod panic_cmd;
+mod replay_block_cmd;
 
 pub use gen_txn_cmd::*;
 pub use log_cmd::*;
 pub use panic_cmd::*;
+pub use replay_block_cmd::*;
@@ -0,0 +1,77 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::HashValue;
+use starcoin_rpc_api::debug::RangeReplayReport;
+use starcoin_types::block::BlockNumber;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+#[derive(Debug, Clone, Copy)]
+pub enum BlockNumberOrHash {
+    Number(BlockNumber),
+    Hash(HashValue),
+}
+
+impl FromStr for BlockNumberOrHash {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.parse::<BlockNumber>() {
+            Ok(number) => Ok(BlockNumberOrHash::Number(number)),
+            Err(_) => Ok(BlockNumberOrHash::Hash(HashValue::from_str(s)?)),
+        }
+    }
+}
+
+/// Execute blocks again on the state of their parent blocks, and diff the result with the
+/// stored txn infos, to debug state root mismatch or check a new vm build.
+/// Only the mismatched blocks are returned.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "replay-block")]
+pub struct ReplayBlockOpt {
+    #[structopt(name = "block")]
+    /// number or hash of the block to replay.
+    block: BlockNumberOrHash,
+    #[structopt(long = "to")]
+    /// replay all the blocks from `block` to the block with number `to`, `block` must be a number.
+    to: Option<BlockNumber>,
+}
+
+pub struct ReplayBlockCommand;
+
+impl CommandAction for ReplayBlockCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ReplayBlockOpt;
+    type ReturnItem = RangeReplayReport;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        match (opt.block, opt.to) {
+            (BlockNumberOrHash::Number(from), to) => {
+                client.debug_replay_blocks(from, to.unwrap_or(from))
+            }
+            (BlockNumberOrHash::Hash(block_id), None) => {
+                let report = client.debug_replay_block(block_id)?;
+                let number = report.number;
+                Ok(RangeReplayReport {
+                    from: number,
+                    to: number,
+                    mismatched: if report.is_ok() { vec![] } else { vec![report] },
+                })
+            }
+            (BlockNumberOrHash::Hash(_), Some(_)) => {
+                bail!("The first block must be a number if `to` is present.")
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::contract::{ContractCall, ContractCallPayload, ContractCallResult};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::language_storage::ModuleId;
+use starcoin_types::transaction::{parse_transaction_argument, Script, TransactionArgument};
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::fs::OpenOptions;
+use std::io::Read;
+use structopt::StructOpt;
+
+/// Call a public function or execute a script against the state of current head block,
+/// nothing is written to the chain.
+/// The return values are not supported yet, only the status and the gas used are returned.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "call")]
+pub struct CallOpt {
+    #[structopt(
+        name = "sender",
+        short = "s",
+        long = "sender",
+        help = "sender of the call, default to the zero address"
+    )]
+    sender: Option<AccountAddress>,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    help = "can specify multi type_tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    type_tags: Vec<TypeTag>,
+
+    #[structopt(long = "arg", name = "transaction-args", help = "can specify multi arg", parse(try_from_str = parse_transaction_argument))]
+    args: Vec<TransactionArgument>,
+
+    #[structopt(
+        short = "g",
+        long = "max-gas",
+        name = "max-gas-amount",
+        default_value = "1000000",
+        help = "max gas used by the call"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(
+        short = "f",
+        long = "function",
+        name = "function",
+        help = "the public function to call, in format address::module::function",
+        conflicts_with = "bytecode_file",
+        required_unless = "bytecode_file"
+    )]
+    function: Option<String>,
+
+    #[structopt(name = "bytecode_file", help = "script bytecode file path")]
+    bytecode_file: Option<String>,
+}
+
+pub struct CallCommand;
+
+impl CommandAction for CallCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = CallOpt;
+    type ReturnItem = ContractCallResult;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let payload = match (opt.function.as_ref(), opt.bytecode_file.as_ref()) {
+            (Some(function), _) => {
+                let parts: Vec<&str> = function.split("::").collect();
+                if parts.len() != 3 {
+                    bail!(
+                        "invalid function {}, should be in format address::module::function",
+                        function
+                    );
+                }
+                let address: AccountAddress = parts[0].parse()?;
+                ContractCallPayload::Function {
+                    module: ModuleId::new(address, Identifier::new(parts[1])?),
+                    function_name: parts[2].to_string(),
+                    type_args: opt.type_tags.clone(),
+                    args: opt.args.clone(),
+                }
+            }
+            (None, Some(bytecode_file)) => {
+                let mut file = OpenOptions::new()
+                    .read(true)
+                    .write(false)
+                    .open(bytecode_file.as_str())?;
+                let mut bytecode = vec![];
+                file.read_to_end(&mut bytecode)?;
+                if let Err(e) =
+                    starcoin_vm_types::file_format::CompiledScript::deserialize(bytecode.as_slice())
+                {
+                    bail!("invalid bytecode file, cannot deserialize as script, {}", e);
+                }
+                ContractCallPayload::Script(Script::new(
+                    bytecode,
+                    opt.type_tags.clone(),
+                    opt.args.clone(),
+                ))
+            }
+            (None, None) => bail!("function or bytecode_file is required"),
+        };
+        ctx.state().client().contract_call(ContractCall {
+            sender: opt.sender,
+            payload,
+            max_gas_amount: opt.max_gas_amount,
+        })
+    }
+}
@@ -8,6 +8,7 @@ use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::hash::{HashValue, PlainCryptoHash};
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
+use starcoin_time_service::{RealTimeService, TimeService};
 use starcoin_types::transaction::{Module, RawUserTransaction};
 use starcoin_vm_types::{access::ModuleAccess, file_format::CompiledModule};
 use std::fs::OpenOptions;
@@ -94,7 +95,8 @@ impl CommandAction for DeployCommand {
 
         let account_resource = account_resource.unwrap();
 
-        let expiration_time = Duration::from_secs(opt.expiration_time);
+        let expiration_time =
+            Duration::from_secs(RealTimeService::new().now_secs() + opt.expiration_time);
         let deploy_txn = RawUserTransaction::new_module(
             module_address,
             account_resource.sequence_number(),
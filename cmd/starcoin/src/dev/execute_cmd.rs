@@ -8,6 +8,7 @@ use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::hash::{HashValue, PlainCryptoHash};
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
+use starcoin_time_service::{RealTimeService, TimeService};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{
     parse_transaction_argument, RawUserTransaction, Script, TransactionArgument,
@@ -119,7 +120,8 @@ impl CommandAction for ExecuteCommand {
             bail!("address {} not exists on chain", &sender);
         }
         let account_resource = account_resource.unwrap();
-        let expiration_time = Duration::from_secs(opt.expiration_time);
+        let expiration_time =
+            Duration::from_secs(RealTimeService::new().now_secs() + opt.expiration_time);
         let script_txn = RawUserTransaction::new_script(
             sender,
             account_resource.sequence_number(),
@@ -12,6 +12,7 @@ use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
 use starcoin_crypto::ValidCryptoMaterialStringExt;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
+use starcoin_time_service::{RealTimeService, TimeService};
 use starcoin_transaction_builder::StdlibScript;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction;
@@ -165,7 +166,8 @@ impl CommandAction for GenerateMultisigTxnCommand {
             bail!("address {} not exists on chain", &sender);
         }
         let account_resource = account_resource.unwrap();
-        let expiration_time = Duration::from_secs(opt.expiration_time);
+        let expiration_time =
+            Duration::from_secs(RealTimeService::new().now_secs() + opt.expiration_time);
         let script_txn = RawUserTransaction::new_script(
             sender,
             account_resource.sequence_number(),
@@ -8,6 +8,7 @@ use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::hash::PlainCryptoHash;
 use starcoin_executor::TXN_RESERVED;
+use starcoin_rpc_api::txpool::DryRunTransaction;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::{
@@ -69,15 +70,23 @@ impl CommandAction for GetCoinCommand {
             .get_balance(&pre_mine_address)?
             .unwrap_or_else(|| panic!("pre mine address {} balance must exist", pre_mine_address));
         let amount = opt.amount.unwrap_or(balance * 20 / 100);
-        let raw_txn = starcoin_executor::build_transfer_txn(
-            pre_mine_address,
-            to.address,
-            to_auth_key_prefix.to_vec(),
-            account_resource.sequence_number(),
-            amount,
-            1,
-            TXN_RESERVED,
-        );
+        let gas_price = client.gas_price()?;
+        let build_txn = |max_gas| {
+            starcoin_executor::build_transfer_txn(
+                pre_mine_address,
+                to.address,
+                to_auth_key_prefix.to_vec(),
+                account_resource.sequence_number(),
+                amount,
+                gas_price,
+                max_gas,
+            )
+        };
+        let max_gas = client.estimate_gas(DryRunTransaction::Unsigned {
+            raw_txn: build_txn(TXN_RESERVED),
+            public_key: pre_mine_config.public_key.clone(),
+        })?;
+        let raw_txn = build_txn(max_gas);
         let txn = pre_mine_config.sign_txn(raw_txn)?;
         let succ = client.submit_transaction(txn.clone())?;
         if let Err(e) = succ {
@@ -0,0 +1,48 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_types::startup_info::ChainInfo;
+use structopt::StructOpt;
+
+/// Mine blocks with the pending txns of txpool, and return the new chain head.
+/// This command only available in dev network.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mine")]
+pub struct MineOpt {
+    #[structopt(long = "blocks", default_value = "1")]
+    /// how many blocks to mine.
+    blocks: u64,
+    #[structopt(long = "timestamp")]
+    /// timestamp(in seconds) of the new blocks, if absent, use the current time.
+    timestamp: Option<u64>,
+}
+
+pub struct MineCommand;
+
+impl CommandAction for MineCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = MineOpt;
+    type ReturnItem = ChainInfo;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let net = ctx.state().net();
+        if !net.is_dev() {
+            bail!(
+                "This command only available in dev network, current network is: {}",
+                net
+            );
+        }
+        ctx.state()
+            .client()
+            .dev_mine_blocks(opt.blocks, opt.timestamp)
+    }
+}
@@ -1,22 +1,28 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod call_cmd;
 mod compile_cmd;
 mod deploy_cmd;
 mod derive_account_address_cmd;
 mod execute_cmd;
 mod generate_multisig_txn_cmd;
 mod get_coin_cmd;
+mod mine_cmd;
+mod simulate_cmd;
 mod submit_multisig_txn_cmd;
 mod subscribe_cmd;
 mod upgrade_stdlib_cmd;
 
+pub use call_cmd::*;
 pub use compile_cmd::*;
 pub use deploy_cmd::*;
 pub use derive_account_address_cmd::*;
 pub use execute_cmd::*;
 pub use generate_multisig_txn_cmd::*;
 pub use get_coin_cmd::*;
+pub use mine_cmd::*;
+pub use simulate_cmd::*;
 pub use submit_multisig_txn_cmd::*;
 pub use subscribe_cmd::*;
 pub use upgrade_stdlib_cmd::*;
@@ -0,0 +1,126 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::txpool::{DryRunOutput, DryRunTransaction};
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_time_service::{RealTimeService, TimeService};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::{
+    parse_transaction_argument, RawUserTransaction, Script, TransactionArgument,
+};
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::time::Duration;
+use structopt::StructOpt;
+
+/// Execute a script txn against the state of current head block, without submitting it.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "simulate")]
+pub struct SimulateOpt {
+    #[structopt(name = "sender", short = "s", long = "sender")]
+    sender: Option<AccountAddress>,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    help = "can specify multi type_tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    type_tags: Vec<TypeTag>,
+
+    #[structopt(long = "arg", name = "transaction-args", help = "can specify multi arg", parse(try_from_str = parse_transaction_argument))]
+    args: Vec<TransactionArgument>,
+
+    #[structopt(
+        short = "g",
+        long = "max-gas",
+        name = "max-gas-amount",
+        default_value = "1000000",
+        help = "max gas used to execute the script"
+    )]
+    max_gas_amount: u64,
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        default_value = "1",
+        help = "gas price used to execute the script"
+    )]
+    gas_price: u64,
+    #[structopt(
+        long = "sign",
+        help = "sign the txn and check the signature, the sender account should be unlocked"
+    )]
+    sign: bool,
+
+    #[structopt(name = "bytecode_file", help = "script bytecode file path")]
+    bytecode_file: String,
+}
+
+pub struct SimulateCommand;
+
+impl CommandAction for SimulateCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SimulateOpt;
+    type ReturnItem = DryRunOutput;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let sender = if let Some(sender) = opt.sender {
+            client
+                .wallet_get(sender)?
+                .ok_or_else(|| format_err!("Account {} not exist in wallet", sender))?
+        } else {
+            ctx.state().default_account()?
+        };
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(false)
+            .open(opt.bytecode_file.as_str())?;
+        let mut bytecode = vec![];
+        file.read_to_end(&mut bytecode)?;
+        if let Err(e) =
+            starcoin_vm_types::file_format::CompiledScript::deserialize(bytecode.as_slice())
+        {
+            bail!("invalid bytecode file, cannot deserialize as script, {}", e);
+        }
+
+        let chain_state_reader = RemoteStateReader::new(client);
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        let account_resource = account_state_reader
+            .get_account_resource(&sender.address)?
+            .ok_or_else(|| format_err!("address {} not exists on chain", sender.address))?;
+        let expiration_time = Duration::from_secs(RealTimeService::new().now_secs() + 3000);
+        let script_txn = RawUserTransaction::new_script(
+            sender.address,
+            account_resource.sequence_number(),
+            Script::new(bytecode, opt.type_tags.clone(), opt.args.clone()),
+            opt.max_gas_amount,
+            opt.gas_price,
+            expiration_time,
+        );
+
+        let txn = if opt.sign {
+            DryRunTransaction::Signed(client.wallet_sign_txn(script_txn)?)
+        } else {
+            DryRunTransaction::Unsigned {
+                raw_txn: script_txn,
+                public_key: sender.public_key,
+            }
+        };
+        client.dry_run_transaction(txn)
+    }
+}
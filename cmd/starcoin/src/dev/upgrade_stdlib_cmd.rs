@@ -8,6 +8,7 @@ use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::hash::{HashValue, PlainCryptoHash};
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
+use starcoin_time_service::{RealTimeService, TimeService};
 use starcoin_transaction_builder::build_upgrade_package;
 use starcoin_types::transaction::RawUserTransaction;
 use starcoin_vm_types::transaction::TransactionPayload;
@@ -75,7 +76,8 @@ impl CommandAction for UpgradeStdlibCommand {
             let upgrade_package =
                 build_upgrade_package(cli_state.net(), StdLibOptions::Fresh, false)?;
 
-            let expiration_time = Duration::from_secs(opt.expiration_time);
+            let expiration_time =
+                Duration::from_secs(RealTimeService::new().now_secs() + opt.expiration_time);
             let upgrade_txn = RawUserTransaction::new(
                 association_account.address,
                 account_resource.sequence_number(),
@@ -102,13 +102,16 @@ fn run() -> Result<()> {
             Command::with_name("node")
                 .subcommand(node::InfoCommand)
                 .subcommand(node::PeersCommand)
-                .subcommand(node::MetricsCommand),
+                .subcommand(node::MetricsCommand)
+                .subcommand(node::StorageStatsCommand)
+                .subcommand(node::RewindCommand),
         )
         .command(
             Command::with_name("chain")
                 .subcommand(chain::ShowCommand)
                 .subcommand(chain::GetBlockByNumberCommand)
                 .subcommand(chain::ListBlockCommand)
+                .subcommand(chain::ListTxnCommand)
                 .subcommand(chain::GetTransactionCommand)
                 .subcommand(chain::GetTxnByBlockCommand)
                 .subcommand(chain::GetTransactionInfoCommand)
@@ -125,6 +128,9 @@ fn run() -> Result<()> {
                 .subcommand(dev::GenerateMultisigTxnCommand)
                 .subcommand(dev::ExecuteMultiSignedTxnCommand)
                 .subcommand(dev::UpgradeStdlibCommand)
+                .subcommand(dev::MineCommand)
+                .subcommand(dev::SimulateCommand)
+                .subcommand(dev::CallCommand)
                 .subcommand(
                     Command::with_name("subscribe")
                         .subcommand(dev::SubscribeBlockCommand)
@@ -140,7 +146,8 @@ fn run() -> Result<()> {
                         .subcommand(debug::LogPatternCommand),
                 )
                 .subcommand(debug::GenTxnCommand)
-                .subcommand(debug::PanicCommand),
+                .subcommand(debug::PanicCommand)
+                .subcommand(debug::ReplayBlockCommand),
         )
         .exec();
     Ok(())
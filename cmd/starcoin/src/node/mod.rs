@@ -4,7 +4,11 @@
 mod info_cmd;
 mod metrics_cmd;
 mod peers_cmd;
+mod rewind_cmd;
+mod storage_stats_cmd;
 
 pub use info_cmd::*;
 pub use metrics_cmd::*;
 pub use peers_cmd::*;
+pub use rewind_cmd::*;
+pub use storage_stats_cmd::*;
@@ -0,0 +1,59 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::view::BlockHeaderView;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::HashValue;
+use starcoin_types::block::BlockNumber;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+/// Rewind master chain to an ancestor block, the abandoned blocks are kept as a branch
+#[derive(Debug, StructOpt)]
+#[structopt(name = "rewind")]
+pub struct RewindOpt {
+    #[structopt(long = "to")]
+    /// Number or hash of the master block to rewind to
+    to: RewindTarget,
+}
+
+#[derive(Debug)]
+pub enum RewindTarget {
+    Number(BlockNumber),
+    Hash(HashValue),
+}
+
+impl FromStr for RewindTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<BlockNumber>() {
+            Ok(number) => Ok(RewindTarget::Number(number)),
+            Err(_) => Ok(RewindTarget::Hash(HashValue::from_str(s)?)),
+        }
+    }
+}
+
+pub struct RewindCommand;
+
+impl CommandAction for RewindCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = RewindOpt;
+    type ReturnItem = BlockHeaderView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let block_id = match ctx.opt().to {
+            RewindTarget::Number(number) => client.chain_get_block_by_number(number)?.id(),
+            RewindTarget::Hash(hash) => hash,
+        };
+        Ok(client.debug_rewind(block_id)?.into())
+    }
+}
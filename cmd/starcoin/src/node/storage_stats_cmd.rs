@@ -0,0 +1,41 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::node::StorageStats;
+use structopt::StructOpt;
+
+/// Show estimated key count, sst size and RocksDB internal stats of each column family
+#[derive(Debug, StructOpt)]
+#[structopt(name = "storage_stats")]
+pub struct StorageStatsOpt {
+    #[structopt(short = "i", long = "internal")]
+    /// Include RocksDB internal stats
+    internal: bool,
+}
+
+pub struct StorageStatsCommand;
+
+impl CommandAction for StorageStatsCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = StorageStatsOpt;
+    type ReturnItem = StorageStats;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let mut stats = client.node_storage_stats()?;
+        if !ctx.opt().internal {
+            for cf_stats in stats.column_families.iter_mut() {
+                cf_stats.internal_stats.clear();
+            }
+        }
+        Ok(stats)
+    }
+}
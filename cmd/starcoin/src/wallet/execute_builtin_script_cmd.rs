@@ -8,6 +8,7 @@ use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::hash::{HashValue, PlainCryptoHash};
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
+use starcoin_time_service::{RealTimeService, TimeService};
 use starcoin_transaction_builder::StdlibScript;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{
@@ -102,7 +103,8 @@ impl CommandAction for ExecuteBuildInCommand {
         }
 
         let account_resource = account_resource.unwrap();
-        let expiration_time = Duration::from_secs(opt.expiration_time);
+        let expiration_time =
+            Duration::from_secs(RealTimeService::new().now_secs() + opt.expiration_time);
 
         let bytecode = opt.script_name.compiled_bytes().into_vec();
         let type_tags = opt.type_tags.clone();
@@ -8,6 +8,8 @@ use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::hash::PlainCryptoHash;
 use starcoin_crypto::{ed25519::Ed25519PublicKey, ValidCryptoMaterialStringExt};
+use starcoin_executor::TXN_RESERVED;
+use starcoin_rpc_api::txpool::DryRunTransaction;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_address::AccountAddress;
@@ -36,18 +38,16 @@ pub struct TransferOpt {
         short = "g",
         long = "max-gas",
         name = "max-gas-amount",
-        default_value = "1000000",
-        help = "max gas to use"
+        help = "max gas to use, if absent, estimate it by a dry run"
     )]
-    max_gas_amount: u64,
+    max_gas_amount: Option<u64>,
     #[structopt(
         short = "p",
         long = "gas-price",
         name = "price of gas",
-        default_value = "1",
-        help = "gas price used"
+        help = "gas price used, if absent, use the gas price suggested by the node"
     )]
-    gas_price: u64,
+    gas_price: Option<u64>,
 
     #[structopt(
     short = "c",
@@ -126,16 +126,30 @@ impl CommandAction for TransferCommand {
                 )
             })?;
         let coin_type = opt.coin_type.clone().unwrap_or_else(stc_type_tag);
-        let raw_txn = starcoin_executor::build_transfer_txn_by_coin_type(
-            sender.address,
-            receiver,
-            receiver_auth_key_prefix,
-            account_resource.sequence_number(),
-            opt.amount,
-            opt.gas_price,
-            opt.max_gas_amount,
-            coin_type,
-        );
+        let gas_price = match opt.gas_price {
+            Some(gas_price) => gas_price,
+            None => client.gas_price()?,
+        };
+        let build_txn = |max_gas| {
+            starcoin_executor::build_transfer_txn_by_coin_type(
+                sender.address,
+                receiver,
+                receiver_auth_key_prefix.clone(),
+                account_resource.sequence_number(),
+                opt.amount,
+                gas_price,
+                max_gas,
+                coin_type.clone(),
+            )
+        };
+        let max_gas = match opt.max_gas_amount {
+            Some(max_gas) => max_gas,
+            None => client.estimate_gas(DryRunTransaction::Unsigned {
+                raw_txn: build_txn(TXN_RESERVED),
+                public_key: sender.public_key.clone(),
+            })?,
+        };
+        let raw_txn = build_txn(max_gas);
         let txn = client.wallet_sign_txn(raw_txn)?;
         let succ = client.submit_transaction(txn.clone())?;
         if let Err(e) = succ {
@@ -1,11 +1,13 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, format_err, Result};
 
 use starcoin_crypto::ed25519::Ed25519PublicKey;
 use starcoin_crypto::ValidCryptoMaterialStringExt;
+use starcoin_executor::TXN_RESERVED;
 use starcoin_logger::prelude::*;
+use starcoin_rpc_api::txpool::DryRunTransaction;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_rpc_client::RpcClient;
 use starcoin_state_api::AccountStateReader;
@@ -150,6 +152,8 @@ struct TxnMocker {
     account_address: AccountAddress,
     account_password: String,
     unlock_duration: Duration,
+    gas_price: u64,
+    max_gas: u64,
 
     next_sequence_number: u64,
     account_unlock_time: Option<Instant>,
@@ -171,6 +175,19 @@ impl TxnMocker {
             bail!("account {} not exists, please faucet it", account_address);
         }
         let account_resource = account_resource.unwrap();
+        let account = client
+            .wallet_get(account_address)?
+            .ok_or_else(|| format_err!("account {} not exists in wallet", account_address))?;
+        // all the mock txns are the same transfer, so estimate the gas only once.
+        let gas_price = client.gas_price()?;
+        let max_gas = client.estimate_gas(DryRunTransaction::Unsigned {
+            raw_txn: generator.generate_mock_txn(
+                account_resource.sequence_number(),
+                gas_price,
+                TXN_RESERVED,
+            )?,
+            public_key: account.public_key,
+        })?;
         let mut next_sequence_number = account_resource.sequence_number();
         // if txpool already has some future txn, use the sequence number after that.
         let seq_number_in_txpool = client.next_sequence_number_in_txpool(account_address)?;
@@ -185,6 +202,8 @@ impl TxnMocker {
             account_address,
             account_password,
             unlock_duration,
+            gas_price,
+            max_gas,
             account_unlock_time: None,
             next_sequence_number,
         })
@@ -221,9 +240,11 @@ impl TxnMocker {
         // let seq_number_in_pool = self.client.next_sequence_number_in_txpool(self.account_address)?;
         // if let Some(n) = seq_number_in_pool {
         // }
-        let raw_txn = self
-            .generator
-            .generate_mock_txn(self.next_sequence_number)?;
+        let raw_txn = self.generator.generate_mock_txn(
+            self.next_sequence_number,
+            self.gas_price,
+            self.max_gas,
+        )?;
         info!("prepare to sign txn, sender: {}", raw_txn.sender());
 
         let unlock_time = self.account_unlock_time;
@@ -25,7 +25,12 @@ impl MockTxnGenerator {
         }
     }
 
-    pub fn generate_mock_txn(&self, sequence_number: u64) -> Result<RawUserTransaction> {
+    pub fn generate_mock_txn(
+        &self,
+        sequence_number: u64,
+        gas_price: u64,
+        max_gas: u64,
+    ) -> Result<RawUserTransaction> {
         let amount_to_transfer = 1000;
 
         let transfer_txn = starcoin_executor::build_transfer_txn(
@@ -34,8 +39,8 @@ impl MockTxnGenerator {
             self.receiver_auth_key_prefix.clone(),
             sequence_number,
             amount_to_transfer,
-            1,
-            50_000_000,
+            gas_price,
+            max_gas,
         );
         Ok(transfer_txn)
     }
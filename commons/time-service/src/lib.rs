@@ -0,0 +1,90 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The clock of the node. Components read the time from a `TimeService` instead of
+//! `SystemTime::now()`, so tests can control the time with `MockTimeService`.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub trait TimeService: Send + Sync + Debug {
+    /// Milliseconds since the unix epoch.
+    fn now_millis(&self) -> u64;
+
+    /// Seconds since the unix epoch.
+    fn now_secs(&self) -> u64 {
+        self.now_millis() / 1000
+    }
+}
+
+/// The system clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealTimeService;
+
+impl RealTimeService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TimeService for RealTimeService {
+    fn now_millis(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64
+    }
+}
+
+/// A clock which only moves when it is told to, just for test.
+#[derive(Debug, Default)]
+pub struct MockTimeService {
+    now_millis: AtomicU64,
+}
+
+impl MockTimeService {
+    pub fn new() -> Self {
+        Self::new_with_value(0)
+    }
+
+    pub fn new_with_value(now_millis: u64) -> Self {
+        Self {
+            now_millis: AtomicU64::new(now_millis),
+        }
+    }
+
+    /// Start from the current system time.
+    pub fn new_with_real_time() -> Self {
+        Self::new_with_value(RealTimeService.now_millis())
+    }
+
+    pub fn set(&self, now_millis: u64) {
+        self.now_millis.store(now_millis, Ordering::SeqCst);
+    }
+
+    pub fn increase(&self, millis: u64) {
+        self.now_millis.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl TimeService for MockTimeService {
+    fn now_millis(&self) -> u64 {
+        self.now_millis.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_time_service() {
+        let time_service = MockTimeService::new_with_value(1_500);
+        assert_eq!(time_service.now_secs(), 1);
+        time_service.increase(1_000);
+        assert_eq!(time_service.now_millis(), 2_500);
+        time_service.set(10_000);
+        assert_eq!(time_service.now_secs(), 10);
+    }
+}
@@ -11,6 +11,7 @@ use starcoin_crypto::{
     ValidCryptoMaterialStringExt,
 };
 use starcoin_types::{
+    block::{BlockNumber, PowVersion},
     transaction::{
         helpers::TransactionSigner,
         {RawUserTransaction, SignedUserTransaction},
@@ -158,6 +159,36 @@ impl TransactionSigner for PreMineConfig {
     }
 }
 
+/// The algorithm to adjust the difficulty of next block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum DifficultyAlgorithm {
+    /// Average the targets of the window, scaled by the linearly weighted solve time,
+    /// the change of every block is limited to 2 times.
+    WindowedAverage,
+    /// Linearly weighted moving average, every solve time is clamped to 6 times of the block time target.
+    /// It follows the hash rate more quickly than `WindowedAverage`.
+    Lwma,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DifficultyConfig {
+    pub algorithm: DifficultyAlgorithm,
+    /// Block time target in seconds.
+    pub block_time_target: u64,
+    /// How many recent blocks are used to calculate the difficulty.
+    pub block_window: u64,
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: DifficultyAlgorithm::WindowedAverage,
+            block_time_target: 20,
+            block_window: 24,
+        }
+    }
+}
+
 /// ChainConfig is a static hard code config.
 #[derive(Debug)]
 pub struct ChainConfig {
@@ -177,6 +208,8 @@ pub struct ChainConfig {
     pub reward_delay: u64,
     /// Genesis difficulty, should match consensus in different ChainNetwork.
     pub difficulty: U256,
+    /// Difficulty adjustment of the following blocks.
+    pub difficulty_config: DifficultyConfig,
     /// Genesis consensus header.
     pub consensus_header: Vec<u8>,
     /// Pre mine to Association account config, if not preset, Not do pre mine, and association account only can be used in genesis.
@@ -185,8 +218,55 @@ pub struct ChainConfig {
     pub vm_config: VMConfig,
     /// List of initial node addresses
     pub boot_nodes: Vec<Multiaddr>,
+    /// Hard coded `(number, block_hash)` of master blocks, a branch conflicts with them is rejected.
+    pub checkpoints: Vec<(BlockNumber, HashValue)>,
+    /// Max number of master blocks a fork can retract, a branch forking deeper is rejected.
+    pub max_reorg_depth: u64,
+    /// Block number from which the proof of work commits to the mining header blob instead of the parent hash.
+    pub header_blob_pow_number: BlockNumber,
+    /// Block number from which a block must not be older than the median timestamp of recent blocks,
+    /// the blocks below it must not be older than their parent.
+    pub median_time_past_number: BlockNumber,
 }
 
+impl ChainConfig {
+    /// Return the checkpoint hash at block `number`.
+    pub fn checkpoint(&self, number: BlockNumber) -> Option<HashValue> {
+        self.checkpoints
+            .iter()
+            .find(|(checkpoint, _)| *checkpoint == number)
+            .map(|(_, hash)| *hash)
+    }
+
+    /// Return the proof of work version of block `number`.
+    pub fn pow_version(&self, number: BlockNumber) -> PowVersion {
+        PowVersion::of(number, self.header_blob_pow_number)
+    }
+
+    /// Whether the timestamp of block `number` is checked against the median timestamp of recent blocks.
+    pub fn is_median_time_past(&self, number: BlockNumber) -> bool {
+        number >= self.median_time_past_number
+    }
+}
+
+/// Max reorg depth of the long-running networks.
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 1000;
+
+/// Halley blocks below it are mined over the parent hash, it must be above the Halley head when released.
+/// The nodes can move it by `--header-blob-pow-number`, such as to 0 when Halley is reset.
+pub const HALLEY_HEADER_BLOB_POW_NUMBER: BlockNumber = 300_000;
+
+/// Proxima blocks are mined over the parent hash until the release which upgrades Proxima
+/// sets the activation number.
+pub const PROXIMA_HEADER_BLOB_POW_NUMBER: BlockNumber = BlockNumber::max_value();
+
+/// The median time past rule is released with the header blob pow on Halley.
+pub const HALLEY_MEDIAN_TIME_PAST_NUMBER: BlockNumber = HALLEY_HEADER_BLOB_POW_NUMBER;
+
+/// Proxima blocks are checked against their parent until the release which upgrades Proxima
+/// sets the activation number.
+pub const PROXIMA_MEDIAN_TIME_PAST_NUMBER: BlockNumber = BlockNumber::max_value();
+
 pub static STARCOIN_TOTAL_SUPPLY: u64 = 2_100_000_000 * 1_000_000;
 
 pub static DEV_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
@@ -201,6 +281,7 @@ pub static DEV_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
         reward_halving_interval: 100,
         reward_delay: 1,
         difficulty: 1.into(),
+        difficulty_config: DifficultyConfig::default(),
         consensus_header: vec![],
         pre_mine_config: Some(PreMineConfig {
             public_key,
@@ -212,6 +293,10 @@ pub static DEV_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
             gas_schedule: INITIAL_GAS_SCHEDULE.clone(),
         },
         boot_nodes: vec![],
+        checkpoints: vec![],
+        max_reorg_depth: 16,
+        header_blob_pow_number: 0,
+        median_time_past_number: 0,
     }
 });
 
@@ -225,6 +310,7 @@ pub static HALLEY_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
         reward_halving_interval: 1000,
         reward_delay: 3,
         difficulty: 10.into(),
+        difficulty_config: DifficultyConfig::default(),
         consensus_header: vec![],
         pre_mine_config: Some(PreMineConfig {
             public_key: Ed25519PublicKey::from_encoded_string(
@@ -241,6 +327,11 @@ pub static HALLEY_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
         boot_nodes: vec!["/dns4/halley1.seed.starcoin.org/tcp/9840/p2p/12D3KooWFvCKQ1n2JkSQpn8drqGwU27vTPkKx264zD4CFbgaKDJU".parse().expect("parse multi addr should be ok"),
                          "/dns4/halley2.seed.starcoin.org/tcp/9840/p2p/12D3KooWAua4KokJMiCodGPEF2n4yN42B2Q26KgwrQTntnrCDRHd".parse().expect("parse multi addr should be ok"),
                          "/dns4/halley3.seed.starcoin.org/tcp/9840/p2p/12D3KooW9vHQJk9o69tZPMM2viQ3eWpgp6veDBRz8tTvDFDBejwk".parse().expect("parse multi addr should be ok"), ],
+        // The data of Halley is cleaned up periodically, checkpoints would not survive a reset.
+        checkpoints: vec![],
+        max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+        header_blob_pow_number: HALLEY_HEADER_BLOB_POW_NUMBER,
+        median_time_past_number: HALLEY_MEDIAN_TIME_PAST_NUMBER,
     }
 });
 
@@ -254,6 +345,7 @@ pub static PROXIMA_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
         reward_halving_interval: 10000,
         reward_delay: 7,
         difficulty: 10.into(),
+        difficulty_config: DifficultyConfig::default(),
         consensus_header: vec![],
         pre_mine_config: None,
         vm_config: VMConfig {
@@ -263,6 +355,13 @@ pub static PROXIMA_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
         boot_nodes: vec!["/dns4/proxima1.seed.starcoin.org/tcp/9840/p2p/12D3KooW9vHQJk9o69tZPMM2viQ3eWpgp6veDBRz8tTvDFDBejwk".parse().expect("parse multi addr should be ok"),
                          "/dns4/proxima2.seed.starcoin.org/tcp/9840/p2p/12D3KooWAua4KokJMiCodGPEF2n4yN42B2Q26KgwrQTntnrCDRHd".parse().expect("parse multi addr should be ok"),
                          "/dns4/proxima3.seed.starcoin.org/tcp/9840/p2p/12D3KooWFvCKQ1n2JkSQpn8drqGwU27vTPkKx264zD4CFbgaKDJU".parse().expect("parse multi addr should be ok"), ],
+        // No checkpoint is pinned yet: the generated genesis of Proxima is regenerated when the
+        // stdlib changes, which resets the chain, so no block of it stays well confirmed across
+        // releases. Append checkpoints of well confirmed master blocks once Proxima is stable.
+        checkpoints: vec![],
+        max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+        header_blob_pow_number: PROXIMA_HEADER_BLOB_POW_NUMBER,
+        median_time_past_number: PROXIMA_MEDIAN_TIME_PAST_NUMBER,
     }
 });
 
@@ -275,6 +374,7 @@ pub static MAIN_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| ChainConfig {
     reward_halving_interval: 52500,
     reward_delay: 7,
     difficulty: 10.into(),
+    difficulty_config: DifficultyConfig::default(),
     consensus_header: vec![],
     pre_mine_config: None,
     vm_config: VMConfig {
@@ -282,4 +382,10 @@ pub static MAIN_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| ChainConfig {
         gas_schedule: INITIAL_GAS_SCHEDULE.clone(),
     },
     boot_nodes: vec![],
+    // Main is not launched, there is no block to pin. Append the checkpoints of well confirmed
+    // master blocks on every release after launch.
+    checkpoints: vec![],
+    max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+    header_blob_pow_number: 0,
+    median_time_past_number: 0,
 });
@@ -13,6 +13,8 @@ use starcoin_crypto::{
     test_utils::KeyPair,
 };
 use starcoin_logger::prelude::*;
+use starcoin_time_service::{RealTimeService, TimeService};
+use starcoin_types::block::{BlockNumber, PowVersion};
 use std::convert::TryFrom;
 use std::fs::create_dir_all;
 use std::fs::File;
@@ -35,16 +37,16 @@ mod sync_config;
 mod txpool_config;
 
 pub use chain_config::{
-    ChainConfig, ChainNetwork, PreMineConfig, DEV_CHAIN_CONFIG, HALLEY_CHAIN_CONFIG,
-    MAIN_CHAIN_CONFIG, PROXIMA_CHAIN_CONFIG,
+    ChainConfig, ChainNetwork, DifficultyAlgorithm, DifficultyConfig, PreMineConfig,
+    DEV_CHAIN_CONFIG, HALLEY_CHAIN_CONFIG, MAIN_CHAIN_CONFIG, PROXIMA_CHAIN_CONFIG,
 };
 pub use libra_temppath::TempPath;
 pub use logger_config::LoggerConfig;
 pub use metrics_config::MetricsConfig;
-pub use miner_config::{ConsensusStrategy, MinerConfig, PacemakerStrategy};
+pub use miner_config::{ConsensusStrategy, MinerConfig, PacemakerStrategy, MIN_GAS_PER_TXN};
 pub use network_config::NetworkConfig;
 pub use rpc_config::RpcConfig;
-pub use storage_config::StorageConfig;
+pub use storage_config::{CompressionType, StorageConfig};
 pub use sync_config::SyncMode;
 pub use txpool_config::TxPoolConfig;
 
@@ -124,6 +126,10 @@ pub struct StarcoinOpt {
     /// Block period in second to use in dev network mode (0 = mine only if transaction pending)
     pub dev_period: u64,
 
+    #[structopt(long = "dev-manual-mine")]
+    /// Only mine blocks by the dev.mine_blocks rpc in dev network mode, the dev_period is ignored.
+    pub dev_manual_mine: bool,
+
     #[structopt(long = "node-key")]
     /// Node network private key, only work for first init.
     pub node_key: Option<String>,
@@ -163,6 +169,10 @@ pub struct StarcoinOpt {
     #[structopt(long = "rpc_address")]
     /// Rpc address, default is 127.0.0.1
     pub rpc_address: Option<String>,
+
+    #[structopt(long = "header-blob-pow-number")]
+    /// Block number from which the proof of work commits to the mining header blob, only work for dev and halley network.
+    pub header_blob_pow_number: Option<BlockNumber>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -192,7 +202,7 @@ impl Default for DataDirPath {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BaseConfig {
     #[serde(default)]
     net: ChainNetwork,
@@ -200,6 +210,23 @@ pub struct BaseConfig {
     base_data_dir: DataDirPath,
     #[serde(skip)]
     data_dir: PathBuf,
+    #[serde(skip, default = "default_time_service")]
+    time_service: Arc<dyn TimeService>,
+    #[serde(skip)]
+    header_blob_pow_number: Option<BlockNumber>,
+}
+
+fn default_time_service() -> Arc<dyn TimeService> {
+    Arc::new(RealTimeService::new())
+}
+
+impl PartialEq for BaseConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.net == other.net
+            && self.base_data_dir == other.base_data_dir
+            && self.data_dir == other.data_dir
+            && self.header_blob_pow_number == other.header_blob_pow_number
+    }
 }
 
 impl BaseConfig {
@@ -223,6 +250,8 @@ impl BaseConfig {
             net,
             base_data_dir,
             data_dir,
+            time_service: default_time_service(),
+            header_blob_pow_number: None,
         }
     }
 
@@ -238,6 +267,9 @@ impl BaseConfig {
     pub fn random_for_test() -> Self {
         Self::new(ChainNetwork::Dev, None)
     }
+    pub fn time_service(&self) -> Arc<dyn TimeService> {
+        self.time_service.clone()
+    }
 }
 
 impl Default for BaseConfig {
@@ -293,7 +325,15 @@ impl NodeConfig {
     }
 
     pub fn load_with_opt(opt: &StarcoinOpt) -> Result<Self> {
-        let base = BaseConfig::new(opt.net.unwrap_or_default(), opt.data_dir.clone());
+        let mut base = BaseConfig::new(opt.net.unwrap_or_default(), opt.data_dir.clone());
+        if let Some(number) = opt.header_blob_pow_number {
+            ensure!(
+                base.net.is_dev() || base.net.is_halley(),
+                "header blob pow number can not be changed on {} network.",
+                base.net
+            );
+            base.header_blob_pow_number = Some(number);
+        }
         let data_dir = base.data_dir();
         ensure!(data_dir.is_dir(), "please pass in a dir as data_dir");
 
@@ -328,6 +368,30 @@ impl NodeConfig {
     pub fn net(&self) -> ChainNetwork {
         self.base.net
     }
+
+    pub fn time_service(&self) -> Arc<dyn TimeService> {
+        self.base.time_service()
+    }
+
+    /// Replace the clock of the node, such as a `MockTimeService`, just for test.
+    pub fn set_time_service(&mut self, time_service: Arc<dyn TimeService>) {
+        self.base.time_service = time_service;
+    }
+
+    /// Return the proof of work version of block `number`, by the `header_blob_pow_number` of
+    /// the network if it is not overridden.
+    pub fn pow_version(&self, number: BlockNumber) -> PowVersion {
+        let header_blob_pow_number = self
+            .base
+            .header_blob_pow_number
+            .unwrap_or_else(|| self.net().get_config().header_blob_pow_number);
+        PowVersion::of(number, header_blob_pow_number)
+    }
+
+    /// Override the `header_blob_pow_number` of the network, just for test.
+    pub fn set_header_blob_pow_number(&mut self, number: BlockNumber) {
+        self.base.header_blob_pow_number = Some(number);
+    }
 }
 
 impl Default for NodeConfig {
@@ -377,11 +441,28 @@ impl ConfigModule for NodeConfig {
         self.vault.load(base, opt)?;
         self.metrics.load(base, opt)?;
         self.logger.load(base, opt)?;
-        Ok(())
+        self.check_state_prune_window()
     }
 }
 
-impl NodeConfig {}
+impl NodeConfig {
+    /// A fork deeper than the state prune window can not be executed, for the state of its
+    /// parent is pruned, so the window must cover the max reorg depth.
+    fn check_state_prune_window(&self) -> Result<()> {
+        if self.storage.archive() {
+            return Ok(());
+        }
+        let window = self.storage.state_prune_window();
+        let max_reorg_depth = self.net().get_config().max_reorg_depth;
+        ensure!(
+            window >= max_reorg_depth,
+            "storage.state_prune_window {} should not be less than the max reorg depth {} of the chain",
+            window,
+            max_reorg_depth
+        );
+        Ok(())
+    }
+}
 
 pub(crate) fn save_config<T, P>(c: &T, output_file: P) -> Result<()>
 where
@@ -8,6 +8,9 @@ use std::net::SocketAddr;
 
 pub static DEFAULT_STRATUM_SERVER_PORT: u16 = 9940;
 
+/// The min gas used by a transaction, which limits how many transactions a block can include.
+pub const MIN_GAS_PER_TXN: u64 = 600;
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MinerConfig {
@@ -32,6 +35,13 @@ impl Default for MinerConfig {
     }
 }
 
+impl MinerConfig {
+    /// Max number of transactions to take from the txpool for a block.
+    pub fn max_block_txns(&self) -> u64 {
+        self.block_gas_limit / MIN_GAS_PER_TXN
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(tag = "type")]
 pub enum ConsensusStrategy {
@@ -45,6 +55,8 @@ pub enum PacemakerStrategy {
     HeadBlock,
     Ondemand,
     Schedule,
+    /// No pacemaker, blocks are only mined by the `dev.mine_blocks` rpc.
+    Manual,
 }
 
 impl ConfigModule for MinerConfig {
@@ -84,7 +96,9 @@ impl ConfigModule for MinerConfig {
     }
 
     fn load(&mut self, base: &BaseConfig, opt: &StarcoinOpt) -> Result<()> {
-        if base.net.is_dev() && opt.dev_period > 0 {
+        if base.net.is_dev() && opt.dev_manual_mine {
+            self.pacemaker_strategy = PacemakerStrategy::Manual;
+        } else if base.net.is_dev() && opt.dev_period > 0 {
             self.pacemaker_strategy = PacemakerStrategy::Schedule;
             self.consensus_strategy = ConsensusStrategy::Dummy(opt.dev_period);
         } else if !base.net.is_dev() {
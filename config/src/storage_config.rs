@@ -4,14 +4,46 @@
 use crate::{BaseConfig, ChainNetwork, ConfigModule, StarcoinOpt};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CompressionType {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StorageConfig {
     dir: PathBuf,
     #[serde(skip)]
     absolute_dir: Option<PathBuf>,
+    /// Keep all history state if true, otherwise prune the state nodes which are only
+    /// reachable from state roots older than `state_prune_window` blocks behind head.
+    archive: bool,
+    /// How many blocks of history state to keep when not in archive mode.
+    /// It should be larger than the max reorg depth, or blocks of a fork can not be executed.
+    state_prune_window: u64,
+    /// RocksDB block cache capacity in bytes, shared by all column families.
+    block_cache_size: usize,
+    /// Max number of files RocksDB keeps open, -1 means no limit.
+    max_open_files: i32,
+    /// RocksDB memtable size in bytes of each column family.
+    write_buffer_size: usize,
+    /// Max number of RocksDB background flush and compaction jobs.
+    max_background_jobs: i32,
+    /// Compression of each column family.
+    compression: CompressionType,
+    /// Cache capacity in bytes of each column family.
+    cache_size: usize,
+    /// Cache capacity in bytes of the specified column families, which overrides `cache_size`.
+    /// 0 turns off the cache of the column family, which is useful for write-mostly ones.
+    column_cache_sizes: BTreeMap<String, usize>,
+    /// Compression of the specified column families, which overrides `compression`.
+    column_compressions: BTreeMap<String, CompressionType>,
 }
 
 impl Default for StorageConfig {
@@ -27,6 +59,50 @@ impl StorageConfig {
             .cloned()
             .expect("config should init first.")
     }
+
+    pub fn archive(&self) -> bool {
+        self.archive
+    }
+
+    pub fn state_prune_window(&self) -> u64 {
+        self.state_prune_window
+    }
+
+    pub fn block_cache_size(&self) -> usize {
+        self.block_cache_size
+    }
+
+    pub fn max_open_files(&self) -> i32 {
+        self.max_open_files
+    }
+
+    pub fn write_buffer_size(&self) -> usize {
+        self.write_buffer_size
+    }
+
+    pub fn max_background_jobs(&self) -> i32 {
+        self.max_background_jobs
+    }
+
+    /// Compression of the column family `cf`.
+    pub fn column_compression(&self, cf: &str) -> CompressionType {
+        self.column_compressions
+            .get(cf)
+            .cloned()
+            .unwrap_or(self.compression)
+    }
+
+    pub fn cache_size(&self) -> usize {
+        self.cache_size
+    }
+
+    /// Cache capacity in bytes of the column family `cf`.
+    pub fn column_cache_size(&self, cf: &str) -> usize {
+        self.column_cache_sizes
+            .get(cf)
+            .cloned()
+            .unwrap_or(self.cache_size)
+    }
 }
 
 impl ConfigModule for StorageConfig {
@@ -34,6 +110,22 @@ impl ConfigModule for StorageConfig {
         Self {
             dir: PathBuf::from("starcoindb/db"),
             absolute_dir: None,
+            archive: true,
+            state_prune_window: 1000,
+            block_cache_size: 128 * 1024 * 1024,
+            max_open_files: 4096,
+            write_buffer_size: 64 * 1024 * 1024,
+            max_background_jobs: 4,
+            compression: CompressionType::Lz4,
+            cache_size: 8 * 1024 * 1024,
+            column_cache_sizes: vec![
+                ("state_node".to_string(), 128 * 1024 * 1024),
+                ("acc_node".to_string(), 32 * 1024 * 1024),
+                ("transaction_info".to_string(), 0),
+            ]
+            .into_iter()
+            .collect(),
+            column_compressions: BTreeMap::new(),
         }
     }
 
@@ -3,6 +3,7 @@
 
 use crate::difficulty;
 use crate::difficulty::{difficult_to_target, target_to_difficulty};
+use crate::verified_cache::{POW_VERIFIED_HEADERS, VERIFIED_HEADERS};
 use anyhow::{Error, Result};
 use argon2::{self, Config};
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -12,7 +13,7 @@ use rand::Rng;
 use std::convert::TryFrom;
 use std::io::Cursor;
 use std::sync::Arc;
-use traits::ChainReader;
+use traits::ChainHeaderReader;
 use traits::{Consensus, ConsensusHeader};
 use types::block::BlockHeader;
 use types::{H256, U256};
@@ -49,45 +50,58 @@ impl Consensus for ArgonConsensus {
     type ConsensusHeader = ArgonConsensusHeader;
 
     fn calculate_next_difficulty(
-        _config: Arc<NodeConfig>,
-        reader: &dyn ChainReader,
+        config: Arc<NodeConfig>,
+        reader: &dyn ChainHeaderReader,
     ) -> Result<U256> {
-        let target = difficulty::get_next_work_required(reader)?;
+        let target = difficulty::get_next_work_required(
+            &config.net().get_config().difficulty_config,
+            reader,
+        )?;
         Ok(target_to_difficulty(target))
     }
-    fn solve_consensus_header(header_hash: &[u8], difficulty: U256) -> Self::ConsensusHeader {
+    fn solve_consensus_header(pow_header: &[u8], difficulty: U256) -> Self::ConsensusHeader {
         let mut nonce = generate_nonce();
-        loop {
-            let pow_hash: U256 = calculate_hash(&set_header_nonce(&header_hash, nonce))
-                .expect("calculate hash should work")
-                .into();
-            if pow_hash > difficulty {
-                nonce += 1;
-                continue;
-            }
-            break;
+        while !verify(pow_header, nonce, difficulty) {
+            nonce = nonce.wrapping_add(1);
         }
         ArgonConsensusHeader { nonce }
     }
 
     fn verify(
         config: Arc<NodeConfig>,
-        reader: &dyn ChainReader,
+        reader: &dyn ChainHeaderReader,
         header: &BlockHeader,
     ) -> Result<()> {
-        let difficulty = ArgonConsensus::calculate_next_difficulty(config, reader)?;
+        if VERIFIED_HEADERS.contains(&config, header) {
+            return Ok(());
+        }
+        let difficulty = ArgonConsensus::calculate_next_difficulty(config.clone(), reader)?;
         if header.difficulty() != difficulty {
             return Err(anyhow::Error::msg("Invalid difficulty"));
         }
+        Self::verify_pow(config.clone(), header)?;
+        VERIFIED_HEADERS.insert(&config, header);
+        Ok(())
+    }
+
+    fn verify_pow(config: Arc<NodeConfig>, header: &BlockHeader) -> Result<()> {
+        if POW_VERIFIED_HEADERS.contains(&config, header) {
+            return Ok(());
+        }
         let consensus_header: ArgonConsensusHeader =
             ArgonConsensusHeader::try_from(header.consensus_header().to_vec())?;
         let nonce = consensus_header.nonce;
-        let header = header.parent_hash().to_hex();
+        let difficulty = header.difficulty();
+        let version = config.pow_version(header.number());
         debug!(
-            "Verify header, nonce, difficulty :{:?}, {:o}, {:x}",
-            header, nonce, difficulty
+            "Verify header, version, nonce, difficulty :{:?}, {:?}, {:o}, {:x}",
+            header.id(),
+            version,
+            nonce,
+            difficulty
         );
-        if verify(header.as_bytes(), nonce, difficulty) {
+        if verify(&header.pow_header(version), nonce, difficulty) {
+            POW_VERIFIED_HEADERS.insert(&config, header);
             Ok(())
         } else {
             Err(anyhow::Error::msg("Invalid header"))
@@ -140,3 +154,74 @@ pub fn set_header_nonce(header: &[u8], nonce: u64) -> Vec<u8> {
 pub fn vec_to_u64(v: Vec<u8>) -> u64 {
     LittleEndian::read_u64(&v)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::block::PowVersion;
+
+    /// Mine from nonce 0, so the nonce of a header is deterministic.
+    fn mine(config: &NodeConfig, header: &mut BlockHeader) {
+        let pow_header = header.pow_header(config.pow_version(header.number()));
+        let nonce = (0..)
+            .find(|nonce| verify(&pow_header, *nonce, header.difficulty()))
+            .expect("A nonce must be found.");
+        header.consensus_header = ArgonConsensusHeader { nonce }.into();
+    }
+
+    fn new_header(number: u64) -> BlockHeader {
+        let mut header = BlockHeader::default();
+        header.number = number;
+        header.difficulty = 128.into();
+        header
+    }
+
+    #[test]
+    fn test_pow_commits_to_header() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        assert_eq!(config.pow_version(0), PowVersion::HeaderBlob);
+        let mut header = new_header(1);
+        mine(&config, &mut header);
+        ArgonConsensus::verify_pow(config.clone(), &header)?;
+
+        // the nonce does not work for other block content with the same parent.
+        let mut other = header.clone();
+        other.gas_used = 1;
+        assert!(ArgonConsensus::verify_pow(config, &other).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pow_version_switch() -> Result<()> {
+        let mut config = NodeConfig::random_for_test();
+        config.set_header_blob_pow_number(10);
+        let config = Arc::new(config);
+        assert_eq!(config.pow_version(9), PowVersion::ParentHash);
+        assert_eq!(config.pow_version(10), PowVersion::HeaderBlob);
+
+        // below the switch, the nonce only commits to the parent hash.
+        let mut header = new_header(9);
+        mine(&config, &mut header);
+        ArgonConsensus::verify_pow(config.clone(), &header)?;
+        let mut other = header.clone();
+        other.gas_used = 1;
+        ArgonConsensus::verify_pow(config.clone(), &other)?;
+
+        // from the switch, a nonce mined over the parent hash is invalid.
+        let mut header = new_header(10);
+        let nonce = (0..)
+            .find(|nonce| {
+                verify(
+                    &header.pow_header(PowVersion::ParentHash),
+                    *nonce,
+                    header.difficulty(),
+                )
+            })
+            .expect("A nonce must be found.");
+        header.consensus_header = ArgonConsensusHeader { nonce }.into();
+        assert!(ArgonConsensus::verify_pow(config.clone(), &header).is_err());
+        mine(&config, &mut header);
+        ArgonConsensus::verify_pow(config, &header)?;
+        Ok(())
+    }
+}
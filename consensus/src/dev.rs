@@ -9,7 +9,7 @@ use std::convert::TryFrom;
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
-use traits::ChainReader;
+use traits::ChainHeaderReader;
 use traits::{Consensus, ConsensusHeader};
 use types::block::BlockHeader;
 use types::U256;
@@ -42,7 +42,7 @@ impl Consensus for DevConsensus {
 
     fn calculate_next_difficulty(
         config: Arc<NodeConfig>,
-        _reader: &dyn ChainReader,
+        _reader: &dyn ChainHeaderReader,
     ) -> Result<U256> {
         let mut rng = rand::thread_rng();
         // if produce block on demand, use a default wait time.
@@ -60,7 +60,7 @@ impl Consensus for DevConsensus {
         }
     }
 
-    fn solve_consensus_header(_header_hash: &[u8], difficulty: U256) -> Self::ConsensusHeader {
+    fn solve_consensus_header(_pow_header: &[u8], difficulty: U256) -> Self::ConsensusHeader {
         let time: u64 = difficulty.as_u64();
         debug!("DevConsensus rand sleep time : {}", time);
         thread::sleep(Duration::from_millis(time));
@@ -69,7 +69,7 @@ impl Consensus for DevConsensus {
 
     fn verify(
         _config: Arc<NodeConfig>,
-        _reader: &dyn ChainReader,
+        _reader: &dyn ChainHeaderReader,
         _header: &BlockHeader,
     ) -> Result<()> {
         Ok(())
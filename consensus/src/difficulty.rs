@@ -3,42 +3,57 @@
 
 use types::U256;
 
-pub const BLOCK_TIME_SEC: u32 = 20;
-pub const BLOCK_WINDOW: u64 = 24;
-
 use anyhow::Result;
+use config::{DifficultyAlgorithm as Algorithm, DifficultyConfig};
 use logger::prelude::*;
-use traits::ChainReader;
+use traits::ChainHeaderReader;
 
 pub fn difficult_1_target() -> U256 {
     U256::max_value()
 }
 
-pub fn current_hash_rate(target: &[u8]) -> u64 {
+pub fn current_hash_rate(target: &[u8], block_time_target: u64) -> u64 {
     // current_hash_rate = (difficult_1_target/target_current) * difficult_1_hash/block_per_esc
     let target_u256: U256 = target.into();
-    (difficult_1_target() / target_u256).low_u64() / (BLOCK_TIME_SEC as u64)
+    (difficult_1_target() / target_u256).low_u64() / block_time_target
+}
+
+/// An algorithm to calculate the target of next block.
+pub trait DifficultyAlgorithm {
+    /// `blocks` are the recent blocks in descending order, at least 2 blocks.
+    fn next_target(&self, blocks: &[BlockInfo]) -> U256;
+}
+
+/// Create the difficulty algorithm selected by `config`.
+pub fn difficulty_algorithm(config: &DifficultyConfig) -> Box<dyn DifficultyAlgorithm> {
+    match config.algorithm {
+        Algorithm::WindowedAverage => Box::new(WindowedAverage::new(config.block_time_target)),
+        Algorithm::Lwma => Box::new(Lwma::new(config.block_time_target)),
+    }
 }
 
 /// Get the target of next pow work
-pub fn get_next_work_required(chain: &dyn ChainReader) -> Result<U256> {
-    let mut current_header = chain.current_header();
+pub fn get_next_work_required(
+    config: &DifficultyConfig,
+    chain: &dyn ChainHeaderReader,
+) -> Result<U256> {
+    let mut current_header = chain.head_header();
     if current_header.number <= 1 {
         return Ok(difficult_to_target(current_header.difficulty));
     }
     let blocks = {
         let mut blocks: Vec<BlockInfo> = vec![];
-        let calculate_window = if current_header.number < BLOCK_WINDOW {
+        let calculate_window = if current_header.number < config.block_window {
             current_header.number
         } else {
-            BLOCK_WINDOW
+            config.block_window
         };
         blocks.push(BlockInfo {
             timestamp: current_header.timestamp,
             target: difficult_to_target(current_header.difficulty),
         });
         for _ in 1..calculate_window {
-            match chain.get_header(current_header.parent_hash)? {
+            match chain.find_header(current_header.parent_hash)? {
                 Some(header) => {
                     // Skip genesis
                     if header.number == 0 {
@@ -57,31 +72,53 @@ pub fn get_next_work_required(chain: &dyn ChainReader) -> Result<U256> {
         }
         blocks
     };
-
-    let mut avg_time: u64 = 0;
-    let mut avg_target = U256::zero();
-    let mut latest_block_index = 0;
-    let block_n = blocks.len() - 1;
-    while latest_block_index < block_n {
-        let solve_time =
-            blocks[latest_block_index].timestamp - blocks[latest_block_index + 1].timestamp;
-        avg_time += solve_time * (block_n - latest_block_index) as u64;
-        debug!(
-            "solve_time:{:?}, avg_time:{:?}, block_n:{:?}",
-            solve_time, avg_time, block_n
-        );
-        avg_target = avg_target + blocks[latest_block_index].target / block_n.into();
-        latest_block_index += 1
+    if blocks.len() < 2 {
+        return Ok(blocks[0].target);
     }
-    avg_time /= (block_n as u64) * ((block_n + 1) as u64) / 2;
-    if avg_time == 0 {
-        avg_time = 1
+    Ok(difficulty_algorithm(config).next_target(&blocks))
+}
+
+/// Average the targets of the window, and scale it by the linearly weighted average solve
+/// time, the target changes at most 2 times every block.
+pub struct WindowedAverage {
+    block_time_target: u64,
+}
+
+impl WindowedAverage {
+    pub fn new(block_time_target: u64) -> Self {
+        Self { block_time_target }
     }
-    let time_plan = BLOCK_TIME_SEC;
-    // new_target = avg_target * avg_time_used/time_plan
-    // avoid the target increase or reduce too fast.
-    let new_target =
-        if let Some(new_target) = (avg_target / time_plan.into()).checked_mul(avg_time.into()) {
+}
+
+impl DifficultyAlgorithm for WindowedAverage {
+    fn next_target(&self, blocks: &[BlockInfo]) -> U256 {
+        let mut avg_time: u64 = 0;
+        let mut avg_target = U256::zero();
+        let mut latest_block_index = 0;
+        let block_n = blocks.len() - 1;
+        while latest_block_index < block_n {
+            // a block may be older than its parent, as long as it is newer than the median.
+            let solve_time = blocks[latest_block_index]
+                .timestamp
+                .saturating_sub(blocks[latest_block_index + 1].timestamp);
+            avg_time += solve_time * (block_n - latest_block_index) as u64;
+            debug!(
+                "solve_time:{:?}, avg_time:{:?}, block_n:{:?}",
+                solve_time, avg_time, block_n
+            );
+            avg_target = avg_target + blocks[latest_block_index].target / block_n.into();
+            latest_block_index += 1
+        }
+        avg_time /= (block_n as u64) * ((block_n + 1) as u64) / 2;
+        if avg_time == 0 {
+            avg_time = 1
+        }
+        let time_plan = self.block_time_target;
+        // new_target = avg_target * avg_time_used/time_plan
+        // avoid the target increase or reduce too fast.
+        let new_target = if let Some(new_target) =
+            (avg_target / time_plan.into()).checked_mul(avg_time.into())
+        {
             if new_target / 2.into() > avg_target {
                 debug!("target increase too fast, limit to 2 times");
                 avg_target * 2
@@ -95,11 +132,57 @@ pub fn get_next_work_required(chain: &dyn ChainReader) -> Result<U256> {
             debug!("target large than max value, set to 1_difficulty");
             difficult_1_target()
         };
-    debug!(
-        "avg_time:{:?}s, time_plan:{:?}s, target: {:?}",
-        avg_time, time_plan, new_target
-    );
-    Ok(new_target)
+        debug!(
+            "avg_time:{:?}s, time_plan:{:?}s, target: {:?}",
+            avg_time, time_plan, new_target
+        );
+        new_target
+    }
+}
+
+/// Linearly weighted moving average: the solve times are weighted by their position in the
+/// window, the newest weighs most, and every solve time is clamped to [1, 6 * block time target]
+/// so a single block with a bad timestamp can not swing the difficulty.
+pub struct Lwma {
+    block_time_target: u64,
+}
+
+impl Lwma {
+    pub fn new(block_time_target: u64) -> Self {
+        Self { block_time_target }
+    }
+}
+
+impl DifficultyAlgorithm for Lwma {
+    fn next_target(&self, blocks: &[BlockInfo]) -> U256 {
+        let n = (blocks.len() - 1) as u64;
+        let max_solve_time = 6 * self.block_time_target;
+        let mut weighted_time: u64 = 0;
+        let mut sum_target = U256::zero();
+        // blocks are in descending order, the newest solve time has the weight n.
+        for (i, pair) in blocks.windows(2).enumerate() {
+            let solve_time = pair[0]
+                .timestamp
+                .saturating_sub(pair[1].timestamp)
+                .max(1)
+                .min(max_solve_time);
+            weighted_time += solve_time * (n - i as u64);
+            sum_target = sum_target.saturating_add(pair[0].target / n.into());
+        }
+        let expect_time = self.block_time_target * n * (n + 1) / 2;
+        // limit the target to drop at most 10 times.
+        let weighted_time = weighted_time.max(expect_time / 10).max(1);
+        let new_target = match (sum_target / expect_time.into()).checked_mul(weighted_time.into()) {
+            Some(new_target) if !new_target.is_zero() => new_target,
+            Some(_) => 1.into(),
+            None => difficult_1_target(),
+        };
+        debug!(
+            "weighted_time:{:?}s, expect_time:{:?}s, target: {:?}",
+            weighted_time, expect_time, new_target
+        );
+        new_target
+    }
 }
 
 pub fn target_to_difficulty(target: U256) -> U256 {
@@ -6,7 +6,7 @@ use anyhow::Result;
 use config::NodeConfig;
 use rand::prelude::*;
 use std::sync::Arc;
-use traits::ChainReader;
+use traits::ChainHeaderReader;
 use traits::Consensus;
 use types::block::BlockHeader;
 use types::U256;
@@ -19,7 +19,7 @@ impl Consensus for DummyConsensus {
 
     fn calculate_next_difficulty(
         config: Arc<NodeConfig>,
-        _reader: &dyn ChainReader,
+        _reader: &dyn ChainHeaderReader,
     ) -> Result<U256> {
         let mut rng = rand::thread_rng();
         // if produce block on demand, use a default wait time.
@@ -32,13 +32,13 @@ impl Consensus for DummyConsensus {
         Ok(time.into())
     }
 
-    fn solve_consensus_header(_header_hash: &[u8], _difficulty: U256) -> Self::ConsensusHeader {
+    fn solve_consensus_header(_pow_header: &[u8], _difficulty: U256) -> Self::ConsensusHeader {
         DummyHeader {}
     }
 
     fn verify(
         _config: Arc<NodeConfig>,
-        _reader: &dyn ChainReader,
+        _reader: &dyn ChainHeaderReader,
         _header: &BlockHeader,
     ) -> Result<()> {
         Ok(())
@@ -5,3 +5,5 @@ pub mod argon;
 pub mod dev;
 pub mod difficulty;
 pub mod dummy;
+pub mod simulation;
+pub mod verified_cache;
@@ -0,0 +1,200 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Simulate difficulty algorithms with synthetic hash rate curves, to compare how they follow
+//! the hash rate and how much the block time oscillates.
+
+use crate::difficulty::{
+    difficult_to_target, difficulty_algorithm, target_to_difficulty, BlockInfo,
+};
+use config::DifficultyConfig;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt::{Display, Formatter};
+use types::U256;
+
+/// Hash rate of the network, in hashes per second, over the simulated time.
+#[derive(Clone, Debug)]
+pub enum HashRateCurve {
+    Constant(f64),
+    /// A large miner joins (or leaves) at time `at`.
+    Step {
+        from: f64,
+        to: f64,
+        at: u64,
+    },
+    /// A large miner switches on and off every `period` seconds.
+    Square {
+        low: f64,
+        high: f64,
+        period: u64,
+    },
+}
+
+impl HashRateCurve {
+    pub fn hash_rate(&self, time: u64) -> f64 {
+        match self {
+            HashRateCurve::Constant(rate) => *rate,
+            HashRateCurve::Step { from, to, at } => {
+                if time < *at {
+                    *from
+                } else {
+                    *to
+                }
+            }
+            HashRateCurve::Square { low, high, period } => {
+                if (time / period) % 2 == 0 {
+                    *low
+                } else {
+                    *high
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationReport {
+    pub blocks: u64,
+    /// Average block time in seconds.
+    pub mean_block_time: f64,
+    /// Standard deviation of the block time, it measures the oscillation.
+    pub block_time_std_dev: f64,
+    pub max_block_time: u64,
+    /// Blocks which take more than 3 times of the block time target.
+    pub slow_blocks: u64,
+    /// Mean relative error between the difficulty and the ideal difficulty of the hash rate.
+    pub difficulty_error: f64,
+}
+
+impl Display for SimulationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "blocks: {}, mean block time: {:.2}s, std dev: {:.2}s, max: {}s, slow blocks: {}, difficulty error: {:.2}%",
+            self.blocks,
+            self.mean_block_time,
+            self.block_time_std_dev,
+            self.max_block_time,
+            self.slow_blocks,
+            self.difficulty_error * 100.0
+        )
+    }
+}
+
+/// Mine `blocks` blocks with the algorithm of `config` under the hash rate `curve`, the solve
+/// time of every block is sampled from the exponential distribution of the difficulty and the
+/// hash rate at that time. The same `seed` always gives the same report.
+pub fn simulate(
+    config: &DifficultyConfig,
+    curve: &HashRateCurve,
+    blocks: u64,
+    seed: u64,
+) -> SimulationReport {
+    let algorithm = difficulty_algorithm(config);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let block_time_target = config.block_time_target;
+    let ideal_difficulty = |time: u64| curve.hash_rate(time) * block_time_target as f64;
+
+    // start from a window mined at the ideal difficulty, in descending order.
+    let initial_difficulty = U256::from((ideal_difficulty(0) as u64).max(1));
+    let window = config.block_window.max(2) as usize;
+    let mut history: Vec<BlockInfo> = (0..window as u64)
+        .map(|i| BlockInfo {
+            timestamp: (window as u64 - 1 - i) * block_time_target,
+            target: difficult_to_target(initial_difficulty),
+        })
+        .collect();
+    let mut now = history[0].timestamp;
+
+    let mut block_times = Vec::with_capacity(blocks as usize);
+    let mut difficulty_error = 0f64;
+    for _ in 0..blocks {
+        let target = algorithm.next_target(&history);
+        let difficulty = target_to_difficulty(target).low_u128() as f64;
+        let ideal = ideal_difficulty(now);
+        difficulty_error += (difficulty - ideal).abs() / ideal;
+
+        let expect_time = difficulty / curve.hash_rate(now);
+        let sample: f64 = rng.gen_range(0.0, 1.0);
+        let solve_time = (-(1.0 - sample).ln() * expect_time).round() as u64;
+        now += solve_time;
+        block_times.push(solve_time);
+
+        history.insert(
+            0,
+            BlockInfo {
+                timestamp: now,
+                target,
+            },
+        );
+        history.truncate(window);
+    }
+
+    let count = block_times.len().max(1) as f64;
+    let mean_block_time = block_times.iter().sum::<u64>() as f64 / count;
+    let variance = block_times
+        .iter()
+        .map(|time| (*time as f64 - mean_block_time).powi(2))
+        .sum::<f64>()
+        / count;
+    SimulationReport {
+        blocks,
+        mean_block_time,
+        block_time_std_dev: variance.sqrt(),
+        max_block_time: block_times.iter().copied().max().unwrap_or_default(),
+        slow_blocks: block_times
+            .iter()
+            .filter(|time| **time > 3 * block_time_target)
+            .count() as u64,
+        difficulty_error: difficulty_error / count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::DifficultyAlgorithm;
+
+    #[test]
+    fn test_difficulty_simulation() {
+        let curves = vec![
+            HashRateCurve::Constant(1000.0),
+            HashRateCurve::Step {
+                from: 1000.0,
+                to: 10000.0,
+                at: 20000,
+            },
+            HashRateCurve::Step {
+                from: 10000.0,
+                to: 1000.0,
+                at: 20000,
+            },
+            HashRateCurve::Square {
+                low: 1000.0,
+                high: 5000.0,
+                period: 3600,
+            },
+        ];
+        for algorithm in vec![
+            DifficultyAlgorithm::WindowedAverage,
+            DifficultyAlgorithm::Lwma,
+        ] {
+            let config = DifficultyConfig {
+                algorithm,
+                ..Default::default()
+            };
+            for curve in &curves {
+                let report = simulate(&config, curve, 2000, 1);
+                let target = config.block_time_target as f64;
+                assert!(
+                    report.mean_block_time > target / 2.0 && report.mean_block_time < target * 2.0,
+                    "{:?} {:?}: {}",
+                    algorithm,
+                    curve,
+                    report
+                );
+            }
+        }
+    }
+}
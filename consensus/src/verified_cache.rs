@@ -0,0 +1,93 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Caches of verified headers, so a header is not verified again when it is re-examined on
+//! fork switches, in the future block queue of sync, or after its proof of work has been checked
+//! in parallel during sync.
+//! A header id commits to the nonce, the difficulty and the parent chain, so the result of
+//! verifying a header never changes for the same id under the same rules, and only successes are
+//! cached. The rules are the network and the proof of work version of the node config, they are
+//! part of the key, so nodes of different networks or versions in one process, such as in tests,
+//! do not share results.
+
+use config::{ChainNetwork, NodeConfig};
+use crypto::HashValue;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use types::block::{BlockHeader, PowVersion};
+
+const VERIFIED_CACHE_SIZE: usize = 10_000;
+
+/// Headers passed `Consensus::verify`.
+pub static VERIFIED_HEADERS: Lazy<VerifiedHeaderCache> =
+    Lazy::new(|| VerifiedHeaderCache::new(VERIFIED_CACHE_SIZE));
+
+/// Headers passed `Consensus::verify_pow`, their difficulty is not checked yet.
+pub static POW_VERIFIED_HEADERS: Lazy<VerifiedHeaderCache> =
+    Lazy::new(|| VerifiedHeaderCache::new(VERIFIED_CACHE_SIZE));
+
+type CacheKey = (ChainNetwork, PowVersion, HashValue);
+
+pub struct VerifiedHeaderCache {
+    cache: Mutex<LruCache<CacheKey, ()>>,
+}
+
+impl VerifiedHeaderCache {
+    pub fn new(size: usize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(size)),
+        }
+    }
+
+    pub fn contains(&self, config: &NodeConfig, header: &BlockHeader) -> bool {
+        self.cache.lock().get(&Self::key(config, header)).is_some()
+    }
+
+    pub fn insert(&self, config: &NodeConfig, header: &BlockHeader) {
+        self.cache.lock().put(Self::key(config, header), ());
+    }
+
+    fn key(config: &NodeConfig, header: &BlockHeader) -> CacheKey {
+        (
+            config.net(),
+            config.pow_version(header.number()),
+            header.id(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verified_header_cache() {
+        let config = NodeConfig::random_for_test();
+        let cache = VerifiedHeaderCache::new(2);
+        let headers: Vec<BlockHeader> = (0..3).map(|_| BlockHeader::random()).collect();
+        cache.insert(&config, &headers[0]);
+        cache.insert(&config, &headers[1]);
+        assert!(cache.contains(&config, &headers[0]));
+        // headers[1] is the least recently used one now.
+        cache.insert(&config, &headers[2]);
+        assert!(cache.contains(&config, &headers[0]));
+        assert!(!cache.contains(&config, &headers[1]));
+        assert!(cache.contains(&config, &headers[2]));
+    }
+
+    #[test]
+    fn test_verified_header_cache_scope() {
+        let config = NodeConfig::random_for_test();
+        let cache = VerifiedHeaderCache::new(10);
+        let mut header = BlockHeader::random();
+        header.number = 10;
+        cache.insert(&config, &header);
+        assert!(cache.contains(&config, &header));
+
+        // another pow version of the same header.
+        let mut other_config = NodeConfig::random_for_test();
+        other_config.set_header_blob_pow_number(11);
+        assert!(!cache.contains(&other_config, &header));
+    }
+}
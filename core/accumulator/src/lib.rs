@@ -3,7 +3,7 @@
 
 use crate::node::AccumulatorStoreType;
 use crate::node_index::NodeIndex;
-use crate::proof::AccumulatorProof;
+pub use crate::proof::AccumulatorProof;
 use crate::tree::AccumulatorTree;
 use anyhow::{ensure, Error, Result};
 pub use node::AccumulatorNode;
@@ -1,12 +1,17 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use starcoin_crypto::HashValue;
 use starcoin_state_api::{ChainState, ChainStateReader};
 use starcoin_types::{
     account_address::AccountAddress,
-    block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate},
+    block::{
+        Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate,
+        MEDIAN_TIME_BLOCK_COUNT,
+    },
+    contract_event::ContractEventInfo,
+    filter::Filter,
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
     U256,
 };
@@ -18,6 +23,36 @@ pub struct ExcludedTxns {
     pub untouched_txns: Vec<SignedUserTransaction>,
 }
 
+/// The header reads of a chain, which are all `Consensus` needs to verify a new header, so a
+/// chain which only keeps headers can verify headers too.
+pub trait ChainHeaderReader {
+    /// Header of the chain head, a new header is verified as a child of it.
+    fn head_header(&self) -> BlockHeader;
+    fn find_header(&self, hash: HashValue) -> Result<Option<BlockHeader>>;
+
+    /// The median timestamp of the last `MEDIAN_TIME_BLOCK_COUNT` blocks until block `block_id`,
+    /// a child of the block must not be older than it.
+    /// Fewer blocks are used near genesis, or if the ancestors are not available.
+    fn median_timestamp(&self, block_id: HashValue) -> Result<u64> {
+        let mut timestamps = vec![];
+        let mut current = self
+            .find_header(block_id)?
+            .ok_or_else(|| format_err!("Can not find block header {:?}", block_id))?;
+        loop {
+            timestamps.push(current.timestamp());
+            if timestamps.len() as u64 >= MEDIAN_TIME_BLOCK_COUNT || current.number() == 0 {
+                break;
+            }
+            current = match self.find_header(current.parent_hash())? {
+                Some(parent) => parent,
+                None => break,
+            };
+        }
+        timestamps.sort_unstable();
+        Ok(timestamps[timestamps.len() / 2])
+    }
+}
+
 pub trait ChainReader {
     fn head_block(&self) -> Block;
     fn current_header(&self) -> BlockHeader;
@@ -43,11 +78,14 @@ pub trait ChainReader {
     fn get_block_info(&self, block_id: Option<HashValue>) -> Result<Option<BlockInfo>>;
     fn get_total_difficulty(&self) -> Result<U256>;
     fn exist_block(&self, block_id: HashValue) -> bool;
+    /// Get events match `filter` on current chain, in ascending order of block number.
+    fn get_events(&self, filter: &Filter) -> Result<Vec<ContractEventInfo>>;
 }
 
 pub trait ChainWriter {
-    /// execute and insert block to current chain.
-    fn apply(&mut self, block: Block) -> Result<bool>;
+    /// verify, execute and insert block to current chain.
+    /// Return a `BlockVerifyError` if the block is invalid.
+    fn apply(&mut self, block: Block) -> Result<()>;
     /// execute and insert block to current chain.
     fn commit(
         &mut self,
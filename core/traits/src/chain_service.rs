@@ -4,7 +4,11 @@
 use crate::ConnectResult;
 use anyhow::Result;
 use starcoin_crypto::HashValue;
+use starcoin_types::account_transaction::AccountTransactionInfo;
 use starcoin_types::block::BlockState;
+use starcoin_types::contract_event::ContractEventInfo;
+use starcoin_types::filter::Filter;
+use starcoin_types::proof::{BlockProof, TransactionProof};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::{Transaction, TransactionInfo};
 use starcoin_types::{
@@ -35,6 +39,12 @@ pub trait ChainService {
         block_id: HashValue,
         idx: u64,
     ) -> Result<Option<TransactionInfo>>;
+    /// Get the proof of the txn info at `txn_index` of block `block_id`.
+    fn get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> Result<Option<TransactionProof>>;
 
     /// for master
     fn master_head_header(&self) -> BlockHeader;
@@ -42,19 +52,33 @@ pub trait ChainService {
     fn master_block_by_number(&self, number: BlockNumber) -> Result<Option<Block>>;
     fn master_block_header_by_number(&self, number: BlockNumber) -> Result<Option<BlockHeader>>;
     fn master_startup_info(&self) -> StartupInfo;
+    /// Get the proof of master block at `number` against the block accumulator of master head.
+    fn master_block_proof(&self, number: BlockNumber) -> Result<Option<BlockProof>>;
     fn master_blocks_by_number(
         &self,
         number: Option<BlockNumber>,
         count: u64,
     ) -> Result<Vec<Block>>;
+    fn master_events(&self, filter: Filter) -> Result<Vec<ContractEventInfo>>;
+    /// Get transactions sent or received by `address` on master, from newest to oldest.
+    fn master_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>>;
+    /// Rewind master to its ancestor `block_id`, the abandoned blocks are kept as a branch.
+    /// Return the new master head.
+    fn rewind(&mut self, block_id: HashValue) -> Result<BlockHeader>;
 
-    /// just for test
+    /// just for test, `block_timestamp` defaults to the current time.
     fn create_block_template(
         &self,
         author: AccountAddress,
         auth_key_prefix: Option<Vec<u8>>,
         parent_hash: Option<HashValue>,
         user_txns: Vec<SignedUserTransaction>,
+        block_timestamp: Option<u64>,
     ) -> Result<BlockTemplate>;
 }
 
@@ -81,6 +105,11 @@ pub trait ChainAsyncService:
         block_id: HashValue,
         idx: u64,
     ) -> Result<Option<TransactionInfo>>;
+    async fn get_transaction_proof(
+        self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> Result<Option<TransactionProof>>;
 
     /// for master
     async fn master_head_header(self) -> Result<Option<BlockHeader>>;
@@ -93,14 +122,24 @@ pub trait ChainAsyncService:
     ) -> Result<Vec<Block>>;
     async fn master_block_header_by_number(self, number: BlockNumber) -> Result<BlockHeader>;
     async fn master_startup_info(self) -> Result<StartupInfo>;
+    async fn master_block_proof(self, number: BlockNumber) -> Result<Option<BlockProof>>;
     async fn master_head(self) -> Result<ChainInfo>;
+    async fn master_events(self, filter: Filter) -> Result<Vec<ContractEventInfo>>;
+    async fn master_transactions_by_account(
+        self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>>;
+    async fn rewind(self, block_id: HashValue) -> Result<BlockHeader>;
 
-    /// just for test
+    /// just for test, `block_timestamp` defaults to the current time.
     async fn create_block_template(
         self,
         author: AccountAddress,
         auth_key_prefix: Option<Vec<u8>>,
         parent_hash: Option<HashValue>,
         txs: Vec<SignedUserTransaction>,
+        block_timestamp: Option<u64>,
     ) -> Result<Option<BlockTemplate>>;
 }
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::ChainReader;
+use crate::ChainHeaderReader;
 use anyhow::Result;
 use starcoin_config::NodeConfig;
 use starcoin_types::{
@@ -20,28 +20,37 @@ pub trait ConsensusHeader:
 pub trait Consensus: std::marker::Unpin + Clone + Sync + Send {
     type ConsensusHeader: ConsensusHeader;
 
-    fn calculate_next_difficulty(config: Arc<NodeConfig>, reader: &dyn ChainReader)
-        -> Result<U256>;
+    fn calculate_next_difficulty(
+        config: Arc<NodeConfig>,
+        reader: &dyn ChainHeaderReader,
+    ) -> Result<U256>;
 
-    /// Calculate new block consensus header
-    // TODO use &HashValue to replace &[u8] for parent_hash
-    fn solve_consensus_header(parent_hash: &[u8], difficulty: U256) -> Self::ConsensusHeader;
+    /// Calculate new block consensus header, `pow_header` is the data to mine, see `BlockHeader::pow_header`.
+    fn solve_consensus_header(pow_header: &[u8], difficulty: U256) -> Self::ConsensusHeader;
 
     fn verify(
         config: Arc<NodeConfig>,
-        reader: &dyn ChainReader,
+        reader: &dyn ChainHeaderReader,
         header: &BlockHeader,
     ) -> Result<()>;
 
+    /// Verify the proof of work of the header against the difficulty in the header, it does not
+    /// read the chain, so the headers of a batch can be checked in parallel before they are applied.
+    /// `verify` still checks the difficulty.
+    fn verify_pow(_config: Arc<NodeConfig>, _header: &BlockHeader) -> Result<()> {
+        Ok(())
+    }
+
     /// Construct block with BlockTemplate, this a shortcut method for calculate_next_difficulty + solve_consensus_header
     fn create_block(
         config: Arc<NodeConfig>,
-        reader: &dyn ChainReader,
+        reader: &dyn ChainHeaderReader,
         block_template: BlockTemplate,
     ) -> Result<Block> {
-        let difficulty = Self::calculate_next_difficulty(config, reader)?;
+        let difficulty = Self::calculate_next_difficulty(config.clone(), reader)?;
+        let version = config.pow_version(block_template.number);
         let consensus_header = Self::solve_consensus_header(
-            block_template.parent_hash.to_vec().as_slice(),
+            &block_template.pow_header(difficulty, version),
             difficulty,
         );
         Ok(block_template.into_block(consensus_header, difficulty))
@@ -5,9 +5,10 @@ mod chain;
 mod chain_service;
 mod consensus;
 
-pub use chain::{Chain, ChainReader, ChainWriter, ExcludedTxns};
+pub use chain::{Chain, ChainHeaderReader, ChainReader, ChainWriter, ExcludedTxns};
 pub use chain_service::{ChainAsyncService, ChainService};
 pub use consensus::{Consensus, ConsensusHeader};
+use starcoin_crypto::HashValue;
 use thiserror::Error;
 
 pub type ConnectResult<T> = anyhow::Result<T, ConnectBlockError>;
@@ -18,12 +19,45 @@ pub enum ConnectBlockError {
     DuplicateConn,
     #[error("parent block not exist.")]
     FutureBlock,
-    #[error("block verify failed.")]
-    VerifyFailed,
+    #[error("block verify failed: {0}")]
+    VerifyFailed(BlockVerifyError),
     #[error("connect failed, cause : {0:?}")]
     Other(String),
 }
 
+/// The rule a block breaks, `expect` is the value computed by the local node.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum BlockVerifyError {
+    #[error("parent hash {got:?} is not the chain head {expect:?}")]
+    ParentMismatch { expect: HashValue, got: HashValue },
+    #[error("timestamp {got} is older than the min timestamp {min}")]
+    TimestampTooOld { min: u64, got: u64 },
+    #[error("timestamp {got} is too far in the future, now is {now}")]
+    TimestampTooNew { now: u64, got: u64 },
+    #[error("gas used {gas_used} exceeds gas limit {gas_limit}")]
+    GasUsedExceedsLimit { gas_used: u64, gas_limit: u64 },
+    #[error("consensus verify failed: {0}")]
+    Consensus(String),
+    #[error("state root {got:?} mismatch, expect {expect:?}")]
+    StateRootMismatch { expect: HashValue, got: HashValue },
+    #[error("gas used {got} mismatch, expect {expect}")]
+    GasUsedMismatch { expect: u64, got: u64 },
+    #[error("txn count {got} mismatch, expect {expect}")]
+    TxnCountMismatch { expect: usize, got: usize },
+    #[error("txn accumulator root {got:?} mismatch, expect {expect:?}")]
+    AccumulatorRootMismatch { expect: HashValue, got: HashValue },
+    #[error("block {got:?} at checkpoint {number} mismatch, expect {expect:?}")]
+    CheckpointMismatch {
+        number: u64,
+        expect: HashValue,
+        got: HashValue,
+    },
+    #[error("fork from block {fork_number} conflicts with checkpoint {checkpoint}")]
+    CheckpointConflict { checkpoint: u64, fork_number: u64 },
+    #[error("reorg depth {depth} exceeds the max reorg depth {max}")]
+    ReorgTooDeep { depth: u64, max: u64 },
+}
+
 pub fn is_ok<T>(conn_result: &ConnectResult<T>) -> bool {
     conn_result.is_ok()
         || if let Err(ConnectBlockError::DuplicateConn) = conn_result {
@@ -3,11 +3,21 @@
 
 use anyhow::Result;
 use starcoin_types::{
-    transaction::{SignedUserTransaction, Transaction, TransactionOutput},
+    account_address::AccountAddress,
+    transaction::{
+        Script, SignedUserTransaction, Transaction, TransactionArgument, TransactionOutput,
+    },
     vm_error::VMStatus,
 };
-use starcoin_vm_types::state_view::StateView;
-use vm_runtime::{metrics::TXN_EXECUTION_HISTOGRAM, starcoin_vm::StarcoinVM};
+use starcoin_vm_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, TypeTag},
+    state_view::StateView,
+};
+use vm_runtime::{
+    metrics::TXN_EXECUTION_HISTOGRAM,
+    starcoin_vm::{ReadonlyFunctionOutput, StarcoinVM},
+};
 
 pub fn execute_transactions(
     chain_state: &dyn StateView,
@@ -50,3 +60,62 @@ pub fn validate_transaction(
     timer.observe_duration();
     result
 }
+
+/// Execute a user transaction against `chain_state` without committing the output.
+pub fn dry_run(
+    chain_state: &dyn StateView,
+    txn: SignedUserTransaction,
+    check_signature: bool,
+) -> TransactionOutput {
+    let timer = TXN_EXECUTION_HISTOGRAM
+        .with_label_values(&["dry_run"])
+        .start_timer();
+    let mut vm = StarcoinVM::new();
+    let result = vm.dry_run_transaction(chain_state, txn, check_signature);
+    timer.observe_duration();
+    result
+}
+
+/// Call a public Move function by `sender` against `chain_state`, the write set of the call is
+/// discarded.
+pub fn execute_readonly_function(
+    chain_state: &dyn StateView,
+    sender: AccountAddress,
+    module: &ModuleId,
+    function_name: &IdentStr,
+    type_args: Vec<TypeTag>,
+    args: &[TransactionArgument],
+    max_gas_amount: u64,
+) -> ReadonlyFunctionOutput {
+    let timer = TXN_EXECUTION_HISTOGRAM
+        .with_label_values(&["execute_readonly_function"])
+        .start_timer();
+    let mut vm = StarcoinVM::new();
+    let result = vm.execute_readonly_function(
+        chain_state,
+        sender,
+        module,
+        function_name,
+        type_args,
+        args,
+        max_gas_amount,
+    );
+    timer.observe_duration();
+    result
+}
+
+/// Execute a script by `sender` against `chain_state`, the write set of the script is discarded.
+pub fn execute_readonly_script(
+    chain_state: &dyn StateView,
+    sender: AccountAddress,
+    script: &Script,
+    max_gas_amount: u64,
+) -> ReadonlyFunctionOutput {
+    let timer = TXN_EXECUTION_HISTOGRAM
+        .with_label_values(&["execute_readonly_script"])
+        .start_timer();
+    let mut vm = StarcoinVM::new();
+    let result = vm.execute_readonly_script(chain_state, sender, script, max_gas_amount);
+    timer.observe_duration();
+    result
+}
@@ -11,7 +11,7 @@ use starcoin_functional_tests::account::{
 use starcoin_genesis::Genesis;
 use starcoin_state_api::{AccountStateReader, ChainState, ChainStateReader, ChainStateWriter};
 use starcoin_transaction_builder::{
-    build_upgrade_package, create_signed_txn_with_association_account,
+    build_upgrade_package, create_signed_txn_with_association_account, encode_transfer_script,
 };
 use starcoin_types::language_storage::CORE_CODE_ADDRESS;
 use starcoin_types::transaction::TransactionOutput;
@@ -24,6 +24,7 @@ use starcoin_types::{
     transaction::{Module, TransactionPayload},
     vm_error::{StatusCode, VMStatus},
 };
+use starcoin_vm_types::identifier::Identifier;
 use starcoin_vm_types::parser;
 use statedb::ChainStateDB;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -472,6 +473,73 @@ fn test_stdlib_upgrade() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_execute_readonly_function() -> Result<()> {
+    let chain_state = prepare_genesis();
+    let state_root = chain_state.state_root();
+
+    let output = crate::execute_readonly_function(
+        &chain_state,
+        AccountAddress::default(),
+        &account_config::BLOCK_MODULE,
+        &Identifier::new("get_current_block_height")?,
+        vec![],
+        &[],
+        1_000_000,
+    );
+    assert_eq!(output.status, VMStatus::new(StatusCode::EXECUTED));
+    assert!(output.gas_used > 0);
+
+    let output = crate::execute_readonly_function(
+        &chain_state,
+        AccountAddress::default(),
+        &account_config::BLOCK_MODULE,
+        &Identifier::new("not_exist")?,
+        vec![],
+        &[],
+        1_000_000,
+    );
+    assert_eq!(
+        output.status.major_status,
+        StatusCode::FUNCTION_RESOLUTION_FAILURE
+    );
+
+    // non-public functions are rejected before the call.
+    let output = crate::execute_readonly_function(
+        &chain_state,
+        AccountAddress::default(),
+        &account_config::BLOCK_MODULE,
+        &Identifier::new("process_block_prologue")?,
+        vec![],
+        &[],
+        1_000_000,
+    );
+    assert_eq!(output.status.major_status, StatusCode::VISIBILITY_MISMATCH);
+    assert_eq!(output.gas_used, 0);
+    assert_eq!(chain_state.state_root(), state_root);
+    Ok(())
+}
+
+#[stest::test]
+fn test_execute_readonly_script() -> Result<()> {
+    let chain_state = prepare_genesis();
+    let state_root = chain_state.state_root();
+    let account = Account::new();
+    let script = encode_transfer_script(account.address(), account.auth_key_prefix(), 1000);
+
+    let output = crate::execute_readonly_script(
+        &chain_state,
+        account_config::association_address(),
+        &script,
+        1_000_000,
+    );
+    assert_eq!(output.status, VMStatus::new(StatusCode::EXECUTED));
+    assert!(output.gas_used > 0);
+    assert_eq!(chain_state.state_root(), state_root);
+    assert_eq!(get_balance(*account.address(), &chain_state), 0);
+    Ok(())
+}
+
 fn get_token_balance(
     address: AccountAddress,
     state_db: &dyn ChainStateReader,
@@ -9,6 +9,7 @@ pub use starcoin_transaction_builder::{
     encode_create_account_script, encode_transfer_script, peer_to_peer_txn_sent_as_association,
     TXN_RESERVED,
 };
+pub use vm_runtime::starcoin_vm::ReadonlyFunctionOutput;
 
 mod block_executor;
 mod executor;
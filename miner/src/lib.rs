@@ -88,6 +88,9 @@ where
                         panic!("Incompatible consensus strategy");
                     }
                 },
+                PacemakerStrategy::Manual => {
+                    info!("No pacemaker, blocks are mined manually.");
+                }
             };
 
             let miner = miner::Miner::new(bus.clone(), config.clone());
@@ -146,8 +149,7 @@ where
         let miner = self.miner.clone();
         let stratum = self.stratum.clone();
         let miner_account = self.miner_account.clone();
-        // block_gas_limit / min_gas_per_txn
-        let max_txns = self.config.miner.block_gas_limit / 600;
+        let max_txns = self.config.miner.max_block_txns();
         let f = async move {
             let txns = txpool.get_pending_txns(Some(max_txns));
             let startup_info = chain.master_startup_info().await?;
@@ -6,7 +6,6 @@ use actix::prelude::*;
 use anyhow::Result;
 use bus::{Broadcast, BusActor};
 use config::NodeConfig;
-use crypto::HashValue;
 use logger::prelude::*;
 use starcoin_metrics::HistogramTimer;
 use std::convert::TryFrom;
@@ -14,7 +13,11 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::sync::Mutex;
 use traits::Consensus;
-use types::{block::BlockTemplate, system_events::MinedBlock, U256};
+use types::{
+    block::{BlockTemplate, PowVersion},
+    system_events::MinedBlock,
+    U256,
+};
 
 #[derive(Clone)]
 pub struct Miner<C>
@@ -28,15 +31,15 @@ where
 }
 
 pub struct MineCtx {
-    header_hash: HashValue,
+    pow_header: Vec<u8>,
     block_template: BlockTemplate,
     difficulty: U256,
     metrics_timer: Option<HistogramTimer>,
 }
 
 impl MineCtx {
-    pub fn new(block_template: BlockTemplate, difficulty: U256) -> MineCtx {
-        let header_hash = block_template.parent_hash;
+    pub fn new(block_template: BlockTemplate, difficulty: U256, version: PowVersion) -> MineCtx {
+        let pow_header = block_template.pow_header(difficulty, version);
         let metrics_timer = Some(
             MINER_METRICS
                 .block_mint_time
@@ -44,7 +47,7 @@ impl MineCtx {
                 .start_timer(),
         );
         MineCtx {
-            header_hash,
+            pow_header,
             block_template,
             difficulty,
             metrics_timer,
@@ -72,7 +75,7 @@ where
     pub fn get_mint_job(&mut self) -> String {
         let state = self.state.lock().unwrap();
         let x = state.as_ref().unwrap().to_owned();
-        format!(r#"["{:x}","{:x}"]"#, x.header_hash, x.difficulty)
+        format!(r#"["{}","{:x}"]"#, hex::encode(&x.pow_header), x.difficulty)
     }
 
     pub fn submit(&self, payload: String) -> Result<()> {
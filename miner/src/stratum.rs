@@ -6,7 +6,7 @@ use config::NodeConfig;
 use logger::prelude::*;
 use sc_stratum::*;
 use std::sync::Arc;
-use traits::{ChainReader, Consensus};
+use traits::{ChainHeaderReader, Consensus};
 use types::block::BlockTemplate;
 pub struct StratumManager<C>
 where
@@ -39,14 +39,15 @@ pub fn mint<C>(
     stratum: Arc<Stratum>,
     mut miner: Miner<C>,
     config: Arc<NodeConfig>,
-    chain: &dyn ChainReader,
+    chain: &dyn ChainHeaderReader,
     block_template: BlockTemplate,
 ) -> anyhow::Result<()>
 where
     C: Consensus,
 {
-    let difficulty = C::calculate_next_difficulty(config, chain)?;
-    miner.set_mint_job(MineCtx::new(block_template, difficulty));
+    let difficulty = C::calculate_next_difficulty(config.clone(), chain)?;
+    let version = config.pow_version(block_template.number);
+    miner.set_mint_job(MineCtx::new(block_template, difficulty, version));
     let job = miner.get_mint_job();
     debug!("Push job to worker {}", job);
     if let Err(e) = stratum.push_work_all(job) {
@@ -53,6 +53,7 @@ fn test_miner_with_schedule_pacemaker() {
             let best_block_id = *startup_info.get_master();
             TxPool::start(
                 config.tx_pool.clone(),
+                config.time_service(),
                 storage.clone(),
                 best_block_id,
                 bus.clone(),
@@ -148,6 +149,7 @@ fn test_miner_with_ondemand_pacemaker() {
             let best_block_id = *startup_info.get_master();
             TxPool::start(
                 config.tx_pool.clone(),
+                config.time_service(),
                 storage.clone(),
                 best_block_id,
                 bus.clone(),
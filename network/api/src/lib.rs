@@ -1,6 +1,7 @@
 use crate::messages::PeerMessage;
 use anyhow::*;
 pub use libp2p::PeerId;
+pub use peerset::ReputationChange;
 use starcoin_types::system_events::NewHeadBlock;
 use std::time::Duration;
 
@@ -47,6 +48,10 @@ pub trait NetworkService: Send + Sync + Clone + Sized + std::marker::Unpin {
 
     async fn get_peer_set_size(&self) -> Result<usize>;
 
+    /// Report a given peer as either beneficial (+) or costly (-) according to the
+    /// given scalar.
+    fn report_peer(&self, peer_id: PeerId, cost_benefit: ReputationChange);
+
     async fn register_rpc_proto(
         &self,
         proto_name: Cow<'static, [u8]>,
@@ -124,6 +129,8 @@ impl NetworkService for DummyNetworkService {
         Ok(0)
     }
 
+    fn report_peer(&self, _peer_id: PeerId, _cost_benefit: ReputationChange) {}
+
     async fn register_rpc_proto(
         &self,
         _proto_name: Cow<'static, [u8]>,
@@ -9,6 +9,7 @@ use config::NetworkConfig;
 use crypto::hash::HashValue;
 use futures::{channel::mpsc, prelude::*};
 use libp2p::PeerId;
+use network_api::ReputationChange;
 use network_p2p::{
     identity, Event, Multiaddr, NetworkConfiguration, NetworkService, NetworkWorker, NodeKeyConfig,
     Params, Secret, PROTOCOL_NAME,
@@ -147,6 +148,10 @@ impl SNetworkService {
         self.service.connected_peers().await
     }
 
+    pub fn report_peer(&self, peer_id: PeerId, cost_benefit: ReputationChange) {
+        self.service.report_peer(peer_id, cost_benefit);
+    }
+
     pub fn update_self_info(&self, info: PeerInfo) {
         self.service.update_self_info(info);
     }
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::helper::{get_unix_ts, is_global};
+use crate::helper::is_global;
 use crate::message_processor::{MessageFuture, MessageProcessor};
 use crate::net::{build_network_service, SNetworkService};
 use crate::network_metrics::NetworkMetrics;
@@ -22,16 +22,17 @@ use futures_timer::Delay;
 use libp2p::multiaddr::Protocol;
 use libp2p::PeerId;
 use lru::LruCache;
-use network_api::{messages::RawRpcRequestMessage, NetworkService};
+use network_api::{messages::RawRpcRequestMessage, NetworkService, ReputationChange};
 use network_p2p::Multiaddr;
 use scs::SCSCodec;
 use starcoin_block_relayer_api::{NetCmpctBlockMessage, PeerCmpctBlockEvent};
 use starcoin_sync_api::PeerNewBlock;
+use starcoin_time_service::TimeService;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::path::Path;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicU64};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::runtime::Handle;
@@ -68,6 +69,7 @@ struct Inner {
     node_config: Arc<NodeConfig>,
     peer_id: PeerId,
     rpc_tx: mpsc::UnboundedSender<RawRpcRequestMessage>,
+    request_seq: AtomicU64,
 }
 
 #[derive(Debug)]
@@ -144,7 +146,7 @@ impl NetworkService for NetworkAsyncService {
         message: Vec<u8>,
         time_out: Duration,
     ) -> Result<Vec<u8>> {
-        let request_id = get_unix_ts();
+        let request_id = self.inner.next_request_id();
         let peer_msg = PeerMessage::RawRPCRequest(request_id, rpc_path, message);
         let data = peer_msg.encode()?;
         self.network_service
@@ -236,6 +238,10 @@ impl NetworkService for NetworkAsyncService {
         Ok(size)
     }
 
+    fn report_peer(&self, peer_id: PeerId, cost_benefit: ReputationChange) {
+        self.network_service.report_peer(peer_id, cost_benefit);
+    }
+
     async fn register_rpc_proto(
         &self,
         proto_name: Cow<'static, [u8]>,
@@ -350,6 +356,7 @@ impl NetworkActor {
             node_config,
             peer_id: peer_id.clone(),
             rpc_tx,
+            request_seq: AtomicU64::new(0),
         };
         let inner = Arc::new(inner);
         handle.spawn(Self::start(
@@ -414,6 +421,14 @@ impl NetworkActor {
 }
 
 impl Inner {
+    /// A new id of rpc request, the time of the node clock keeps it unique across restarts,
+    /// and the sequence keeps it unique in the same millisecond.
+    fn next_request_id(&self) -> u128 {
+        let now_millis = self.node_config.time_service().now_millis() as u128;
+        let seq = self.request_seq.fetch_add(1, Ordering::SeqCst) as u128;
+        (now_millis << 64) | seq
+    }
+
     async fn handle_network_receive(inner: Arc<Inner>, network_msg: NetworkMessage) -> Result<()> {
         debug!("receive network_message ");
         // decode msg based on protocol name.
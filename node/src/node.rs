@@ -20,6 +20,7 @@ use starcoin_state_service::ChainStateActor;
 use starcoin_storage::block_info::BlockInfoStore;
 use starcoin_storage::cache_storage::CacheStorage;
 use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::recovery::recover_startup_info;
 use starcoin_storage::{storage::StorageInstance, BlockStore, Storage};
 use starcoin_sync::SyncActor;
 use starcoin_traits::Consensus;
@@ -90,12 +91,25 @@ where
 
     let sync_event_receiver_future = bus.clone().channel::<SyncDone>();
     debug!("init storage.");
-    let cache_storage = Arc::new(CacheStorage::new());
-    let db_storage = Arc::new(DBStorage::new(config.storage.clone().dir()));
-    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
-        cache_storage.clone(),
-        db_storage.clone(),
-    ))?);
+    let cache_storage = Arc::new(CacheStorage::new_with_config(&config.storage));
+    let db_storage = match DBStorage::open_with_config(
+        config.storage.dir().join("starcoindb"),
+        false,
+        &config.storage,
+    ) {
+        Ok(db_storage) => Arc::new(db_storage),
+        Err(e) => {
+            error!(
+                "Open db storage fail: {:?}, please check or clean data_dir.",
+                e
+            );
+            std::process::exit(EXIT_CODE_NEED_HELP);
+        }
+    };
+    let storage = Arc::new(Storage::new_with_state_pruning(
+        StorageInstance::new_cache_and_db_instance(cache_storage.clone(), db_storage.clone()),
+        !config.storage.archive(),
+    )?);
     debug!("load startup_info.");
     let (startup_info, genesis_hash) = match storage.get_startup_info() {
         Ok(Some(startup_info)) => {
@@ -116,6 +130,16 @@ where
                     std::process::exit(EXIT_CODE_NEED_HELP);
                 }
             }
+            let startup_info = match recover_startup_info(storage.as_ref(), startup_info) {
+                Ok(startup_info) => startup_info,
+                Err(e) => {
+                    error!(
+                        "Recover startup info fail: {:?}, please check or clean data_dir.",
+                        e
+                    );
+                    std::process::exit(EXIT_CODE_NEED_HELP);
+                }
+            };
             (startup_info, genesis.block().header().id())
         }
         Ok(None) => {
@@ -169,6 +193,7 @@ where
 
     let txpool = TxPool::start(
         config.tx_pool.clone(),
+        config.time_service(),
         storage.clone(),
         head_block_hash,
         bus.clone(),
@@ -317,6 +342,8 @@ where
         chain_state_service,
         Some(pubsub_service),
         Some(network),
+        Some(db_storage),
+        Some(storage),
         Some(logger_handle),
     )?;
 
@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: Apache-2
 
 pub use self::gen_client::Client as ChainClient;
+use crate::types::event::Event;
+use crate::types::pubsub::EventFilter;
 use crate::FutureResult;
 use jsonrpc_derive::rpc;
 use starcoin_crypto::HashValue;
-use starcoin_types::block::{Block, BlockNumber};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_transaction::AccountTransactionInfo;
+use starcoin_types::block::{Block, BlockHeader, BlockNumber};
+use starcoin_types::proof::{BlockProof, TransactionProof};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::{Transaction, TransactionInfo};
 
@@ -20,6 +25,12 @@ pub trait ChainApi {
     /// Get chain blocks by number
     #[rpc(name = "chain.get_block_by_number")]
     fn get_block_by_number(&self, number: BlockNumber) -> FutureResult<Block>;
+    /// Get block header by hash, the block may be not on master.
+    #[rpc(name = "chain.get_header_by_hash")]
+    fn get_header_by_hash(&self, hash: HashValue) -> FutureResult<BlockHeader>;
+    /// Get block header of master chain by number.
+    #[rpc(name = "chain.get_header_by_number")]
+    fn get_header_by_number(&self, number: BlockNumber) -> FutureResult<BlockHeader>;
     /// Get latest `count` blocks before `number`. if `number` is absent, use head block number.
     #[rpc(name = "chain.get_blocks_by_number")]
     fn get_blocks_by_number(
@@ -46,4 +57,32 @@ pub trait ChainApi {
     /// Get branches of current chain, first is master.
     #[rpc(name = "chain.branches")]
     fn branches(&self) -> FutureResult<Vec<ChainInfo>>;
+
+    /// Get events of master chain by `filter`.
+    #[rpc(name = "chain.get_events")]
+    fn get_events(&self, filter: EventFilter) -> FutureResult<Vec<Event>>;
+
+    /// Get at most `limit` transactions sent or received by `address` on master chain,
+    /// from newest to oldest, starting at history index `start`. if `start` is absent, start from the newest one.
+    #[rpc(name = "chain.get_transactions_by_account")]
+    fn get_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> FutureResult<Vec<AccountTransactionInfo>>;
+
+    /// Get the proof of the txn info at `txn_index` of block `block_id`, against the
+    /// `accumulator_root` of the block header.
+    #[rpc(name = "chain.get_transaction_proof")]
+    fn get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> FutureResult<Option<TransactionProof>>;
+
+    /// Get the proof of master block at `number`, against the block accumulator root of the
+    /// `BlockInfo` of master head.
+    #[rpc(name = "chain.get_block_proof")]
+    fn get_block_proof(&self, number: BlockNumber) -> FutureResult<Option<BlockProof>>;
 }
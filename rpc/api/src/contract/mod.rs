@@ -0,0 +1,49 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+use crate::txpool::VMStatusView;
+use crate::FutureResult;
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::language_storage::{ModuleId, TypeTag};
+use starcoin_types::transaction::{Script, TransactionArgument};
+
+pub use self::gen_client::Client as ContractClient;
+
+#[rpc]
+pub trait ContractApi {
+    /// Call a public function or execute a script against the state of current head block,
+    /// the write set is discarded.
+    #[rpc(name = "contract.call")]
+    fn call(&self, call: ContractCall) -> FutureResult<ContractCallResult>;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ContractCallPayload {
+    /// Call a public function of a published module.
+    Function {
+        module: ModuleId,
+        function_name: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<TransactionArgument>,
+    },
+    /// Execute a script, it must be allowed by the on chain publishing option.
+    Script(Script),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContractCall {
+    /// The sender of the call, defaults to the zero address which has no privilege.
+    pub sender: Option<AccountAddress>,
+    pub payload: ContractCallPayload,
+    pub max_gas_amount: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ContractCallResult {
+    pub status: VMStatusView,
+    pub gas_used: u64,
+    /// Always `None`, the MoveVM of current libra rev does not return the values of a call.
+    pub return_values: Option<Vec<Vec<u8>>>,
+}
@@ -0,0 +1,18 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+use crate::FutureResult;
+use jsonrpc_derive::rpc;
+use starcoin_crypto::HashValue;
+use starcoin_types::block::BlockHeader;
+
+pub use self::gen_client::Client as IpcDebugClient;
+
+/// Debug methods which change the chain, they are only served over IPC.
+#[rpc]
+pub trait IpcDebugApi {
+    /// Rewind master chain to its ancestor `block_id`, return the new head.
+    /// The abandoned blocks are kept as a branch, and their transactions are returned to txpool.
+    #[rpc(name = "debug.rewind")]
+    fn rewind(&self, block_id: HashValue) -> FutureResult<BlockHeader>;
+}
@@ -1,11 +1,18 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+use crate::FutureResult;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
 use starcoin_logger::LogPattern;
+use starcoin_types::block::BlockNumber;
 
 pub use self::gen_client::Client as DebugClient;
+pub use self::ipc::{IpcDebugApi, IpcDebugClient};
+
+mod ipc;
 
 #[rpc]
 pub trait DebugApi {
@@ -20,4 +27,47 @@ pub trait DebugApi {
     ///Trigger the node panic, only work for dev network.
     #[rpc(name = "debug.panic")]
     fn panic(&self) -> Result<()>;
+
+    /// Execute the block again on the state of its parent block, and diff the result with the
+    /// stored txn infos of the block.
+    #[rpc(name = "debug.replay_block")]
+    fn replay_block(&self, block_id: HashValue) -> Result<BlockReplayReport>;
+
+    /// Replay the master blocks with number in [from, to], only the mismatched blocks are
+    /// reported. At most 100 blocks can be replayed at once.
+    #[rpc(name = "debug.replay_blocks")]
+    fn replay_blocks(&self, from: BlockNumber, to: BlockNumber) -> FutureResult<RangeReplayReport>;
+}
+
+/// A value which is different between the stored data and the replayed result.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReplayDiff {
+    /// Index of the txn in the block, the block metadata txn is 0.
+    /// `None` if it is a field of the block.
+    pub txn_index: Option<u64>,
+    pub field: String,
+    pub stored: String,
+    pub replayed: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockReplayReport {
+    pub block_id: HashValue,
+    pub number: BlockNumber,
+    pub diffs: Vec<ReplayDiff>,
+    /// Set if the block can not be executed.
+    pub error: Option<String>,
+}
+
+impl BlockReplayReport {
+    pub fn is_ok(&self) -> bool {
+        self.diffs.is_empty() && self.error.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RangeReplayReport {
+    pub from: BlockNumber,
+    pub to: BlockNumber,
+    pub mismatched: Vec<BlockReplayReport>,
 }
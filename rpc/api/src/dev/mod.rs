@@ -0,0 +1,17 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+pub use self::gen_client::Client as DevClient;
+use crate::FutureResult;
+use jsonrpc_derive::rpc;
+use starcoin_types::startup_info::ChainInfo;
+
+/// Apis only registered on the dev network.
+#[rpc]
+pub trait DevApi {
+    /// Mine `blocks` blocks on the master head with the pending txns of txpool, and return the new head.
+    /// If `timestamp` is present, it is the timestamp(in seconds) of the new blocks, which must
+    /// still pass the timestamp check of the chain.
+    #[rpc(name = "dev.mine_blocks")]
+    fn mine_blocks(&self, blocks: u64, timestamp: Option<u64>) -> FutureResult<ChainInfo>;
+}
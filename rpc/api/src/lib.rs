@@ -6,7 +6,9 @@ use jsonrpc_core::Error;
 pub type FutureResult<T> = Box<dyn jsonrpc_core::futures::Future<Item = T, Error = Error> + Send>;
 
 pub mod chain;
+pub mod contract;
 pub mod debug;
+pub mod dev;
 pub mod errors;
 pub mod node;
 pub mod pubsub;
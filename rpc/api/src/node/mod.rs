@@ -29,6 +29,25 @@ impl NodeInfo {
     }
 }
 
+/// Statistics of a RocksDB column family.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ColumnFamilyStats {
+    pub name: String,
+    /// Estimated number of keys.
+    pub estimate_num_keys: u64,
+    /// Total size in bytes of all SST files.
+    pub total_sst_files_size: u64,
+    /// Size in bytes of all memtables.
+    pub mem_tables_size: u64,
+    /// RocksDB internal stats, one line per item.
+    pub internal_stats: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StorageStats {
+    pub column_families: Vec<ColumnFamilyStats>,
+}
+
 #[rpc]
 pub trait NodeApi {
     /// Get node run status, just for api available check.
@@ -45,4 +64,8 @@ pub trait NodeApi {
 
     #[rpc(name = "node.metrics")]
     fn metrics(&self) -> Result<HashMap<String, String>>;
+
+    /// Get storage statistics of each column family.
+    #[rpc(name = "node.storage_stats")]
+    fn storage_stats(&self) -> Result<StorageStats>;
 }
@@ -1,9 +1,19 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+use crate::types::event::Event;
 use crate::FutureResult;
+use anyhow::Result;
 use jsonrpc_derive::rpc;
-use starcoin_types::transaction::SignedUserTransaction;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use starcoin_types::access_path::DataType;
+use starcoin_types::transaction::{
+    RawUserTransaction, SignedUserTransaction, TransactionOutput, TransactionStatus,
+};
+use starcoin_types::vm_error::{StatusCode, VMStatus};
+use starcoin_types::write_set::WriteOp;
+use std::convert::TryFrom;
 
 pub use self::gen_client::Client as TxPoolClient;
 use starcoin_types::account_address::AccountAddress;
@@ -17,4 +27,137 @@ pub trait TxPoolApi {
     /// or `None` if there are no pending transactions from that sender in txpool.
     #[rpc(name = "txpool.next_sequence_number")]
     fn next_sequence_number(&self, address: AccountAddress) -> FutureResult<Option<u64>>;
+
+    /// Execute the txn against the state of current head block, the output is not committed.
+    #[rpc(name = "txpool.dry_run")]
+    fn dry_run(&self, txn: DryRunTransaction) -> FutureResult<DryRunOutput>;
+
+    /// Returns the max gas amount to execute the txn, that is the gas used by a dry run plus a
+    /// safety margin. The max gas amount of the dry run is capped at what the sender can pay by
+    /// its balance. Returns an error if the txn can not be executed successfully.
+    #[rpc(name = "txpool.estimate_gas")]
+    fn estimate_gas(&self, txn: DryRunTransaction) -> FutureResult<u64>;
+
+    /// Suggest a gas price from the txns of recent blocks and the minimal gas price of txpool.
+    #[rpc(name = "txpool.gas_price")]
+    fn gas_price(&self) -> FutureResult<u64>;
+}
+
+/// The percent of gas used added by `estimate_gas`, in case the state is changed before the
+/// txn is executed.
+pub const GAS_ESTIMATE_MARGIN_PERCENT: u64 = 20;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum DryRunTransaction {
+    /// The signature of the txn is checked.
+    Signed(SignedUserTransaction),
+    /// The txn is not signed yet, the `public_key` of sender is required by the prologue
+    /// to check the authentication key.
+    Unsigned {
+        raw_txn: RawUserTransaction,
+        public_key: Ed25519PublicKey,
+    },
+}
+
+impl DryRunTransaction {
+    /// Returns the txn to execute, and whether its signature should be checked.
+    pub fn into_signed_txn(self) -> Result<(SignedUserTransaction, bool)> {
+        match self {
+            DryRunTransaction::Signed(txn) => Ok((txn, true)),
+            DryRunTransaction::Unsigned {
+                raw_txn,
+                public_key,
+            } => {
+                // a placeholder, the signature is not checked.
+                let signature = Ed25519Signature::try_from(&[0u8; 64][..])?;
+                Ok((
+                    SignedUserTransaction::new(raw_txn, public_key, signature),
+                    false,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VMStatusView {
+    pub major_status: StatusCode,
+    pub sub_status: Option<u64>,
+    pub message: Option<String>,
+}
+
+impl From<VMStatus> for VMStatusView {
+    fn from(status: VMStatus) -> Self {
+        Self {
+            major_status: status.major_status,
+            sub_status: status.sub_status,
+            message: status.message,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransactionStatusView {
+    /// The txn would be included in a block.
+    Keep(VMStatusView),
+    /// The txn would be discarded, it has no effect on the state.
+    Discard(VMStatusView),
+}
+
+impl From<TransactionStatus> for TransactionStatusView {
+    fn from(status: TransactionStatus) -> Self {
+        match status {
+            TransactionStatus::Keep(status) => TransactionStatusView::Keep(status.into()),
+            TransactionStatus::Discard(status) => TransactionStatusView::Discard(status.into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WriteSetEntry {
+    pub address: AccountAddress,
+    /// `None` if the path does not start with a known data type.
+    pub data_type: Option<DataType>,
+    pub path: Vec<u8>,
+    /// `None` if the value is deleted.
+    pub value: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DryRunOutput {
+    pub status: TransactionStatusView,
+    pub gas_used: u64,
+    pub write_set: Vec<WriteSetEntry>,
+    pub events: Vec<Event>,
+}
+
+impl From<TransactionOutput> for DryRunOutput {
+    fn from(output: TransactionOutput) -> Self {
+        let (write_set, events, gas_used, status) = output.into_inner();
+        let write_set = write_set
+            .iter()
+            .map(|(access_path, op)| WriteSetEntry {
+                address: access_path.address,
+                data_type: access_path
+                    .path
+                    .first()
+                    .and_then(|data_type| DataType::try_from(*data_type).ok()),
+                path: access_path.path.clone(),
+                value: match op {
+                    WriteOp::Value(value) => Some(value.clone()),
+                    WriteOp::Deletion => None,
+                },
+            })
+            .collect();
+        let events = events
+            .iter()
+            .map(|event| Event::new(None, None, None, None, event))
+            .collect();
+        Self {
+            status: status.into(),
+            gas_used,
+            write_set,
+            events,
+        }
+    }
 }
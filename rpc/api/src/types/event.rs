@@ -1,7 +1,7 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use starcoin_crypto::HashValue;
 use starcoin_types::block::BlockNumber;
-use starcoin_types::contract_event::ContractEvent;
+use starcoin_types::contract_event::{ContractEvent, ContractEventInfo};
 use starcoin_types::event::EventKey;
 use starcoin_types::language_storage::TypeTag;
 use std::convert::TryFrom;
@@ -23,6 +23,9 @@ pub struct Event {
     )]
     pub event_key: EventKey,
     pub event_seq_number: u64,
+    /// True if the block of the event is retracted from the master chain by a reorg.
+    #[serde(default)]
+    pub removed: bool,
 }
 
 impl Event {
@@ -42,8 +45,26 @@ impl Event {
             type_tags: contract_event.type_tag().clone(),
             event_key: *contract_event.key(),
             event_seq_number: contract_event.sequence_number(),
+            removed: false,
         }
     }
+
+    pub fn with_removed(mut self, removed: bool) -> Self {
+        self.removed = removed;
+        self
+    }
+}
+
+impl From<ContractEventInfo> for Event {
+    fn from(info: ContractEventInfo) -> Self {
+        Event::new(
+            Some(info.block_hash),
+            Some(info.block_number),
+            Some(info.transaction_hash),
+            Some(info.transaction_index),
+            &info.event,
+        )
+    }
 }
 
 pub fn serialize_event_key<S>(key: &EventKey, s: S) -> Result<S::Ok, S::Error>
@@ -18,9 +18,10 @@ use std::convert::TryInto;
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
 pub enum Kind {
-    /// New block subscription.
+    /// New block subscription, blocks retracted by a reorg are notified with `removed: true`.
     NewHeads,
-    /// Events subscription.
+    /// Events subscription, events of blocks retracted by a reorg are notified with
+    /// `removed: true`.
     Events,
     /// New Pending Transactions subscription.
     NewPendingTransactions,
@@ -59,14 +60,25 @@ pub struct ThinBlock {
     header: BlockHeader,
     #[serde(rename = "txn_hashes")]
     body: Vec<HashValue>,
+    /// True if the block is retracted from the master chain by a reorg.
+    #[serde(default)]
+    removed: bool,
 }
 impl ThinBlock {
     pub fn new(header: BlockHeader, txn_hashes: Vec<HashValue>) -> Self {
         Self {
             header,
             body: txn_hashes,
+            removed: false,
         }
     }
+    pub fn with_removed(mut self, removed: bool) -> Self {
+        self.removed = removed;
+        self
+    }
+    pub fn removed(&self) -> bool {
+        self.removed
+    }
     pub fn header(&self) -> &BlockHeader {
         &self.header
     }
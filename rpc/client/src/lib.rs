@@ -12,20 +12,31 @@ use jsonrpc_core::{MetaIoHandler, Metadata};
 use jsonrpc_core_client::{transports::ipc, transports::local, transports::ws, RpcChannel};
 use starcoin_crypto::HashValue;
 use starcoin_logger::{prelude::*, LogPattern};
-use starcoin_rpc_api::node::NodeInfo;
+use starcoin_rpc_api::contract::{ContractCall, ContractCallResult};
+use starcoin_rpc_api::debug::{BlockReplayReport, RangeReplayReport};
+use starcoin_rpc_api::node::{NodeInfo, StorageStats};
+use starcoin_rpc_api::txpool::{DryRunOutput, DryRunTransaction};
 use starcoin_rpc_api::types::event::Event;
 use starcoin_rpc_api::types::pubsub::EventFilter;
 use starcoin_rpc_api::types::pubsub::ThinBlock;
 use starcoin_rpc_api::{
-    chain::ChainClient, debug::DebugClient, node::NodeClient, state::StateClient,
-    txpool::TxPoolClient, wallet::WalletClient,
+    chain::ChainClient,
+    contract::ContractClient,
+    debug::{DebugClient, IpcDebugClient},
+    dev::DevClient,
+    node::NodeClient,
+    state::StateClient,
+    txpool::TxPoolClient,
+    wallet::WalletClient,
 };
 use starcoin_state_api::StateWithProof;
 use starcoin_types::access_path::AccessPath;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_state::AccountState;
-use starcoin_types::block::{Block, BlockNumber};
+use starcoin_types::account_transaction::AccountTransactionInfo;
+use starcoin_types::block::{Block, BlockHeader, BlockNumber};
 use starcoin_types::peer_info::PeerInfo;
+use starcoin_types::proof::{BlockProof, TransactionProof};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::{
     RawUserTransaction, SignedUserTransaction, Transaction, TransactionInfo,
@@ -43,6 +54,7 @@ use tokio_compat::prelude::*;
 use tokio_compat::runtime::Runtime;
 
 pub mod chain_watcher;
+pub mod proof_verifier;
 mod pubsub_client;
 mod remote_state_reader;
 pub use crate::remote_state_reader::RemoteStateReader;
@@ -196,6 +208,13 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn node_storage_stats(&self) -> anyhow::Result<StorageStats> {
+        self.call_rpc_blocking(
+            |inner| async move { inner.node_client.storage_stats().compat().await },
+        )
+        .map_err(map_err)
+    }
+
     pub fn node_peers(&self) -> anyhow::Result<Vec<PeerInfo>> {
         self.call_rpc_blocking(|inner| async move { inner.node_client.peers().compat().await })
             .map_err(map_err)
@@ -215,6 +234,27 @@ impl RpcClient {
         .map_err(map_err)
     }
 
+    pub fn dry_run_transaction(&self, txn: DryRunTransaction) -> anyhow::Result<DryRunOutput> {
+        self.call_rpc_blocking(
+            |inner| async move { inner.txpool_client.dry_run(txn).compat().await },
+        )
+        .map_err(map_err)
+    }
+
+    pub fn estimate_gas(&self, txn: DryRunTransaction) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.txpool_client.estimate_gas(txn).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn gas_price(&self) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(
+            |inner| async move { inner.txpool_client.gas_price().compat().await },
+        )
+        .map_err(map_err)
+    }
+
     pub fn submit_transaction(
         &self,
         txn: SignedUserTransaction,
@@ -354,6 +394,13 @@ impl RpcClient {
         .map_err(map_err)
     }
 
+    pub fn contract_call(&self, call: ContractCall) -> anyhow::Result<ContractCallResult> {
+        self.call_rpc_blocking(
+            |inner| async move { inner.contract_client.call(call).compat().await },
+        )
+        .map_err(map_err)
+    }
+
     pub fn debug_set_log_level(
         &self,
         logger_name: Option<String>,
@@ -381,6 +428,46 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn debug_replay_block(&self, block_id: HashValue) -> anyhow::Result<BlockReplayReport> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.debug_client.replay_block(block_id).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn debug_replay_blocks(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<RangeReplayReport> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.debug_client.replay_blocks(from, to).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn debug_rewind(&self, block_id: HashValue) -> anyhow::Result<BlockHeader> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.ipc_debug_client.rewind(block_id).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn dev_mine_blocks(
+        &self,
+        blocks: u64,
+        timestamp: Option<u64>,
+    ) -> anyhow::Result<ChainInfo> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .dev_client
+                .mine_blocks(blocks, timestamp)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_head(&self) -> anyhow::Result<ChainInfo> {
         self.call_rpc_blocking(|inner| async move { inner.chain_client.head().compat().await })
             .map_err(map_err)
@@ -403,6 +490,24 @@ impl RpcClient {
         })
         .map_err(map_err)
     }
+    pub fn chain_get_header_by_hash(&self, hash: HashValue) -> anyhow::Result<BlockHeader> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.chain_client.get_header_by_hash(hash).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn chain_get_header_by_number(&self, number: BlockNumber) -> anyhow::Result<BlockHeader> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .chain_client
+                .get_header_by_number(number)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_get_blocks_by_number(
         &self,
         number: Option<BlockNumber>,
@@ -455,6 +560,51 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn chain_get_events(&self, filter: EventFilter) -> anyhow::Result<Vec<Event>> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.chain_client.get_events(filter).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn chain_get_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<AccountTransactionInfo>> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .chain_client
+                .get_transactions_by_account(address, start, limit)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn chain_get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> anyhow::Result<Option<TransactionProof>> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .chain_client
+                .get_transaction_proof(block_id, txn_index)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn chain_get_block_proof(&self, number: BlockNumber) -> anyhow::Result<Option<BlockProof>> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.chain_client.get_block_proof(number).compat().await
+        })
+        .map_err(map_err)
+    }
+
     pub fn subscribe_events(
         &self,
         filter: EventFilter,
@@ -537,7 +687,10 @@ pub(crate) struct RpcClientInner {
     txpool_client: TxPoolClient,
     wallet_client: WalletClient,
     state_client: StateClient,
+    contract_client: ContractClient,
     debug_client: DebugClient,
+    ipc_debug_client: IpcDebugClient,
+    dev_client: DevClient,
     chain_client: ChainClient,
     pubsub_client: PubSubClient,
 }
@@ -549,7 +702,10 @@ impl RpcClientInner {
             txpool_client: channel.clone().into(),
             wallet_client: channel.clone().into(),
             state_client: channel.clone().into(),
+            contract_client: channel.clone().into(),
             debug_client: channel.clone().into(),
+            ipc_debug_client: channel.clone().into(),
+            dev_client: channel.clone().into(),
             chain_client: channel.clone().into(),
             pubsub_client: channel.into(),
         }
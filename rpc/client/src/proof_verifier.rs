@@ -0,0 +1,210 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verify the proofs returned by the node against a header or an accumulator root the client
+//! already trusts, e.g. one from a verified header chain. The verifiers only check the proof
+//! links the data to that anchor, they do not make the anchor itself trustworthy.
+
+use anyhow::{ensure, Result};
+use starcoin_crypto::HashValue;
+use starcoin_types::block::BlockHeader;
+use starcoin_types::proof::{BlockProof, TransactionProof};
+use starcoin_types::transaction::Transaction;
+
+/// Verify the transaction `txn_hash` is the txn at `proof.txn_index` of the block of the
+/// trusted `header`, against the txn accumulator root of the header.
+pub fn verify_transaction_proof(
+    header: &BlockHeader,
+    txn_hash: HashValue,
+    proof: &TransactionProof,
+) -> Result<()> {
+    ensure!(
+        proof.block_id == header.id(),
+        "Transaction proof is for block {:?}, expect {:?}.",
+        proof.block_id,
+        header.id()
+    );
+    ensure!(
+        proof.accumulator_root == header.accumulator_root(),
+        "Transaction proof is against accumulator root {:?}, expect {:?}.",
+        proof.accumulator_root,
+        header.accumulator_root()
+    );
+    ensure!(
+        proof.txn_info.transaction_hash() == txn_hash,
+        "Transaction proof is for txn {:?}, expect {:?}.",
+        proof.txn_info.transaction_hash(),
+        txn_hash
+    );
+    ensure!(
+        proof.leaf_index >= proof.txn_index,
+        "Transaction proof leaf index {} is less than txn index {}.",
+        proof.leaf_index,
+        proof.txn_index
+    );
+    // The leaves of the accumulator end with the txns of the block, and the first txn of a
+    // block is its block metadata txn, so the leaf of it is the first leaf of the block.
+    let first_leaf_index = proof.leaf_index - proof.txn_index;
+    let block_metadata_hash = Transaction::BlockMetadata(header.clone().into_metadata()).id();
+    ensure!(
+        proof.first_txn_info.transaction_hash() == block_metadata_hash,
+        "Transaction proof's first txn {:?} is not the block metadata txn {:?}.",
+        proof.first_txn_info.transaction_hash(),
+        block_metadata_hash
+    );
+    proof.first_txn_proof.verify(
+        proof.accumulator_root,
+        proof.first_txn_info.id(),
+        first_leaf_index,
+    )?;
+    proof.proof.verify(
+        proof.accumulator_root,
+        proof.txn_info.id(),
+        proof.leaf_index,
+    )
+}
+
+/// Verify the block `block_id` is the leaf at `proof.number` of the block accumulator with
+/// the trusted root `accumulator_root`, e.g. the `parent_block_accumulator_root` of a later
+/// header. The block is on master only if that header is.
+pub fn verify_block_proof(
+    accumulator_root: HashValue,
+    block_id: HashValue,
+    proof: &BlockProof,
+) -> Result<()> {
+    ensure!(
+        proof.block_id == block_id,
+        "Block proof is for block {:?}, expect {:?}.",
+        proof.block_id,
+        block_id
+    );
+    ensure!(
+        proof.accumulator_root == accumulator_root,
+        "Block proof is against accumulator root {:?}, expect {:?}.",
+        proof.accumulator_root,
+        accumulator_root
+    );
+    proof
+        .proof
+        .verify(accumulator_root, proof.block_id, proof.number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_accumulator::node::{AccumulatorStoreType, ACCUMULATOR_PLACEHOLDER_HASH};
+    use starcoin_accumulator::tree_store::MockAccumulatorStore;
+    use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+    use starcoin_types::transaction::TransactionInfo;
+    use starcoin_types::vm_error::StatusCode;
+    use std::sync::Arc;
+
+    fn txn_info(txn_hash: HashValue) -> TransactionInfo {
+        TransactionInfo::new(
+            txn_hash,
+            HashValue::zero(),
+            HashValue::zero(),
+            vec![],
+            0,
+            StatusCode::EXECUTED,
+        )
+    }
+
+    fn block_txn_infos(header: &BlockHeader, user_txns: usize) -> Vec<TransactionInfo> {
+        let mut txn_infos = vec![txn_info(
+            Transaction::BlockMetadata(header.clone().into_metadata()).id(),
+        )];
+        txn_infos.extend((0..user_txns).map(|_| txn_info(HashValue::random())));
+        txn_infos
+    }
+
+    fn new_accumulator() -> MerkleAccumulator {
+        MerkleAccumulator::new(
+            *ACCUMULATOR_PLACEHOLDER_HASH,
+            vec![],
+            0,
+            0,
+            AccumulatorStoreType::Transaction,
+            Arc::new(MockAccumulatorStore::new()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_transaction_proof() {
+        let accumulator = new_accumulator();
+        // block 1 has 1 user txn, block 2 has 2 user txns.
+        let mut header1 = BlockHeader::random();
+        let infos1 = block_txn_infos(&header1, 1);
+        let ids1: Vec<_> = infos1.iter().map(|info| info.id()).collect();
+        header1.accumulator_root = accumulator.append(&ids1).unwrap().0;
+        let mut header2 = BlockHeader::random();
+        header2.parent_hash = header1.id();
+        let infos2 = block_txn_infos(&header2, 2);
+        let ids2: Vec<_> = infos2.iter().map(|info| info.id()).collect();
+        header2.accumulator_root = accumulator.append(&ids2).unwrap().0;
+
+        let proof_of = |header: &BlockHeader, infos: &[TransactionInfo], first_leaf: u64, idx| {
+            TransactionProof {
+                block_id: header.id(),
+                txn_index: idx,
+                leaf_index: first_leaf + idx,
+                accumulator_root: header2.accumulator_root(),
+                txn_info: infos[idx as usize].clone(),
+                proof: accumulator.get_proof(first_leaf + idx).unwrap().unwrap(),
+                first_txn_info: infos[0].clone(),
+                first_txn_proof: accumulator.get_proof(first_leaf).unwrap().unwrap(),
+            }
+        };
+
+        let proof = proof_of(&header2, &infos2, 2, 2);
+        let txn_hash = infos2[2].transaction_hash();
+        verify_transaction_proof(&header2, txn_hash, &proof).unwrap();
+        assert!(verify_transaction_proof(&header2, HashValue::random(), &proof).is_err());
+        assert!(verify_transaction_proof(&header1, txn_hash, &proof).is_err());
+
+        // the anchor root must be the root of the header.
+        let mut bad_proof = proof.clone();
+        bad_proof.accumulator_root = header1.accumulator_root();
+        assert!(verify_transaction_proof(&header2, txn_hash, &bad_proof).is_err());
+
+        // a txn of block 1 can not be proved to be in block 2, even it is in the accumulator.
+        let proof1 = proof_of(&header1, &infos1, 0, 1);
+        let mut bad_proof = proof1.clone();
+        bad_proof.block_id = header2.id();
+        assert!(
+            verify_transaction_proof(&header2, infos1[1].transaction_hash(), &bad_proof).is_err()
+        );
+        bad_proof.first_txn_info = infos2[0].clone();
+        bad_proof.first_txn_proof = proof.first_txn_proof.clone();
+        assert!(
+            verify_transaction_proof(&header2, infos1[1].transaction_hash(), &bad_proof).is_err()
+        );
+
+        // the txn index must match the leaf index.
+        let mut bad_proof = proof;
+        bad_proof.txn_index = 1;
+        assert!(verify_transaction_proof(&header2, txn_hash, &bad_proof).is_err());
+    }
+
+    #[test]
+    fn test_verify_block_proof() {
+        let accumulator = new_accumulator();
+        let block_ids: Vec<_> = (0..5).map(|_| HashValue::random()).collect();
+        let (root, _) = accumulator.append(&block_ids).unwrap();
+        let proof = BlockProof {
+            block_id: block_ids[3],
+            number: 3,
+            anchor_block_id: HashValue::random(),
+            accumulator_root: root,
+            proof: accumulator.get_proof(3).unwrap().unwrap(),
+        };
+        verify_block_proof(root, block_ids[3], &proof).unwrap();
+        assert!(verify_block_proof(root, block_ids[2], &proof).is_err());
+        assert!(verify_block_proof(HashValue::random(), block_ids[3], &proof).is_err());
+
+        let mut bad_proof = proof;
+        bad_proof.number = 2;
+        assert!(verify_block_proof(root, block_ids[3], &bad_proof).is_err());
+    }
+}
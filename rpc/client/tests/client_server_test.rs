@@ -31,7 +31,7 @@ fn test_multi_client() -> Result<()> {
         //io_handler.add_method("status", |_params: Params| Ok(Value::Bool(true)));
         let (_rpc_actor, _) = RpcActor::launch_with_method(
             config.clone(),
-            NodeRpcImpl::new(config, None).to_delegate(),
+            NodeRpcImpl::new(config, None, None).to_delegate(),
         )
         .unwrap();
 
@@ -0,0 +1,347 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_traits::{ChainHeaderReader, Consensus};
+use starcoin_types::{
+    block::{BlockHeader, BlockNumber},
+    U256,
+};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// How many headers below the head are kept, the chain can not reorganize deeper than it.
+pub const MAX_RETAINED_HEADERS: u64 = 1000;
+
+/// A chain of verified headers from a trusted checkpoint, the head is the header with the
+/// most total difficulty since the checkpoint.
+/// It implements `ChainHeaderReader`, which is all `Consensus::verify` reads.
+pub struct HeaderChain<C>
+where
+    C: Consensus,
+{
+    config: Arc<NodeConfig>,
+    headers: HashMap<HashValue, BlockHeader>,
+    /// Total difficulty of every header since the checkpoint.
+    total_difficulties: HashMap<HashValue, U256>,
+    head: BlockHeader,
+    checkpoint_number: BlockNumber,
+    phantom: PhantomData<C>,
+}
+
+impl<C> HeaderChain<C>
+where
+    C: Consensus,
+{
+    /// `trusted` are the header of `checkpoint` followed by its ancestors in descending order,
+    /// the ancestors are trusted by the hash links from the checkpoint, and are used to
+    /// calculate the difficulty of the following headers.
+    pub fn new(
+        config: Arc<NodeConfig>,
+        checkpoint: HashValue,
+        trusted: Vec<BlockHeader>,
+    ) -> Result<Self> {
+        let head = trusted
+            .first()
+            .cloned()
+            .ok_or_else(|| format_err!("Header of checkpoint {:?} is missing.", checkpoint))?;
+        ensure!(
+            head.id() == checkpoint,
+            "Header {:?} mismatch checkpoint {:?}.",
+            head.id(),
+            checkpoint
+        );
+        for pair in trusted.windows(2) {
+            ensure!(
+                pair[0].parent_hash() == pair[1].id() && pair[0].number() == pair[1].number() + 1,
+                "Header {:?} is not the parent of {:?}.",
+                pair[1].id(),
+                pair[0].id()
+            );
+        }
+        Ok(Self {
+            config,
+            total_difficulties: trusted
+                .iter()
+                .map(|header| (header.id(), U256::zero()))
+                .collect(),
+            headers: trusted
+                .into_iter()
+                .map(|header| (header.id(), header))
+                .collect(),
+            checkpoint_number: head.number(),
+            head,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn head(&self) -> &BlockHeader {
+        &self.head
+    }
+
+    pub fn contains(&self, block_id: HashValue) -> bool {
+        self.headers.contains_key(&block_id)
+    }
+
+    /// The header of number `number` on the chain of the head.
+    pub fn get_header_by_number(&self, number: BlockNumber) -> Option<BlockHeader> {
+        let mut current = Some(self.head.clone());
+        while let Some(header) = current {
+            if header.number() == number {
+                return Some(header);
+            }
+            if header.number() < number {
+                break;
+            }
+            current = self.headers.get(&header.parent_hash()).cloned();
+        }
+        None
+    }
+
+    /// Number of the oldest retained header.
+    pub fn tail_number(&self) -> BlockNumber {
+        self.headers
+            .values()
+            .map(|header| header.number())
+            .min()
+            .unwrap_or_else(|| self.head.number())
+    }
+
+    /// Verify `headers` in ascending order, and switch the head to the last one if it has more
+    /// total difficulty than the head. The first header must be a child of a known header, the
+    /// head keeps unchanged if any header is invalid.
+    pub fn connect(&mut self, headers: Vec<BlockHeader>) -> Result<()> {
+        let mut parent = match headers.first() {
+            Some(first) => self
+                .headers
+                .get(&first.parent_hash())
+                .cloned()
+                .ok_or_else(|| format_err!("Parent of header {:?} is unknown.", first.id()))?,
+            None => return Ok(()),
+        };
+        ensure!(
+            parent.number() >= self.checkpoint_number,
+            "Header {:?} forks from {}, below the checkpoint {}.",
+            headers[0].id(),
+            parent.number(),
+            self.checkpoint_number
+        );
+        let head = self.head.clone();
+        for header in headers {
+            if let Err(e) = self.verify(&parent, &header) {
+                self.head = head;
+                return Err(e);
+            }
+            let total_difficulty = self.total_difficulty(&parent) + header.difficulty();
+            self.total_difficulties
+                .insert(header.id(), total_difficulty);
+            self.headers.insert(header.id(), header.clone());
+            parent = header;
+        }
+        if self.total_difficulty(&parent) <= self.total_difficulty(&head) {
+            debug!(
+                "Header {:?}({}) has less total difficulty than head {:?}({}).",
+                parent.id(),
+                parent.number(),
+                head.id(),
+                head.number()
+            );
+            self.head = head;
+            return Ok(());
+        }
+        debug!("Light client head: {:?}({})", parent.id(), parent.number());
+        self.head = parent;
+        self.prune();
+        Ok(())
+    }
+
+    fn total_difficulty(&self, header: &BlockHeader) -> U256 {
+        self.total_difficulties
+            .get(&header.id())
+            .copied()
+            .unwrap_or_else(U256::zero)
+    }
+
+    fn verify(&mut self, parent: &BlockHeader, header: &BlockHeader) -> Result<()> {
+        ensure!(
+            header.parent_hash() == parent.id() && header.number() == parent.number() + 1,
+            "Header {:?}({}) is not a child of {:?}({}).",
+            header.id(),
+            header.number(),
+            parent.id(),
+            parent.number()
+        );
+        let min_timestamp = if self
+            .config
+            .net()
+            .get_config()
+            .is_median_time_past(header.number())
+        {
+            self.median_timestamp(parent.id())?
+        } else {
+            parent.timestamp()
+        };
+        ensure!(
+            header.timestamp() >= min_timestamp,
+            "Timestamp {} of header {:?} is older than the min timestamp {}.",
+            header.timestamp(),
+            header.id(),
+            min_timestamp
+        );
+        // consensus reads the chain from its head, which is the parent of the header.
+        self.head = parent.clone();
+        C::verify(self.config.clone(), self, header)
+    }
+
+    fn prune(&mut self) {
+        let head_number = self.head.number();
+        if head_number > MAX_RETAINED_HEADERS {
+            let tail = head_number - MAX_RETAINED_HEADERS;
+            self.headers.retain(|_, header| header.number() >= tail);
+            let headers = &self.headers;
+            self.total_difficulties
+                .retain(|block_id, _| headers.contains_key(block_id));
+            self.checkpoint_number = self.checkpoint_number.max(tail);
+        }
+    }
+}
+
+impl<C> ChainHeaderReader for HeaderChain<C>
+where
+    C: Consensus,
+{
+    fn head_header(&self) -> BlockHeader {
+        self.head.clone()
+    }
+
+    fn find_header(&self, hash: HashValue) -> Result<Option<BlockHeader>> {
+        Ok(self.headers.get(&hash).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_consensus::argon::ArgonConsensus;
+    use starcoin_consensus::dev::DevConsensus;
+
+    fn child(parent: &BlockHeader) -> BlockHeader {
+        let mut header = BlockHeader::random();
+        header.parent_hash = parent.id();
+        header.number = parent.number() + 1;
+        header.timestamp = parent.timestamp() + 1;
+        header.difficulty = 1.into();
+        header
+    }
+
+    #[test]
+    fn test_header_chain() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let mut parent = BlockHeader::random();
+        parent.number = 10;
+        let checkpoint = child(&parent);
+        let mut chain = HeaderChain::<DevConsensus>::new(
+            config,
+            checkpoint.id(),
+            vec![checkpoint.clone(), parent.clone()],
+        )?;
+
+        let a1 = child(&checkpoint);
+        let a2 = child(&a1);
+        chain.connect(vec![a1.clone(), a2.clone()])?;
+        assert_eq!(chain.head().id(), a2.id());
+        assert_eq!(chain.get_header_by_number(11), Some(checkpoint.clone()));
+
+        // fork from a1 with more total difficulty switches the head.
+        let b2 = child(&a1);
+        let b3 = child(&b2);
+        chain.connect(vec![b2.clone(), b3.clone()])?;
+        assert_eq!(chain.head().id(), b3.id());
+
+        // fork with less or equal total difficulty keeps the head, even it is longer.
+        let mut c2 = child(&a1);
+        c2.difficulty = 2.into();
+        chain.connect(vec![c2.clone()])?;
+        assert_eq!(chain.head().id(), b3.id());
+        assert!(chain.contains(c2.id()));
+        let mut d3 = child(&b2);
+        d3.difficulty = 0.into();
+        let d4 = child(&d3);
+        chain.connect(vec![d3, d4])?;
+        assert_eq!(chain.head().id(), b3.id());
+
+        // extend the lighter fork to make it heavier.
+        let c3 = child(&c2);
+        chain.connect(vec![c3.clone()])?;
+        assert_eq!(chain.head().id(), c3.id());
+
+        // broken parent link keeps the head.
+        let c4 = child(&c3);
+        let mut c5 = child(&c4);
+        c5.parent_hash = HashValue::random();
+        assert!(chain.connect(vec![c4, c5]).is_err());
+        assert_eq!(chain.head().id(), c3.id());
+
+        // fork below the checkpoint is rejected.
+        assert!(chain.connect(vec![child(&parent)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_chain_reject_bad_pow() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let block_time_target = config
+            .net()
+            .get_config()
+            .difficulty_config
+            .block_time_target;
+        // trusted headers from genesis, enough to calculate the difficulty.
+        let mut trusted = vec![BlockHeader::random()];
+        trusted[0].number = 0;
+        for _ in 0..3 {
+            let mut header = child(&trusted[trusted.len() - 1]);
+            header.timestamp = trusted[trusted.len() - 1].timestamp() + block_time_target;
+            header.difficulty = 4.into();
+            trusted.push(header);
+        }
+        trusted.reverse();
+        let checkpoint = trusted[0].clone();
+        let mut chain =
+            HeaderChain::<ArgonConsensus>::new(config.clone(), checkpoint.id(), trusted)?;
+
+        let mut header = child(&checkpoint);
+        header.timestamp = checkpoint.timestamp() + block_time_target;
+        header.difficulty = ArgonConsensus::calculate_next_difficulty(config.clone(), &chain)?;
+        let version = config.pow_version(header.number());
+        header.consensus_header =
+            ArgonConsensus::solve_consensus_header(&header.pow_header(version), header.difficulty)
+                .into();
+
+        // a nonce which does not solve the header.
+        let mut bad_header = header.clone();
+        let mut nonce = 0u64;
+        loop {
+            bad_header.consensus_header = nonce.to_le_bytes().to_vec();
+            if ArgonConsensus::verify_pow(config.clone(), &bad_header).is_err() {
+                break;
+            }
+            nonce += 1;
+        }
+        assert!(chain.connect(vec![bad_header]).is_err());
+        assert_eq!(chain.head().id(), checkpoint.id());
+
+        // a header with another difficulty than the calculated one.
+        let mut bad_header = header.clone();
+        bad_header.difficulty = header.difficulty + U256::one();
+        assert!(chain.connect(vec![bad_header]).is_err());
+        assert_eq!(chain.head().id(), checkpoint.id());
+
+        chain.connect(vec![header.clone()])?;
+        assert_eq!(chain.head().id(), header.id());
+        Ok(())
+    }
+}
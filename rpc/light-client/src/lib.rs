@@ -0,0 +1,143 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client which does not trust the rpc node: it tracks the header chain from a trusted
+//! checkpoint, verifies every header by the parent link and `Consensus::verify`, and verifies
+//! every state returned by the node against the `state_root` of the verified head.
+
+use anyhow::{ensure, Result};
+use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_client::RpcClient;
+use starcoin_traits::Consensus;
+use starcoin_types::block::{BlockHeader, BlockNumber};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+mod header_chain;
+mod verified_state_reader;
+
+pub use header_chain::{HeaderChain, MAX_RETAINED_HEADERS};
+pub use verified_state_reader::VerifiedStateReader;
+
+/// Max headers verified and connected at once by `LightClient::sync`.
+pub const MAX_SYNC_BATCH: u64 = 100;
+
+pub struct LightClient<'a, C>
+where
+    C: Consensus,
+{
+    client: &'a RpcClient,
+    chain: RefCell<HeaderChain<C>>,
+}
+
+impl<'a, C> LightClient<'a, C>
+where
+    C: Consensus,
+{
+    /// Start from the trusted block `checkpoint`, its ancestors in the difficulty window are
+    /// fetched from the node and checked by hash links.
+    pub fn new(
+        client: &'a RpcClient,
+        config: Arc<NodeConfig>,
+        checkpoint: HashValue,
+    ) -> Result<Self> {
+        let block_window = config.net().get_config().difficulty_config.block_window;
+        let mut trusted = vec![get_header(client, checkpoint)?];
+        while (trusted.len() as u64) < block_window {
+            let last = &trusted[trusted.len() - 1];
+            if last.number() == 0 {
+                break;
+            }
+            let parent = get_header(client, last.parent_hash())?;
+            trusted.push(parent);
+        }
+        let chain = HeaderChain::new(config, checkpoint, trusted)?;
+        Ok(Self {
+            client,
+            chain: RefCell::new(chain),
+        })
+    }
+
+    pub fn client(&self) -> &'a RpcClient {
+        self.client
+    }
+
+    /// The verified head.
+    pub fn head(&self) -> BlockHeader {
+        self.chain.borrow().head().clone()
+    }
+
+    /// Follow the head of the node, every new header is verified before it is accepted.
+    /// Headers are fetched from the known ancestor of the remote head, and are verified and
+    /// connected in batches of at most `MAX_SYNC_BATCH` headers. Return the verified head.
+    pub fn sync(&self) -> Result<BlockHeader> {
+        let remote_head = get_header(self.client, *self.client.chain_head()?.get_head())?;
+        let mut number = self.find_ancestor(&remote_head)? + 1;
+        while number <= remote_head.number() {
+            let batch_end = std::cmp::min(remote_head.number(), number + MAX_SYNC_BATCH - 1);
+            let headers = (number..=batch_end)
+                .map(|number| get_header_by_number(self.client, number))
+                .collect::<Result<Vec<_>>>()?;
+            self.chain.borrow_mut().connect(headers)?;
+            number = batch_end + 1;
+        }
+        let head = self.head();
+        info!("Light client synced to {:?}({})", head.id(), head.number());
+        Ok(head)
+    }
+
+    /// The number of the latest known header on the master chain of the node, which is not
+    /// after `remote_head`.
+    fn find_ancestor(&self, remote_head: &BlockHeader) -> Result<BlockNumber> {
+        let chain = self.chain.borrow();
+        if chain.contains(remote_head.id()) {
+            return Ok(remote_head.number());
+        }
+        let tail_number = chain.tail_number();
+        let mut number = std::cmp::min(chain.head().number(), remote_head.number());
+        loop {
+            if chain.contains(get_header_by_number(self.client, number)?.id()) {
+                return Ok(number);
+            }
+            ensure!(
+                number > tail_number,
+                "Can not find a known ancestor of remote head {:?}.",
+                remote_head.id()
+            );
+            number -= 1;
+        }
+    }
+
+    /// A state reader which verifies every response of the node.
+    pub fn state_reader(&self) -> VerifiedStateReader<'_, 'a, C> {
+        VerifiedStateReader::new(self)
+    }
+}
+
+/// Get the header of block `block_id` from the node, and check the header matches the id.
+fn get_header(client: &RpcClient, block_id: HashValue) -> Result<BlockHeader> {
+    let header = client.chain_get_header_by_hash(block_id)?;
+    ensure!(
+        header.id() == block_id,
+        "Node returns header {:?} for block {:?}.",
+        header.id(),
+        block_id
+    );
+    Ok(header)
+}
+
+/// Get the header of number `number` on the master chain of the node, and check the number.
+/// The header is not trusted until it is connected to the header chain.
+fn get_header_by_number(client: &RpcClient, number: BlockNumber) -> Result<BlockHeader> {
+    let header = client.chain_get_header_by_number(number)?;
+    ensure!(
+        header.number() == number,
+        "Node returns header {:?}({}) for number {}.",
+        header.id(),
+        header.number(),
+        number
+    );
+    Ok(header)
+}
@@ -0,0 +1,147 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::LightClient;
+use anyhow::{bail, Result};
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_state_api::{ChainStateReader, StateView, StateWithProof};
+use starcoin_traits::Consensus;
+use starcoin_types::access_path::AccessPath;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_state::AccountState;
+use starcoin_types::state_set::ChainStateSet;
+
+/// A `RemoteStateReader` which verifies every state against the `state_root` of the verified
+/// head of the light client, and returns an error on any unproven response.
+pub struct VerifiedStateReader<'l, 'a, C>
+where
+    C: Consensus,
+{
+    light_client: &'l LightClient<'a, C>,
+}
+
+impl<'l, 'a, C> VerifiedStateReader<'l, 'a, C>
+where
+    C: Consensus,
+{
+    pub fn new(light_client: &'l LightClient<'a, C>) -> Self {
+        Self { light_client }
+    }
+
+    fn get_and_verify(
+        &self,
+        access_path: &AccessPath,
+        state_root: HashValue,
+    ) -> Result<StateWithProof> {
+        let state = self
+            .light_client
+            .client()
+            .state_get_with_proof(access_path.clone())?;
+        verify_state(state_root, access_path, &state)?;
+        Ok(state)
+    }
+}
+
+/// Verify `state` is the state of `access_path` in the state tree with root `state_root`.
+fn verify_state(
+    state_root: HashValue,
+    access_path: &AccessPath,
+    state: &StateWithProof,
+) -> Result<()> {
+    state
+        .proof
+        .verify(state_root, access_path.clone(), state.state.as_deref())
+}
+
+impl<'l, 'a, C> ChainStateReader for VerifiedStateReader<'l, 'a, C>
+where
+    C: Consensus,
+{
+    fn get_with_proof(&self, access_path: &AccessPath) -> Result<StateWithProof> {
+        match self.get_and_verify(access_path, self.state_root()) {
+            Ok(state) => Ok(state),
+            Err(e) => {
+                // the node may have a newer head, sync and try again.
+                debug!(
+                    "Verify state of {:?} failed: {}, sync head.",
+                    access_path, e
+                );
+                let head = self.light_client.sync()?;
+                self.get_and_verify(access_path, head.state_root())
+            }
+        }
+    }
+
+    fn get_account_state(&self, _address: &AccountAddress) -> Result<Option<AccountState>> {
+        bail!("Account state is not proven by the node, use get_with_proof instead.")
+    }
+
+    fn state_root(&self) -> HashValue {
+        self.light_client.head().state_root()
+    }
+
+    fn dump(&self) -> Result<ChainStateSet> {
+        unimplemented!()
+    }
+}
+
+impl<'l, 'a, C> StateView for VerifiedStateReader<'l, 'a, C>
+where
+    C: Consensus,
+{
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_with_proof(access_path)?.state)
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths
+            .iter()
+            .map(|access_path| self.get(access_path))
+            .collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_state_api::ChainStateWriter;
+    use starcoin_state_tree::mock::MockStateNodeStore;
+    use starcoin_statedb::ChainStateDB;
+    use starcoin_types::access_path;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_verify_state() -> Result<()> {
+        let chain_state_db = ChainStateDB::new(Arc::new(MockStateNodeStore::new()), None);
+        let access_path = access_path::random_resource();
+        let value = HashValue::random().to_vec();
+        chain_state_db.set(&access_path, value.clone())?;
+        let state_root = chain_state_db.commit()?;
+
+        let state = chain_state_db.get_with_proof(&access_path)?;
+        assert_eq!(state.state, Some(value));
+        verify_state(state_root, &access_path, &state)?;
+        assert!(verify_state(HashValue::random(), &access_path, &state).is_err());
+        assert!(verify_state(state_root, &access_path::random_resource(), &state).is_err());
+
+        // the node returns another value, or hides the value.
+        let mut bad_state = state.clone();
+        bad_state.state = Some(HashValue::random().to_vec());
+        assert!(verify_state(state_root, &access_path, &bad_state).is_err());
+        let mut bad_state = state;
+        bad_state.state = None;
+        assert!(verify_state(state_root, &access_path, &bad_state).is_err());
+
+        // a missing state is proven too.
+        let missing_path = access_path::random_resource();
+        let missing = chain_state_db.get_with_proof(&missing_path)?;
+        assert!(missing.state.is_none());
+        verify_state(state_root, &missing_path, &missing)?;
+        Ok(())
+    }
+}
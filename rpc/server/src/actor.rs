@@ -3,8 +3,8 @@
 
 use crate::metadata::Metadata;
 use crate::module::{
-    ChainRpcImpl, DebugRpcImpl, NodeRpcImpl, PubSubImpl, PubSubService, StateRpcImpl,
-    TxPoolRpcImpl, WalletRpcImpl,
+    ChainRpcImpl, ContractRpcImpl, DebugRpcImpl, DevRpcImpl, IpcDebugRpcImpl, NodeRpcImpl,
+    PubSubImpl, PubSubService, StateRpcImpl, TxPoolRpcImpl, WalletRpcImpl,
 };
 use crate::service::RpcService;
 use actix::prelude::*;
@@ -15,11 +15,15 @@ use starcoin_logger::prelude::*;
 use starcoin_logger::LoggerHandle;
 use starcoin_network::NetworkAsyncService;
 use starcoin_rpc_api::chain::ChainApi;
-use starcoin_rpc_api::debug::DebugApi;
+use starcoin_rpc_api::contract::ContractApi;
+use starcoin_rpc_api::debug::{DebugApi, IpcDebugApi};
+use starcoin_rpc_api::dev::DevApi;
 use starcoin_rpc_api::wallet::WalletApi;
 use starcoin_rpc_api::{node::NodeApi, pubsub::StarcoinPubSub, state::StateApi, txpool::TxPoolApi};
 use starcoin_rpc_middleware::MetricMiddleware;
 use starcoin_state_api::ChainStateAsyncService;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::Store;
 use starcoin_traits::ChainAsyncService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_wallet_api::WalletAsyncService;
@@ -27,7 +31,10 @@ use std::sync::Arc;
 
 pub struct RpcActor {
     config: Arc<NodeConfig>,
+    /// Handler of the IPC server and the local clients, it serves the IPC debug api too.
     io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+    /// Handler of the HTTP, TCP and WebSocket servers.
+    public_io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
     server: Option<RpcService>,
 }
 
@@ -41,6 +48,8 @@ impl RpcActor {
         pubsub_service: Option<PubSubService>,
         //TODO after network async service provide trait, remove Option.
         network_service: Option<NetworkAsyncService>,
+        db_storage: Option<Arc<DBStorage>>,
+        storage: Option<Arc<dyn Store>>,
         logger_handle: Option<Arc<LoggerHandle>>,
     ) -> Result<(Addr<RpcActor>, MetaIoHandler<Metadata, MetricMiddleware>)>
     where
@@ -50,27 +59,51 @@ impl RpcActor {
         SS: ChainStateAsyncService + 'static,
     {
         let config_clone = config.clone();
-        let io_handler = Self::extend_apis(
-            NodeRpcImpl::new(config.clone(), network_service),
+        let dev_api = if config.net().is_dev() {
+            Some(DevRpcImpl::new(
+                config.clone(),
+                chain_service.clone(),
+                txpool_service.clone(),
+                account_service.clone(),
+            ))
+        } else {
+            None
+        };
+        let contract_api = storage
+            .clone()
+            .map(|storage| ContractRpcImpl::new(chain_service.clone(), storage));
+        let debug_api = logger_handle.map(|logger_handle| {
+            DebugRpcImpl::new(config_clone, logger_handle, storage, chain_service.clone())
+        });
+        let ipc_debug_api = IpcDebugRpcImpl::new(chain_service.clone());
+        let public_io_handler = Self::extend_apis(
+            NodeRpcImpl::new(config.clone(), network_service, db_storage),
             Some(ChainRpcImpl::new(chain_service)),
             Some(TxPoolRpcImpl::new(txpool_service)),
             Some(WalletRpcImpl::new(account_service)),
             Some(StateRpcImpl::new(state_service)),
+            contract_api,
             pubsub_service.map(PubSubImpl::new),
-            logger_handle.map(|logger_handle| DebugRpcImpl::new(config_clone, logger_handle)),
+            debug_api,
+            dev_api,
         )?;
+        // rewind changes the chain, so only serve it over IPC.
+        let mut io_handler = public_io_handler.clone();
+        io_handler.extend_with(IpcDebugApi::to_delegate(ipc_debug_api));
 
-        Self::launch_with_handler(config, io_handler)
+        Self::launch_with_handlers(config, io_handler, public_io_handler)
     }
 
-    pub fn extend_apis<C, N, T, A, S, D, P>(
+    pub fn extend_apis<C, N, T, A, S, R, P, D, V>(
         node_api: N,
         chain_api: Option<C>,
         txpool_api: Option<T>,
         account_api: Option<A>,
         state_api: Option<S>,
+        contract_api: Option<R>,
         pubsub_api: Option<P>,
         debug_api: Option<D>,
+        dev_api: Option<V>,
     ) -> Result<MetaIoHandler<Metadata, MetricMiddleware>>
     where
         N: NodeApi,
@@ -78,8 +111,10 @@ impl RpcActor {
         T: TxPoolApi,
         A: WalletApi,
         S: StateApi,
+        R: ContractApi,
         P: StarcoinPubSub<Metadata = Metadata>,
         D: DebugApi,
+        V: DevApi,
     {
         let mut io_handler =
             MetaIoHandler::<Metadata, MetricMiddleware>::with_middleware(MetricMiddleware);
@@ -96,12 +131,18 @@ impl RpcActor {
         if let Some(state_api) = state_api {
             io_handler.extend_with(StateApi::to_delegate(state_api));
         }
+        if let Some(contract_api) = contract_api {
+            io_handler.extend_with(ContractApi::to_delegate(contract_api));
+        }
         if let Some(pubsub_api) = pubsub_api {
             io_handler.extend_with(StarcoinPubSub::to_delegate(pubsub_api));
         }
         if let Some(debug_api) = debug_api {
             io_handler.extend_with(DebugApi::to_delegate(debug_api));
         }
+        if let Some(dev_api) = dev_api {
+            io_handler.extend_with(DevApi::to_delegate(dev_api));
+        }
         Ok(io_handler)
         // Self::launch_with_handler(config, io_handler)
     }
@@ -109,11 +150,21 @@ impl RpcActor {
     pub fn launch_with_handler(
         config: Arc<NodeConfig>,
         io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+    ) -> Result<(Addr<Self>, MetaIoHandler<Metadata, MetricMiddleware>)> {
+        Self::launch_with_handlers(config, io_handler.clone(), io_handler)
+    }
+
+    /// Launch with `io_handler` for IPC and `public_io_handler` for the other transports.
+    pub fn launch_with_handlers(
+        config: Arc<NodeConfig>,
+        io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+        public_io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
     ) -> Result<(Addr<Self>, MetaIoHandler<Metadata, MetricMiddleware>)> {
         let actor = RpcActor {
             config,
             server: None,
             io_handler: io_handler.clone(),
+            public_io_handler,
         };
         Ok((actor.start(), io_handler))
     }
@@ -132,7 +183,11 @@ impl RpcActor {
     }
 
     fn do_start(&mut self) {
-        let server = RpcService::new(self.config.clone(), self.io_handler.clone());
+        let server = RpcService::new(
+            self.config.clone(),
+            self.io_handler.clone(),
+            self.public_io_handler.clone(),
+        );
         self.server = Some(server);
     }
 
@@ -189,6 +244,8 @@ mod tests {
             state_service,
             None,
             None,
+            None,
+            None,
             Some(logger_handle),
         )
         .unwrap();
@@ -2,14 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::module::map_err;
+use anyhow::format_err;
 use futures::future::{FutureExt, TryFutureExt};
 use starcoin_crypto::HashValue;
 use starcoin_rpc_api::chain::ChainApi;
+use starcoin_rpc_api::types::event::Event;
+use starcoin_rpc_api::types::pubsub::EventFilter;
 use starcoin_rpc_api::FutureResult;
 use starcoin_traits::ChainAsyncService;
-use starcoin_types::block::{Block, BlockNumber};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_transaction::AccountTransactionInfo;
+use starcoin_types::block::{Block, BlockHeader, BlockNumber};
+use starcoin_types::filter::Filter;
+use starcoin_types::proof::{BlockProof, TransactionProof};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::{Transaction, TransactionInfo};
+use std::convert::TryInto;
 
 pub struct ChainRpcImpl<S>
 where
@@ -54,6 +62,27 @@ where
         Box::new(fut.compat())
     }
 
+    fn get_header_by_hash(&self, hash: HashValue) -> FutureResult<BlockHeader> {
+        let service = self.service.clone();
+        let fut = async move {
+            service
+                .get_header_by_hash(&hash)
+                .await?
+                .ok_or_else(|| format_err!("Can not find block header by hash {:?}", hash))
+        }
+        .map_err(map_err);
+        Box::new(fut.boxed().compat())
+    }
+
+    fn get_header_by_number(&self, number: BlockNumber) -> FutureResult<BlockHeader> {
+        let fut = self
+            .service
+            .clone()
+            .master_block_header_by_number(number)
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
+
     fn get_blocks_by_number(
         &self,
         number: Option<BlockNumber>,
@@ -106,4 +135,54 @@ where
             .map_err(map_err);
         Box::new(fut.compat())
     }
+
+    fn get_events(&self, filter: EventFilter) -> FutureResult<Vec<Event>> {
+        let filter: Filter = match filter.try_into() {
+            Ok(filter) => filter,
+            Err(e) => return Box::new(jsonrpc_core::futures::future::err(e)),
+        };
+        let fut = self
+            .service
+            .clone()
+            .master_events(filter)
+            .map_ok(|events| events.into_iter().map(Event::from).collect())
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
+
+    fn get_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> FutureResult<Vec<AccountTransactionInfo>> {
+        let fut = self
+            .service
+            .clone()
+            .master_transactions_by_account(address, start, limit)
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
+
+    fn get_transaction_proof(
+        &self,
+        block_id: HashValue,
+        txn_index: u64,
+    ) -> FutureResult<Option<TransactionProof>> {
+        let fut = self
+            .service
+            .clone()
+            .get_transaction_proof(block_id, txn_index)
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
+
+    fn get_block_proof(&self, number: BlockNumber) -> FutureResult<Option<BlockProof>> {
+        let fut = self
+            .service
+            .clone()
+            .master_block_proof(number)
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
 }
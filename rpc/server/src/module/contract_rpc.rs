@@ -0,0 +1,87 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::format_err;
+use futures::future::{FutureExt, TryFutureExt};
+use starcoin_rpc_api::contract::{
+    ContractApi, ContractCall, ContractCallPayload, ContractCallResult,
+};
+use starcoin_rpc_api::FutureResult;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::Store;
+use starcoin_traits::ChainAsyncService;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_vm_types::identifier::Identifier;
+use std::sync::Arc;
+
+pub struct ContractRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    chain_service: C,
+    storage: Arc<dyn Store>,
+}
+
+impl<C> ContractRpcImpl<C>
+where
+    C: ChainAsyncService,
+{
+    pub fn new(chain_service: C, storage: Arc<dyn Store>) -> Self {
+        Self {
+            chain_service,
+            storage,
+        }
+    }
+}
+
+impl<C> ContractApi for ContractRpcImpl<C>
+where
+    C: ChainAsyncService,
+{
+    fn call(&self, call: ContractCall) -> FutureResult<ContractCallResult> {
+        let chain_service = self.chain_service.clone();
+        let storage = self.storage.clone();
+        let fut = async move {
+            let head = chain_service
+                .master_head_header()
+                .await
+                .map_err(map_err)?
+                .ok_or_else(|| map_err(format_err!("Master head header should exist.")))?;
+            let chain_state = ChainStateDB::new(storage.into_super_arc(), Some(head.state_root()));
+            let sender = call.sender.unwrap_or_else(AccountAddress::default);
+            let output = match call.payload {
+                ContractCallPayload::Function {
+                    module,
+                    function_name,
+                    type_args,
+                    args,
+                } => {
+                    let function_name =
+                        Identifier::new(function_name).map_err(to_invalid_param_err)?;
+                    starcoin_executor::execute_readonly_function(
+                        &chain_state,
+                        sender,
+                        &module,
+                        &function_name,
+                        type_args,
+                        &args,
+                        call.max_gas_amount,
+                    )
+                }
+                ContractCallPayload::Script(script) => starcoin_executor::execute_readonly_script(
+                    &chain_state,
+                    sender,
+                    &script,
+                    call.max_gas_amount,
+                ),
+            };
+            Ok(ContractCallResult {
+                status: output.status.into(),
+                gas_used: output.gas_used,
+                return_values: None,
+            })
+        };
+        Box::new(fut.boxed().compat())
+    }
+}
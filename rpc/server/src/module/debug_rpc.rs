@@ -1,27 +1,69 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::to_invalid_param_err;
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::{ensure, format_err};
+use futures::future::{FutureExt, TryFutureExt};
 use jsonrpc_core::Result;
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::LevelFilter;
 use starcoin_logger::{LogPattern, LoggerHandle};
-use starcoin_rpc_api::debug::DebugApi;
+use starcoin_rpc_api::debug::{
+    BlockReplayReport, DebugApi, IpcDebugApi, RangeReplayReport, ReplayDiff,
+};
+use starcoin_rpc_api::FutureResult;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::Store;
+use starcoin_traits::ChainAsyncService;
+use starcoin_types::block::{BlockHeader, BlockNumber};
+use starcoin_types::transaction::Transaction;
+use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
 
-pub struct DebugRpcImpl {
+/// The max number of blocks can be replayed by one `replay_blocks` call.
+const MAX_REPLAY_BLOCKS: u64 = 100;
+
+pub struct DebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
     config: Arc<NodeConfig>,
     log_handle: Arc<LoggerHandle>,
+    storage: Option<Arc<dyn Store>>,
+    chain_service: C,
 }
 
-impl DebugRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, log_handle: Arc<LoggerHandle>) -> Self {
-        Self { config, log_handle }
+impl<C> DebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    pub fn new(
+        config: Arc<NodeConfig>,
+        log_handle: Arc<LoggerHandle>,
+        storage: Option<Arc<dyn Store>>,
+        chain_service: C,
+    ) -> Self {
+        Self {
+            config,
+            log_handle,
+            storage,
+            chain_service,
+        }
+    }
+
+    fn storage(&self) -> Result<Arc<dyn Store>> {
+        self.storage
+            .clone()
+            .ok_or_else(|| map_err(format_err!("Storage is not available.")))
     }
 }
 
-impl DebugApi for DebugRpcImpl {
+impl<C> DebugApi for DebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
     fn set_log_level(&self, logger_name: Option<String>, level: String) -> Result<()> {
         let logger_name = logger_name.and_then(|s| {
             let s = s.trim();
@@ -51,4 +93,303 @@ impl DebugApi for DebugRpcImpl {
         }
         panic!("DebugApi.panic")
     }
+
+    fn replay_block(&self, block_id: HashValue) -> Result<BlockReplayReport> {
+        replay_block(self.storage()?, block_id).map_err(to_invalid_param_err)
+    }
+
+    fn replay_blocks(&self, from: BlockNumber, to: BlockNumber) -> FutureResult<RangeReplayReport> {
+        let storage = self.storage();
+        let chain_service = self.chain_service.clone();
+        let fut = async move {
+            if from == 0 || from > to {
+                return Err(to_invalid_param_err(format_err!(
+                    "Invalid block range [{}, {}], the genesis block can not be replayed.",
+                    from,
+                    to
+                )));
+            }
+            if to - from >= MAX_REPLAY_BLOCKS {
+                return Err(to_invalid_param_err(format_err!(
+                    "Can not replay more than {} blocks at once, got [{}, {}].",
+                    MAX_REPLAY_BLOCKS,
+                    from,
+                    to
+                )));
+            }
+            let storage = storage?;
+            let mut mismatched = vec![];
+            for number in from..=to {
+                // resolve the number on master, the number index of storage may point to a
+                // block of a branch.
+                let block_id = chain_service
+                    .clone()
+                    .master_block_header_by_number(number)
+                    .await
+                    .map_err(to_invalid_param_err)?
+                    .id();
+                let report =
+                    replay_block(storage.clone(), block_id).map_err(to_invalid_param_err)?;
+                if !report.is_ok() {
+                    mismatched.push(report);
+                }
+            }
+            Ok(RangeReplayReport {
+                from,
+                to,
+                mismatched,
+            })
+        };
+        Box::new(fut.boxed().compat())
+    }
+}
+
+pub struct IpcDebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    chain_service: C,
+}
+
+impl<C> IpcDebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    pub fn new(chain_service: C) -> Self {
+        Self { chain_service }
+    }
+}
+
+impl<C> IpcDebugApi for IpcDebugRpcImpl<C>
+where
+    C: ChainAsyncService + 'static,
+{
+    fn rewind(&self, block_id: HashValue) -> FutureResult<BlockHeader> {
+        let fut = self.chain_service.clone().rewind(block_id).map_err(map_err);
+        Box::new(fut.compat())
+    }
+}
+
+/// Execute the block on the state of its parent block, and diff the result with the stored
+/// block header and txn infos. Nothing is written to the storage.
+fn replay_block(storage: Arc<dyn Store>, block_id: HashValue) -> anyhow::Result<BlockReplayReport> {
+    let block = storage
+        .get_block(block_id)?
+        .ok_or_else(|| format_err!("Can not find block {:?}", block_id))?;
+    let header = block.header().clone();
+    ensure!(
+        header.number() > 0,
+        "The genesis block can not be replayed."
+    );
+    let parent = storage
+        .get_block_header_by_hash(header.parent_hash())?
+        .ok_or_else(|| format_err!("Can not find parent block {:?}", header.parent_hash()))?;
+    let stored_infos = storage.get_block_transaction_infos(block_id)?;
+
+    let mut txns = vec![Transaction::BlockMetadata(header.clone().into_metadata())];
+    txns.extend(
+        block
+            .transactions()
+            .iter()
+            .cloned()
+            .map(Transaction::UserTransaction),
+    );
+    let chain_state = ChainStateDB::new(storage.into_super_arc(), Some(parent.state_root()));
+    let mut report = BlockReplayReport {
+        block_id,
+        number: header.number(),
+        diffs: vec![],
+        error: None,
+    };
+    let (state_root, replayed_infos) =
+        match starcoin_executor::block_execute(&chain_state, txns, header.gas_limit()) {
+            Ok(result) => result,
+            Err(e) => {
+                report.error = Some(e.to_string());
+                return Ok(report);
+            }
+        };
+
+    let diffs = &mut report.diffs;
+    diff(diffs, None, "state_root", header.state_root(), state_root);
+    diff(
+        diffs,
+        None,
+        "txn_count",
+        stored_infos.len(),
+        replayed_infos.len(),
+    );
+    for (index, (stored, replayed)) in stored_infos.iter().zip(replayed_infos.iter()).enumerate() {
+        let index = Some(index as u64);
+        diff(
+            diffs,
+            index,
+            "transaction_hash",
+            stored.transaction_hash(),
+            replayed.transaction_hash(),
+        );
+        diff(
+            diffs,
+            index,
+            "state_root_hash",
+            stored.state_root_hash(),
+            replayed.state_root_hash(),
+        );
+        diff(
+            diffs,
+            index,
+            "event_root_hash",
+            stored.event_root_hash(),
+            replayed.event_root_hash(),
+        );
+        diff(
+            diffs,
+            index,
+            "gas_used",
+            stored.gas_used(),
+            replayed.gas_used(),
+        );
+        diff(
+            diffs,
+            index,
+            "major_status",
+            stored.major_status(),
+            replayed.major_status(),
+        );
+    }
+    Ok(report)
+}
+
+fn diff<T: Debug + PartialEq>(
+    diffs: &mut Vec<ReplayDiff>,
+    txn_index: Option<u64>,
+    field: &str,
+    stored: T,
+    replayed: T,
+) {
+    if stored != replayed {
+        diffs.push(ReplayDiff {
+            txn_index,
+            field: field.to_string(),
+            stored: format!("{:?}", stored),
+            replayed: format!("{:?}", replayed),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::DevRpcImpl;
+    use futures::compat::Future01CompatExt;
+    use starcoin_bus::BusActor;
+    use starcoin_chain::ChainActor;
+    use starcoin_consensus::dev::DevConsensus;
+    use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, PrivateKey};
+    use starcoin_rpc_api::dev::DevApi;
+    use starcoin_storage::{BlockStore, TransactionInfoStore};
+    use starcoin_traits::ChainAsyncService;
+    use starcoin_txpool_api::TxPoolSyncService;
+    use starcoin_types::account_address;
+    use starcoin_types::transaction::authenticator::AuthenticationKey;
+    use starcoin_types::transaction::TransactionInfo;
+    use starcoin_wallet_api::mock::MockWalletService;
+    use txpool::test_helper::start_txpool;
+
+    #[stest::test]
+    async fn test_replay_block() -> anyhow::Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let (txpool, storage) = start_txpool();
+        let startup_info = storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info should exist."))?;
+        let chain = ChainActor::<DevConsensus>::launch(
+            config.clone(),
+            startup_info,
+            storage.clone(),
+            BusActor::launch(),
+            txpool.get_service(),
+        )?;
+        let dev_api = DevRpcImpl::new(
+            config.clone(),
+            chain.clone(),
+            txpool.get_service(),
+            MockWalletService::new()?,
+        );
+        let public_key = Ed25519PrivateKey::genesis().public_key();
+        let txn = starcoin_executor::build_transfer_from_association(
+            account_address::from_public_key(&public_key),
+            AuthenticationKey::ed25519(&public_key).prefix().to_vec(),
+            0,
+            10000,
+        );
+        let txn = txn.as_signed_user_txn()?.clone();
+        assert!(txpool.get_service().add_txns(vec![txn])[0].is_ok());
+        dev_api.mine_blocks(2, Some(100)).compat().await.unwrap();
+
+        let debug_api = DebugRpcImpl::new(
+            config,
+            starcoin_logger::init_for_test(),
+            Some(storage.clone()),
+            chain.clone(),
+        );
+        let block = chain.clone().master_block_by_number(1).await?;
+        let report = debug_api.replay_block(block.id()).unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.number, 1);
+        let report = debug_api.replay_blocks(1, 2).compat().await.unwrap();
+        assert!(report.mismatched.is_empty(), "{:?}", report);
+        assert!(debug_api.replay_blocks(0, 2).compat().await.is_err());
+        assert!(debug_api.replay_blocks(2, 1).compat().await.is_err());
+        assert!(debug_api
+            .replay_blocks(1, MAX_REPLAY_BLOCKS + 1)
+            .compat()
+            .await
+            .is_err());
+
+        // corrupt the stored gas used of the user txn.
+        let mut txn_infos = storage.get_block_transaction_infos(block.id())?;
+        let info = txn_infos[1].clone();
+        txn_infos[1] = TransactionInfo::new(
+            info.transaction_hash(),
+            info.state_root_hash(),
+            info.event_root_hash(),
+            info.events().to_vec(),
+            info.gas_used() + 1,
+            info.major_status(),
+        );
+        storage.save_block_txn_info_ids(block.id(), txn_infos.iter().map(|i| i.id()).collect())?;
+        storage.save_transaction_infos(txn_infos)?;
+        let report = debug_api.replay_blocks(1, 2).compat().await.unwrap();
+        assert_eq!(report.mismatched.len(), 1);
+        assert_eq!(
+            report.mismatched[0].diffs,
+            vec![ReplayDiff {
+                txn_index: Some(1),
+                field: "gas_used".to_string(),
+                stored: format!("{:?}", info.gas_used() + 1),
+                replayed: format!("{:?}", info.gas_used()),
+            }]
+        );
+
+        // block 2 is not on master after the rewind, even it is still indexed by number.
+        IpcDebugRpcImpl::new(chain.clone())
+            .rewind(block.id())
+            .compat()
+            .await
+            .unwrap();
+        assert!(storage.get_number(2)?.is_some());
+        assert!(debug_api.replay_blocks(1, 2).compat().await.is_err());
+        assert_eq!(
+            debug_api
+                .replay_blocks(1, 1)
+                .compat()
+                .await
+                .unwrap()
+                .mismatched
+                .len(),
+            1
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,182 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::module::{map_err, map_rpc_err, to_invalid_param_err};
+use anyhow::format_err;
+use futures::future::{FutureExt, TryFutureExt};
+use starcoin_config::NodeConfig;
+use starcoin_consensus::dev::DevConsensus;
+use starcoin_rpc_api::dev::DevApi;
+use starcoin_rpc_api::FutureResult;
+use starcoin_traits::{ChainAsyncService, Consensus};
+use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::startup_info::ChainInfo;
+use starcoin_types::U256;
+use starcoin_wallet_api::WalletAsyncService;
+use std::sync::Arc;
+
+/// Max blocks mined by one `mine_blocks` call, the call blocks until all of them are connected.
+pub const MAX_MINE_BLOCKS: u64 = 1000;
+
+pub struct DevRpcImpl<C, T, A>
+where
+    C: ChainAsyncService + 'static,
+    T: TxPoolSyncService + 'static,
+    A: WalletAsyncService + 'static,
+{
+    config: Arc<NodeConfig>,
+    chain_service: C,
+    txpool_service: T,
+    account_service: A,
+}
+
+impl<C, T, A> DevRpcImpl<C, T, A>
+where
+    C: ChainAsyncService,
+    T: TxPoolSyncService,
+    A: WalletAsyncService,
+{
+    pub fn new(
+        config: Arc<NodeConfig>,
+        chain_service: C,
+        txpool_service: T,
+        account_service: A,
+    ) -> Self {
+        Self {
+            config,
+            chain_service,
+            txpool_service,
+            account_service,
+        }
+    }
+}
+
+impl<C, T, A> DevApi for DevRpcImpl<C, T, A>
+where
+    C: ChainAsyncService,
+    T: TxPoolSyncService,
+    A: WalletAsyncService,
+{
+    fn mine_blocks(&self, blocks: u64, timestamp: Option<u64>) -> FutureResult<ChainInfo> {
+        let config = self.config.clone();
+        let chain_service = self.chain_service.clone();
+        let txpool_service = self.txpool_service.clone();
+        let account_service = self.account_service.clone();
+        let max_txns = config.miner.max_block_txns();
+        let fut = async move {
+            if blocks > MAX_MINE_BLOCKS {
+                return Err(to_invalid_param_err(format_err!(
+                    "Can not mine more than {} blocks at once, got {}.",
+                    MAX_MINE_BLOCKS,
+                    blocks
+                )));
+            }
+            let author = account_service
+                .get_default_account()
+                .await
+                .map_err(|e| map_rpc_err(e.into()))?
+                .ok_or_else(|| map_err(format_err!("Default account should exist.")))?;
+            // the sealing is not checked by DevConsensus, a fixed difficulty avoids the random sleep.
+            let difficulty = U256::one();
+            for _ in 0..blocks {
+                let txns = txpool_service.get_pending_txns(Some(max_txns));
+                let block_template = chain_service
+                    .clone()
+                    .create_block_template(
+                        *author.address(),
+                        Some(author.get_auth_key().prefix().to_vec()),
+                        None,
+                        txns,
+                        timestamp,
+                    )
+                    .await
+                    .map_err(map_err)?
+                    .ok_or_else(|| map_err(format_err!("Create block template failed.")))?;
+                let version = config.pow_version(block_template.number);
+                let consensus_header = DevConsensus::solve_consensus_header(
+                    &block_template.pow_header(difficulty, version),
+                    difficulty,
+                );
+                let block = block_template.into_block(consensus_header, difficulty);
+                let block_id = block.id();
+                chain_service
+                    .clone()
+                    .try_connect(block)
+                    .await
+                    .map_err(map_err)?
+                    .map_err(|e| {
+                        to_invalid_param_err(format_err!(
+                            "Connect block {:?} failed: {}",
+                            block_id,
+                            e
+                        ))
+                    })?;
+            }
+            chain_service.master_head().await.map_err(map_err)
+        };
+        Box::new(fut.boxed().compat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::compat::Future01CompatExt;
+    use starcoin_bus::BusActor;
+    use starcoin_chain::ChainActor;
+    use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, PrivateKey};
+    use starcoin_storage::BlockStore;
+    use starcoin_types::account_address;
+    use starcoin_types::transaction::authenticator::AuthenticationKey;
+    use starcoin_wallet_api::mock::MockWalletService;
+    use txpool::test_helper::start_txpool;
+
+    #[stest::test]
+    async fn test_mine_blocks() -> anyhow::Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let (txpool, storage) = start_txpool();
+        let startup_info = storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info should exist."))?;
+        let chain = ChainActor::<DevConsensus>::launch(
+            config.clone(),
+            startup_info,
+            storage.clone(),
+            BusActor::launch(),
+            txpool.get_service(),
+        )?;
+        let dev_api = DevRpcImpl::new(
+            config,
+            chain.clone(),
+            txpool.get_service(),
+            MockWalletService::new()?,
+        );
+
+        let public_key = Ed25519PrivateKey::genesis().public_key();
+        let txn = starcoin_executor::build_transfer_from_association(
+            account_address::from_public_key(&public_key),
+            AuthenticationKey::ed25519(&public_key).prefix().to_vec(),
+            0,
+            10000,
+        );
+        let txn = txn.as_signed_user_txn()?.clone();
+        assert!(txpool.get_service().add_txns(vec![txn.clone()])[0].is_ok());
+
+        let head = dev_api.mine_blocks(2, Some(100)).compat().await.unwrap();
+        let header = chain.clone().master_head_header().await?.unwrap();
+        assert_eq!(head.get_head(), &header.id());
+        assert_eq!(header.number(), 2);
+        assert_eq!(header.timestamp(), 100);
+        let block = chain.clone().master_block_by_number(1).await?;
+        assert_eq!(block.transactions(), &[txn]);
+
+        // older than the median timestamp of recent blocks.
+        assert!(dev_api.mine_blocks(1, Some(50)).compat().await.is_err());
+        assert!(dev_api
+            .mine_blocks(MAX_MINE_BLOCKS + 1, None)
+            .compat()
+            .await
+            .is_err());
+        Ok(())
+    }
+}
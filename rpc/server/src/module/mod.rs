@@ -4,7 +4,9 @@
 use starcoin_logger::prelude::*;
 
 mod chain_rpc;
+mod contract_rpc;
 mod debug_rpc;
+mod dev_rpc;
 mod node_rpc;
 mod pubsub;
 mod state_rpc;
@@ -12,7 +14,9 @@ mod txpool_rpc;
 mod wallet_rpc;
 
 pub use self::chain_rpc::ChainRpcImpl;
-pub use self::debug_rpc::DebugRpcImpl;
+pub use self::contract_rpc::ContractRpcImpl;
+pub use self::debug_rpc::{DebugRpcImpl, IpcDebugRpcImpl};
+pub use self::dev_rpc::DevRpcImpl;
 pub use self::node_rpc::NodeRpcImpl;
 pub use self::pubsub::{PubSubImpl, PubSubService};
 pub use self::state_rpc::StateRpcImpl;
@@ -8,8 +8,10 @@ use jsonrpc_core::Result;
 use network_api::NetworkService;
 use starcoin_config::NodeConfig;
 use starcoin_network::NetworkAsyncService;
-use starcoin_rpc_api::node::{NodeApi, NodeInfo};
+use starcoin_rpc_api::node::{ColumnFamilyStats, NodeApi, NodeInfo, StorageStats};
 use starcoin_rpc_api::FutureResult;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::VEC_PREFIX_NAME;
 use starcoin_types::peer_info::PeerInfo;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -17,11 +19,42 @@ use std::sync::Arc;
 pub struct NodeRpcImpl {
     config: Arc<NodeConfig>,
     service: Option<NetworkAsyncService>,
+    db_storage: Option<Arc<DBStorage>>,
 }
 
 impl NodeRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, service: Option<NetworkAsyncService>) -> Self {
-        Self { config, service }
+    pub fn new(
+        config: Arc<NodeConfig>,
+        service: Option<NetworkAsyncService>,
+        db_storage: Option<Arc<DBStorage>>,
+    ) -> Self {
+        Self {
+            config,
+            service,
+            db_storage,
+        }
+    }
+
+    fn column_family_stats(db: &DBStorage, cf_name: &str) -> anyhow::Result<ColumnFamilyStats> {
+        let int_value = |name: &str| -> anyhow::Result<u64> {
+            Ok(db.property_int_value(cf_name, name)?.unwrap_or_default())
+        };
+        Ok(ColumnFamilyStats {
+            name: cf_name.to_string(),
+            estimate_num_keys: int_value("rocksdb.estimate-num-keys")?,
+            total_sst_files_size: int_value("rocksdb.total-sst-files-size")?,
+            mem_tables_size: int_value("rocksdb.cur-size-all-mem-tables")?,
+            internal_stats: db
+                .property_value(cf_name, "rocksdb.cfstats")?
+                .map(|stats| {
+                    stats
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .map(|line| line.to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
     }
 }
 
@@ -58,4 +91,16 @@ impl NodeApi for NodeRpcImpl {
     fn metrics(&self) -> Result<HashMap<String, String>> {
         Ok(starcoin_metrics::get_all_metrics())
     }
+
+    fn storage_stats(&self) -> Result<StorageStats> {
+        let db = self.db_storage.as_ref().ok_or_else(|| {
+            jsonrpc_core::Error::invalid_params("Storage stats is not available without db.")
+        })?;
+        let column_families = VEC_PREFIX_NAME
+            .iter()
+            .map(|cf_name| Self::column_family_stats(db, cf_name))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(map_err)?;
+        Ok(StorageStats { column_families })
+    }
 }
@@ -11,7 +11,7 @@ use starcoin_rpc_api::types::event::Event;
 use starcoin_storage::Store;
 use starcoin_types::block::Block;
 use starcoin_types::contract_event::ContractEvent;
-use starcoin_types::system_events::{NewHeadBlock, SyncBegin, SyncDone};
+use starcoin_types::system_events::{ChainReorganized, NewHeadBlock, SyncBegin, SyncDone};
 use std::sync::Arc;
 
 pub struct ChainNotifyHandlerActor {
@@ -60,6 +60,25 @@ impl actix::Actor for ChainNotifyHandlerActor {
             .then(|_res, act, _ctx| async {}.into_actor(act))
             .wait(ctx);
 
+        // subscribe reorgs first, so a reorg is handled before the new head following it.
+        self.bus
+            .clone()
+            .channel::<ChainReorganized>()
+            .into_actor(self)
+            .then(|res, act, ctx| {
+                match res {
+                    Err(e) => {
+                        error!(target: "pubsub", "fail to start event subscription actor, err: {}", &e);
+                        ctx.terminate();
+                    }
+                    Ok(r) => {
+                        ctx.add_stream(r);
+                    }
+                };
+                async {}.into_actor(act)
+            })
+            .wait(ctx);
+
         self.bus
             .clone()
             .channel::<NewHeadBlock>()
@@ -100,18 +119,40 @@ impl actix::StreamHandler<NewHeadBlock> for ChainNotifyHandlerActor {
         if self.broadcast_txn {
             let NewHeadBlock(block_detail) = item;
             let block = block_detail.get_block();
-            // notify header.
-            self.notify_new_block(block);
-            // notify events
-            if let Err(e) = self.notify_events(block, self.store.clone()) {
-                error!(target: "pubsub", "fail to notify events to client, err: {}", &e);
+            self.notify_block(block, false);
+        }
+    }
+}
+
+impl actix::StreamHandler<ChainReorganized> for ChainNotifyHandlerActor {
+    fn handle(&mut self, item: ChainReorganized, _ctx: &mut Self::Context) {
+        if self.broadcast_txn {
+            let ChainReorganized { enacted, retracted } = item;
+            // retract from the old head, then enact the new blocks except the new head,
+            // which is notified by `NewHeadBlock`.
+            for block in retracted.iter().rev() {
+                self.notify_block(block, true);
+            }
+            if let Some((_head, blocks)) = enacted.split_last() {
+                for block in blocks {
+                    self.notify_block(block, false);
+                }
             }
         }
     }
 }
 
 impl ChainNotifyHandlerActor {
-    pub fn notify_new_block(&self, block: &Block) {
+    fn notify_block(&self, block: &Block, removed: bool) {
+        // notify header.
+        self.notify_new_block(block, removed);
+        // notify events
+        if let Err(e) = self.notify_events(block, self.store.clone(), removed) {
+            error!(target: "pubsub", "fail to notify events to client, err: {}", &e);
+        }
+    }
+
+    pub fn notify_new_block(&self, block: &Block, removed: bool) {
         for subscriber in self.new_header_subscribers.read().values() {
             let thin_block = pubsub::ThinBlock::new(
                 block.header().clone(),
@@ -120,7 +161,8 @@ impl ChainNotifyHandlerActor {
                     .iter()
                     .map(|t| t.crypto_hash())
                     .collect(),
-            );
+            )
+            .with_removed(removed);
             notify::notify(
                 subscriber,
                 pubsub::Result::Block(Box::new(thin_block.clone())),
@@ -128,7 +170,7 @@ impl ChainNotifyHandlerActor {
         }
     }
 
-    pub fn notify_events(&self, block: &Block, store: Arc<dyn Store>) -> Result<()> {
+    pub fn notify_events(&self, block: &Block, store: Arc<dyn Store>, removed: bool) -> Result<()> {
         // let block = store.get_block(block_id)?;
         // if block.is_none() {
         //     return Ok(());
@@ -159,7 +201,8 @@ impl ChainNotifyHandlerActor {
                 .take(filter.limit.unwrap_or(std::usize::MAX));
             let mut to_send_events = Vec::new();
             for evt in filtered_events {
-                let e = Event::new(Some(block_id), Some(block_number), None, None, evt);
+                let e = Event::new(Some(block_id), Some(block_number), None, None, evt)
+                    .with_removed(removed);
                 to_send_events.push(pubsub::Result::Event(Box::new(e)));
             }
             to_send_events.reverse();
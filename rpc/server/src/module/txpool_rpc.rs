@@ -1,10 +1,13 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::format_err;
 use futures::future::TryFutureExt;
 use starcoin_rpc_api::{txpool::TxPoolApi, FutureResult};
 use starcoin_txpool_api::TxPoolSyncService;
-use starcoin_types::transaction::SignedUserTransaction;
+use starcoin_types::transaction::{SignedUserTransaction, TransactionStatus};
+use starcoin_types::vm_error::StatusCode;
 
 /// Re-export the API
 pub use starcoin_rpc_api::txpool::*;
@@ -44,13 +47,49 @@ where
         let result = self.service.next_sequence_number(address);
         Box::new(futures::future::ok(result).compat())
     }
+
+    fn dry_run(&self, txn: DryRunTransaction) -> FutureResult<DryRunOutput> {
+        let result = txn
+            .into_signed_txn()
+            .map(|(txn, check_signature)| self.service.dry_run(txn, check_signature).into())
+            .map_err(to_invalid_param_err);
+        Box::new(jsonrpc_core::futures::done(result))
+    }
+
+    fn estimate_gas(&self, txn: DryRunTransaction) -> FutureResult<u64> {
+        let result = txn
+            .into_signed_txn()
+            .and_then(|(txn, check_signature)| {
+                let output = self.service.dry_run_affordable(txn, check_signature)?;
+                match output.status() {
+                    TransactionStatus::Keep(status)
+                        if status.major_status == StatusCode::EXECUTED =>
+                    {
+                        let gas_used = output.gas_used();
+                        Ok(gas_used + gas_used * GAS_ESTIMATE_MARGIN_PERCENT / 100)
+                    }
+                    status => Err(format_err!("Txn execute failed: {:?}", status)),
+                }
+            })
+            .map_err(to_invalid_param_err);
+        Box::new(jsonrpc_core::futures::done(result))
+    }
+
+    fn gas_price(&self) -> FutureResult<u64> {
+        let result = self.service.gas_price().map_err(map_err);
+        Box::new(jsonrpc_core::futures::done(result))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::compat::Future01CompatExt;
     use jsonrpc_core::IoHandler;
+    use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, PrivateKey};
     use starcoin_txpool_mock_service::MockTxPoolService;
+    use starcoin_types::account_address;
+    use starcoin_types::transaction::authenticator::AuthenticationKey;
     use tokio01::prelude::Future;
 
     #[test]
@@ -79,4 +118,31 @@ mod tests {
             Some(response.to_string())
         );
     }
+
+    #[stest::test]
+    async fn test_estimate_gas() -> anyhow::Result<()> {
+        let (txpool, _storage) = txpool::test_helper::start_txpool();
+        let txpool_rpc = TxPoolRpcImpl::new(txpool.get_service());
+        let public_key = Ed25519PrivateKey::genesis().public_key();
+        let txn = starcoin_executor::build_transfer_from_association(
+            account_address::from_public_key(&public_key),
+            AuthenticationKey::ed25519(&public_key).prefix().to_vec(),
+            0,
+            10000,
+        );
+        let txn = txn.as_signed_user_txn()?.clone();
+        let gas_used = txpool.get_service().dry_run(txn.clone(), true).gas_used();
+
+        let max_gas = txpool_rpc
+            .estimate_gas(DryRunTransaction::Signed(txn))
+            .compat()
+            .await
+            .unwrap();
+        assert_eq!(
+            max_gas,
+            gas_used + gas_used * GAS_ESTIMATE_MARGIN_PERCENT / 100
+        );
+        assert_eq!(txpool_rpc.gas_price().compat().await.unwrap(), 1);
+        Ok(())
+    }
 }
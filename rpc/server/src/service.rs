@@ -19,14 +19,16 @@ pub struct RpcService {
 }
 
 impl RpcService {
+    /// Serve `io_handler` over IPC, and `public_io_handler` over HTTP, TCP and WebSocket.
     pub fn new(
         config: Arc<NodeConfig>,
         io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+        public_io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
     ) -> RpcService {
-        let ipc = Self::start_ipc(&config, io_handler.clone());
+        let ipc = Self::start_ipc(&config, io_handler);
         let http = match &config.rpc.http_address {
             Some(address) => {
-                let http = jsonrpc_http_server::ServerBuilder::new(io_handler.clone())
+                let http = jsonrpc_http_server::ServerBuilder::new(public_io_handler.clone())
                     .meta_extractor(RpcExtractor)
                     .cors(DomainsValidation::AllowOnly(vec![
                         AccessControlAllowOrigin::Null,
@@ -44,7 +46,7 @@ impl RpcService {
         };
         let tcp_server = match &config.rpc.tcp_address {
             Some(address) => {
-                let tcp_server = jsonrpc_tcp_server::ServerBuilder::new(io_handler.clone())
+                let tcp_server = jsonrpc_tcp_server::ServerBuilder::new(public_io_handler.clone())
                     .session_meta_extractor(RpcExtractor)
                     .start(address)
                     .expect("rpc: start tcp server should ok");
@@ -57,7 +59,7 @@ impl RpcService {
         let ws_server = match &config.rpc.ws_address {
             None => None,
             Some(address) => {
-                let ws_server = jsonrpc_ws_server::ServerBuilder::new(io_handler)
+                let ws_server = jsonrpc_ws_server::ServerBuilder::new(public_io_handler)
                     .session_meta_extractor(WsExtractor)
                     .max_payload(config.rpc.max_request_body_size)
                     .start(address)
@@ -8,7 +8,7 @@ mod state_tree;
 mod state_tree_test;
 
 pub use starcoin_state_store_api::{StateNode, StateNodeStore};
-pub use state_tree::StateTree;
+pub use state_tree::{StateNodeChanges, StateTree};
 
 use starcoin_crypto::HashValue;
 
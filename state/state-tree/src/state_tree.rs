@@ -15,6 +15,20 @@ use std::ops::DerefMut;
 
 use std::sync::{Arc, Mutex, RwLock};
 
+/// Keys of the state nodes written and the state nodes become stale by flushes of state trees.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateNodeChanges {
+    pub new_nodes: Vec<HashValue>,
+    pub stale_nodes: Vec<HashValue>,
+}
+
+impl StateNodeChanges {
+    pub fn extend(&mut self, other: StateNodeChanges) {
+        self.new_nodes.extend(other.new_nodes);
+        self.stale_nodes.extend(other.stale_nodes);
+    }
+}
+
 pub struct StateCache {
     root_hash: HashValue,
     change_set: TreeUpdateBatch,
@@ -193,17 +207,31 @@ impl StateTree {
 
     /// commit the state change into underline storage.
     pub fn flush(&self) -> Result<()> {
+        self.flush_with_node_changes().map(|_| ())
+    }
+
+    /// commit the state change into underline storage,
+    /// and return the keys of nodes which are written or become stale since last flush.
+    pub fn flush_with_node_changes(&self) -> Result<StateNodeChanges> {
         let (root_hash, change_sets) = self.get_change_sets();
 
         let mut node_map = BTreeMap::new();
         for (nk, n) in change_sets.node_batch.into_iter() {
             node_map.insert(nk, StateNode(n));
         }
+        let new_nodes = node_map.keys().cloned().collect();
         self.storage.write_nodes(node_map).unwrap();
         // and then advance the storage root hash
         *self.storage_root_hash.write().unwrap() = root_hash;
         self.cache.lock().unwrap().reset(root_hash);
-        Ok(())
+        Ok(StateNodeChanges {
+            new_nodes,
+            stale_nodes: change_sets
+                .stale_node_index_batch
+                .into_iter()
+                .map(|stale_node| stale_node.node_key)
+                .collect(),
+        })
     }
 
     /// Dump tree to state set.
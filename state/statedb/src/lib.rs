@@ -10,7 +10,7 @@ use scs::SCSCodec;
 use starcoin_crypto::{hash::PlainCryptoHash, HashValue};
 use starcoin_logger::prelude::*;
 use starcoin_state_tree::mock::MockStateNodeStore;
-use starcoin_state_tree::{StateNodeStore, StateTree};
+use starcoin_state_tree::{StateNodeChanges, StateNodeStore, StateTree};
 use starcoin_types::{
     access_path::{self, AccessPath, DataType},
     account_address::AccountAddress,
@@ -168,14 +168,16 @@ impl AccountStateObject {
         Ok(Self::build_state(trees))
     }
 
-    pub fn flush(&self) -> Result<()> {
+    /// flush all trees of the account, return the written and stale nodes.
+    pub fn flush(&self) -> Result<StateNodeChanges> {
         let trees = self.trees.lock();
+        let mut node_changes = StateNodeChanges::default();
         for tree in trees.iter() {
             if let Some(tree) = tree {
-                tree.flush()?;
+                node_changes.extend(tree.flush_with_node_changes()?);
             }
         }
-        Ok(())
+        Ok(node_changes)
     }
 
     fn build_state(trees: MutexGuard<Vec<Option<StateTree>>>) -> AccountState {
@@ -202,6 +204,8 @@ pub struct ChainStateDB {
     state_tree: StateTree,
     cache: Mutex<LruCache<HashValue, CacheItem>>,
     updates: RwLock<HashSet<AccountAddress>>,
+    /// nodes written or become stale since last `take_node_changes`.
+    node_changes: Mutex<StateNodeChanges>,
 }
 
 static DEFAULT_CACHE_SIZE: usize = 10240;
@@ -217,6 +221,7 @@ impl ChainStateDB {
             state_tree: StateTree::new(store, root_hash),
             cache: Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE)),
             updates: RwLock::new(HashSet::new()),
+            node_changes: Mutex::new(StateNodeChanges::default()),
         }
     }
 
@@ -227,9 +232,23 @@ impl ChainStateDB {
             state_tree: StateTree::new(self.store.clone(), Some(root_hash)),
             cache: Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE)),
             updates: RwLock::new(HashSet::new()),
+            node_changes: Mutex::new(StateNodeChanges::default()),
         }
     }
 
+    /// Take the keys of state nodes which are written or become stale in flushes since last
+    /// take. Stale nodes are still readable from history state roots, it's up to caller to
+    /// prune them.
+    pub fn take_node_changes(&self) -> StateNodeChanges {
+        std::mem::take(&mut *self.node_changes.lock())
+    }
+
+    fn flush_state_tree(&self) -> Result<()> {
+        let node_changes = self.state_tree.flush_with_node_changes()?;
+        self.node_changes.lock().extend(node_changes);
+        Ok(())
+    }
+
     fn new_state_tree(&self, root_hash: HashValue) -> StateTree {
         StateTree::new(self.store.clone(), Some(root_hash))
     }
@@ -430,14 +449,18 @@ impl ChainStateWriter for ChainStateDB {
                     (Some(storage_root), Some(state_set)) => {
                         let state_tree = self.new_state_tree(*storage_root);
                         state_tree.apply(state_set.clone())?;
-                        state_tree.flush()?;
+                        self.node_changes
+                            .lock()
+                            .extend(state_tree.flush_with_node_changes()?);
                         Some(state_tree.root_hash())
                     }
                     (Some(storage_root), None) => Some(*storage_root),
                     (None, Some(state_set)) => {
                         let state_tree = StateTree::new(self.store.clone(), None);
                         state_tree.apply(state_set.clone())?;
-                        state_tree.flush()?;
+                        self.node_changes
+                            .lock()
+                            .extend(state_tree.flush_with_node_changes()?);
                         Some(state_tree.root_hash())
                     }
                     (None, None) => None,
@@ -450,8 +473,7 @@ impl ChainStateWriter for ChainStateDB {
                 .put(address_hash.clone(), new_account_state.try_into()?);
         }
         self.state_tree.commit()?;
-        self.state_tree.flush()?;
-        Ok(())
+        self.flush_state_tree()
     }
 
     fn apply_write_set(&self, write_set: WriteSet) -> Result<()> {
@@ -494,11 +516,12 @@ impl ChainStateWriter for ChainStateDB {
         let mut locks = self.updates.write();
         for address in locks.iter() {
             let account_state_object = self.get_account_state_object(address, false)?;
-            account_state_object.flush()?;
+            let node_changes = account_state_object.flush()?;
+            self.node_changes.lock().extend(node_changes);
         }
         locks.clear();
         // self tree flush
-        self.state_tree.flush()
+        self.flush_state_tree()
     }
 }
 
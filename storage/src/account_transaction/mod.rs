@@ -0,0 +1,313 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::batch::WriteBatch;
+use crate::define_storage;
+use crate::storage::{CodecStorage, KeyCodec, StorageInstance, ValueCodec};
+use crate::upgrade::{get_cf_value, master_block_ids, MigrationWriter};
+use crate::{
+    ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME, ACCOUNT_TRANSACTION_PREFIX_NAME, BLOCK_BODY_PREFIX_NAME,
+    BLOCK_EVENT_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME,
+};
+use anyhow::{ensure, format_err, Error, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use crypto::HashValue;
+use rocksdb::DB;
+use scs::SCSCodec;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_config::{ReceivedPaymentEvent, SentPaymentEvent};
+use starcoin_types::account_transaction::{AccountTransactionInfo, AccountTransactionKind};
+use starcoin_types::block::{BlockBody, BlockHeader, BlockNumber};
+use starcoin_types::contract_event::{ContractEvent, ContractEventInfo};
+use starcoin_types::language_storage::{TypeTag, CORE_CODE_ADDRESS};
+use starcoin_types::move_resource::MoveResource;
+use starcoin_types::transaction::{SignedUserTransaction, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::mem::size_of;
+use std::sync::Arc;
+
+/// Transaction history of accounts, each account's history is an append only list,
+/// blocks are appended when they become part of master chain and reverted from the
+/// tail when they are retracted by a reorg.
+pub trait AccountTransactionStore {
+    /// Append `transactions` to the history of the accounts, in order.
+    /// The `index` of each transaction is assigned by the store.
+    fn append_account_transactions(
+        &self,
+        transactions: Vec<(AccountAddress, AccountTransactionInfo)>,
+    ) -> Result<()>;
+    /// Remove transactions of block `block_id` from the tail of `addresses`' history.
+    fn revert_account_transactions(
+        &self,
+        block_id: HashValue,
+        addresses: Vec<AccountAddress>,
+    ) -> Result<()>;
+    /// Get the count of transactions in the history of `address`.
+    fn get_account_transaction_count(&self, address: AccountAddress) -> Result<u64>;
+    /// Get at most `limit` transactions of `address` from newest to oldest,
+    /// starting at index `start` (inclusive), or at the newest one if `start` is None.
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>>;
+}
+
+/// Key of account transaction history: account address and index in history.
+pub type AccountTransactionKey = (AccountAddress, u64);
+
+define_storage!(
+    AccountTransactionInnerStorage,
+    AccountTransactionKey,
+    AccountTransactionInfo,
+    ACCOUNT_TRANSACTION_PREFIX_NAME
+);
+
+define_storage!(
+    AccountTransactionCountStorage,
+    AccountAddress,
+    u64,
+    ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME
+);
+
+pub struct AccountTransactionStorage {
+    txn_store: AccountTransactionInnerStorage,
+    count_store: AccountTransactionCountStorage,
+}
+
+impl AccountTransactionStorage {
+    pub fn new(instance: StorageInstance) -> Self {
+        Self {
+            txn_store: AccountTransactionInnerStorage::new(instance.clone()),
+            count_store: AccountTransactionCountStorage::new(instance),
+        }
+    }
+}
+
+impl KeyCodec for AccountAddress {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self, Error> {
+        Ok(AccountAddress::try_from(data)?)
+    }
+}
+
+impl KeyCodec for AccountTransactionKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (address, index) = self;
+        let mut encoded_key = address.to_vec();
+        encoded_key.extend_from_slice(&index.to_be_bytes());
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self, Error> {
+        ensure!(
+            data.len() == AccountAddress::LENGTH + size_of::<u64>(),
+            "Unexpected account transaction key len {}.",
+            data.len()
+        );
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let index = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+        Ok((address, index))
+    }
+}
+
+impl ValueCodec for AccountTransactionInfo {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+impl ValueCodec for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+impl AccountTransactionStore for AccountTransactionStorage {
+    fn append_account_transactions(
+        &self,
+        transactions: Vec<(AccountAddress, AccountTransactionInfo)>,
+    ) -> Result<()> {
+        let mut counts: HashMap<AccountAddress, u64> = HashMap::new();
+        let mut batch = WriteBatch::new();
+        for (address, mut txn_info) in transactions {
+            let index = match counts.get(&address) {
+                Some(count) => *count,
+                None => self.get_account_transaction_count(address)?,
+            };
+            txn_info.index = index;
+            batch.put((address, index), txn_info)?;
+            counts.insert(address, index + 1);
+        }
+        self.txn_store.write_batch(batch)?;
+
+        let mut count_batch = WriteBatch::new();
+        for (address, count) in counts {
+            count_batch.put(address, count)?;
+        }
+        self.count_store.write_batch(count_batch)
+    }
+
+    fn revert_account_transactions(
+        &self,
+        block_id: HashValue,
+        addresses: Vec<AccountAddress>,
+    ) -> Result<()> {
+        let mut batch = WriteBatch::new();
+        let mut count_batch = WriteBatch::new();
+        for address in addresses {
+            let origin_count = self.get_account_transaction_count(address)?;
+            let mut count = origin_count;
+            while count > 0 {
+                match self.txn_store.get((address, count - 1))? {
+                    Some(txn_info) if txn_info.block_hash == block_id => {
+                        batch.delete((address, count - 1))?;
+                        count -= 1;
+                    }
+                    _ => break,
+                }
+            }
+            if count != origin_count {
+                count_batch.put(address, count)?;
+            }
+        }
+        self.txn_store.write_batch(batch)?;
+        self.count_store.write_batch(count_batch)
+    }
+
+    fn get_account_transaction_count(&self, address: AccountAddress) -> Result<u64> {
+        Ok(self.count_store.get(address)?.unwrap_or(0))
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>> {
+        let count = self.get_account_transaction_count(address)?;
+        if count == 0 || limit == 0 {
+            return Ok(vec![]);
+        }
+        let start = start.map(|start| start.min(count - 1)).unwrap_or(count - 1);
+        let end = start.saturating_sub(limit - 1);
+        let mut txns = vec![];
+        for index in (end..=start).rev() {
+            if let Some(txn_info) = self.txn_store.get((address, index))? {
+                txns.push(txn_info);
+            }
+        }
+        Ok(txns)
+    }
+}
+
+/// Accounts involved in the transactions of a block: the sender of each user transaction,
+/// and the owners of payment events emitted by the block, ordered by transaction index.
+pub fn block_account_transactions(
+    block_id: HashValue,
+    block_number: BlockNumber,
+    user_txns: &[SignedUserTransaction],
+    events: &[ContractEventInfo],
+) -> Vec<(AccountAddress, AccountTransactionInfo)> {
+    let mut transactions = vec![];
+    // the block metadata txn is the first txn of block.
+    for (idx, user_txn) in user_txns.iter().enumerate() {
+        let txn_hash = Transaction::UserTransaction(user_txn.clone()).id();
+        transactions.push((
+            user_txn.sender(),
+            AccountTransactionInfo::new(
+                AccountTransactionKind::Sent,
+                block_id,
+                block_number,
+                txn_hash,
+                (idx + 1) as u64,
+            ),
+        ));
+    }
+    for event_info in events {
+        if let Some((address, kind)) = payment_event_account(&event_info.event) {
+            transactions.push((
+                address,
+                AccountTransactionInfo::new(
+                    kind,
+                    block_id,
+                    block_number,
+                    event_info.transaction_hash,
+                    event_info.transaction_index,
+                ),
+            ));
+        }
+    }
+    transactions.sort_by_key(|(_, txn_info)| txn_info.transaction_index);
+    let mut indexed = HashSet::new();
+    transactions
+        .retain(|(address, txn_info)| indexed.insert((*address, txn_info.transaction_hash)));
+    transactions
+}
+
+/// Return the owner of the event handle and the direction if `event` is a payment event.
+fn payment_event_account(
+    event: &ContractEvent,
+) -> Option<(AccountAddress, AccountTransactionKind)> {
+    if let TypeTag::Struct(struct_tag) = event.type_tag() {
+        if struct_tag.address != CORE_CODE_ADDRESS
+            || struct_tag.module.as_str() != ReceivedPaymentEvent::MODULE_NAME
+        {
+            return None;
+        }
+        let kind = if struct_tag.name.as_str() == ReceivedPaymentEvent::STRUCT_NAME {
+            AccountTransactionKind::Received
+        } else if struct_tag.name.as_str() == SentPaymentEvent::STRUCT_NAME {
+            AccountTransactionKind::Sent
+        } else {
+            return None;
+        };
+        return Some((event.key().get_creator_address(), kind));
+    }
+    None
+}
+
+/// Backfill the transaction history of accounts from the master blocks saved before schema
+/// version 3, the column families are new in that version so the history starts empty.
+pub(crate) fn backfill_account_transactions(db: &DB) -> Result<()> {
+    let mut counts: HashMap<AccountAddress, u64> = HashMap::new();
+    let mut writer = MigrationWriter::new(db);
+    for block_id in master_block_ids(db)? {
+        let header: BlockHeader = get_cf_value(db, BLOCK_HEADER_PREFIX_NAME, &block_id)?
+            .ok_or_else(|| format_err!("Can not find block header {:?}", block_id))?;
+        let body: BlockBody = get_cf_value(db, BLOCK_BODY_PREFIX_NAME, &block_id)?
+            .ok_or_else(|| format_err!("Can not find block body {:?}", block_id))?;
+        let user_txns: Vec<SignedUserTransaction> = body.into();
+        let events: Vec<ContractEventInfo> =
+            get_cf_value(db, BLOCK_EVENT_PREFIX_NAME, &block_id)?.unwrap_or_default();
+        for (address, mut txn_info) in
+            block_account_transactions(block_id, header.number(), &user_txns, &events)
+        {
+            let count = counts.entry(address).or_insert(0);
+            txn_info.index = *count;
+            writer.put(
+                ACCOUNT_TRANSACTION_PREFIX_NAME,
+                &(address, *count),
+                &txn_info,
+            )?;
+            *count += 1;
+        }
+    }
+    for (address, count) in counts {
+        writer.put(ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME, &address, &count)?;
+    }
+    writer.flush()
+}
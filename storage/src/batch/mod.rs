@@ -37,3 +37,53 @@ impl WriteBatch {
         Ok(())
     }
 }
+
+/// A group of write operations across multiple column families,
+/// which is written to the db atomically.
+#[derive(Debug, Default, Clone)]
+pub struct WriteBatchWithColumn {
+    pub rows: BTreeMap<String, WriteBatch>,
+}
+
+impl WriteBatchWithColumn {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an insert/update operation of column family `prefix_name` to the batch.
+    pub fn put(&mut self, prefix_name: &str, key: Vec<u8>, value: Vec<u8>) {
+        self.column_mut(prefix_name)
+            .rows
+            .insert(key, WriteOp::Value(value));
+    }
+
+    /// Adds a delete operation of column family `prefix_name` to the batch.
+    pub fn delete(&mut self, prefix_name: &str, key: Vec<u8>) {
+        self.column_mut(prefix_name)
+            .rows
+            .insert(key, WriteOp::Deletion);
+    }
+
+    /// Merges a single column family batch into the batch, later operations override earlier ones.
+    pub fn extend(&mut self, prefix_name: &str, batch: WriteBatch) {
+        self.column_mut(prefix_name).rows.extend(batch.rows);
+    }
+
+    /// Get the pending operation of `key` in column family `prefix_name`.
+    pub fn get(&self, prefix_name: &str, key: &[u8]) -> Option<&WriteOp> {
+        self.rows
+            .get(prefix_name)
+            .and_then(|batch| batch.rows.get(key))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.values().all(|batch| batch.rows.is_empty())
+    }
+
+    fn column_mut(&mut self, prefix_name: &str) -> &mut WriteBatch {
+        self.rows
+            .entry(prefix_name.to_string())
+            .or_insert_with(WriteBatch::new)
+    }
+}
@@ -403,6 +403,10 @@ impl BlockStorage {
         self.block_txn_infos_store.put(block_id, txn_info_ids)
     }
 
+    pub fn get_block_sons(&self, block_id: HashValue) -> Result<Vec<HashValue>> {
+        Ok(self.sons_store.read().get(block_id)?.unwrap_or_default())
+    }
+
     fn get_relationship(
         &self,
         block_id1: HashValue,
@@ -1,42 +1,157 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
-use crate::metrics::{record_metrics, CACHE_ITEMS};
-use crate::storage::{CacheObject, InnerStore, WriteOp};
-use anyhow::{Error, Result};
+use crate::batch::{WriteBatch, WriteBatchWithColumn};
+use crate::metrics::{record_metrics, CACHE_BYTES, CACHE_COUNTERS, CACHE_ITEMS};
+use crate::storage::{CacheObject, InnerStore, IterDirection, IterOptions, KVIterator, WriteOp};
+use anyhow::{ensure, Error, Result};
 use lru::LruCache;
 use parking_lot::Mutex;
+use starcoin_config::StorageConfig;
+use std::collections::{BTreeMap, HashMap};
 
-const LRU_CACHE_DEFAULT_SIZE: usize = 65535;
+/// Default cache capacity in bytes of each column family.
+const DEFAULT_CACHE_CAPACITY: usize = 64 * 1024 * 1024;
+/// Estimated memory used by a cache entry besides its key and value.
+const CACHE_ENTRY_OVERHEAD: usize = 64;
 
+/// LRU cache of a column family, bounded by the bytes of its entries.
+struct ColumnCache {
+    cache: LruCache<Vec<u8>, CacheObject>,
+    capacity: usize,
+    size: usize,
+}
+
+impl ColumnCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            cache: LruCache::unbounded(),
+            capacity,
+            size: 0,
+        }
+    }
+
+    fn entry_size(key: &[u8], obj: &CacheObject) -> usize {
+        let value_size = match obj {
+            CacheObject::Value(value) => value.len(),
+            CacheObject::None => 0,
+        };
+        key.len() + value_size + CACHE_ENTRY_OVERHEAD
+    }
+
+    fn get(&mut self, key: &[u8]) -> Option<&CacheObject> {
+        self.cache.get(&key.to_vec())
+    }
+
+    fn put(&mut self, key: Vec<u8>, obj: CacheObject) {
+        self.pop(&key);
+        let entry_size = Self::entry_size(key.as_slice(), &obj);
+        if entry_size > self.capacity {
+            return;
+        }
+        self.size += entry_size;
+        self.cache.put(key, obj);
+        while self.size > self.capacity {
+            match self.cache.pop_lru() {
+                Some((key, obj)) => self.size -= Self::entry_size(key.as_slice(), &obj),
+                None => break,
+            }
+        }
+    }
+
+    fn pop(&mut self, key: &[u8]) {
+        if let Some(obj) = self.cache.pop(&key.to_vec()) {
+            self.size -= Self::entry_size(key, &obj);
+        }
+    }
+}
+
+/// In memory storage, also used as the cache of `DBStorage`.
+/// Every column family has its own LRU cache bounded in bytes, so large entries of one column
+/// family can not evict the entries of others. A column family with zero capacity is not cached,
+/// so never use zero capacity when the `CacheStorage` is used without a db.
 pub struct CacheStorage {
-    cache: Mutex<LruCache<Vec<u8>, CacheObject>>,
+    columns: Mutex<HashMap<String, ColumnCache>>,
+    default_capacity: usize,
+    column_capacities: BTreeMap<String, usize>,
 }
 
 impl CacheStorage {
     pub fn new() -> Self {
+        Self::new_with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Create a cache storage with `size` bytes capacity of each column family.
+    pub fn new_with_capacity(size: usize) -> Self {
         CacheStorage {
-            cache: Mutex::new(LruCache::new(LRU_CACHE_DEFAULT_SIZE)),
+            columns: Mutex::new(HashMap::new()),
+            default_capacity: size,
+            column_capacities: BTreeMap::new(),
         }
     }
-    pub fn new_with_capacity(size: usize) -> Self {
+
+    /// Create a cache storage with the capacity of each column family in `config`.
+    pub fn new_with_config(config: &StorageConfig) -> Self {
+        let column_capacities = crate::VEC_PREFIX_NAME
+            .iter()
+            .map(|cf| (cf.to_string(), config.column_cache_size(cf)))
+            .collect();
         CacheStorage {
-            cache: Mutex::new(LruCache::new(size)),
+            columns: Mutex::new(HashMap::new()),
+            default_capacity: config.cache_size(),
+            column_capacities,
         }
     }
+
+    fn capacity(&self, prefix_name: &str) -> usize {
+        self.column_capacities
+            .get(prefix_name)
+            .cloned()
+            .unwrap_or(self.default_capacity)
+    }
+
+    /// Run `f` with the cache of column family `prefix_name`, return None if the column
+    /// family is not cached.
+    fn with_column<R, F>(&self, prefix_name: &str, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut ColumnCache) -> R,
+    {
+        let capacity = self.capacity(prefix_name);
+        if capacity == 0 {
+            return None;
+        }
+        let mut columns = self.columns.lock();
+        let column = columns
+            .entry(prefix_name.to_string())
+            .or_insert_with(|| ColumnCache::new(capacity));
+        let result = f(column);
+        CACHE_ITEMS
+            .with_label_values(&[prefix_name])
+            .set(column.cache.len() as i64);
+        CACHE_BYTES
+            .with_label_values(&[prefix_name])
+            .set(column.size as i64);
+        Some(result)
+    }
+
     pub fn get_obj(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<CacheObject>> {
         record_metrics("cache", prefix_name, "get").end_with(|| {
-            compose_key(prefix_name.to_string(), key)
-                .and_then(|key| Ok(self.cache.lock().get(&key).cloned()))
+            let obj = self
+                .with_column(prefix_name, |column| column.get(key.as_slice()).cloned())
+                .and_then(|obj| obj);
+            if self.capacity(prefix_name) > 0 {
+                let result = if obj.is_some() { "hit" } else { "miss" };
+                CACHE_COUNTERS
+                    .with_label_values(&[prefix_name, result])
+                    .inc();
+            }
+            Ok(obj)
         })
     }
 
     pub fn put_obj(&self, prefix_name: &str, key: Vec<u8>, obj: CacheObject) -> Result<()> {
         record_metrics("cache", prefix_name, "put").end_with(|| {
-            let mut cache = self.cache.lock();
-            cache.put(compose_key(prefix_name.to_string(), key)?, obj);
-            CACHE_ITEMS.set(cache.len() as u64);
+            self.with_column(prefix_name, |column| column.put(key, obj));
             Ok(())
         })
     }
@@ -69,34 +184,24 @@ impl Default for CacheStorage {
 
 impl InnerStore for CacheStorage {
     fn get(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
-        record_metrics("cache", prefix_name, "get").end_with(|| {
-            compose_key(prefix_name.to_string(), key)
-                .and_then(|key| Ok(self.cache.lock().get(&key).and_then(|v| v.into())))
-        })
+        self.get_obj(prefix_name, key)
+            .map(|obj| obj.and_then(|obj| (&obj).into()))
     }
 
     fn put(&self, prefix_name: &str, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
-        record_metrics("cache", prefix_name, "put").end_with(|| {
-            let mut cache = self.cache.lock();
-            cache.put(
-                compose_key(prefix_name.to_string(), key)?,
-                CacheObject::Value(value),
-            );
-            CACHE_ITEMS.set(cache.len() as u64);
-            Ok(())
-        })
+        self.put_obj(prefix_name, key, CacheObject::Value(value))
     }
 
     fn contains_key(&self, prefix_name: &str, key: Vec<u8>) -> Result<bool> {
         record_metrics("cache", prefix_name, "contains_key").end_with(|| {
-            let compose = compose_key(prefix_name.to_string(), key)?;
-            Ok(self.cache.lock().contains(&compose))
+            Ok(self
+                .with_column(prefix_name, |column| column.cache.contains(&key))
+                .unwrap_or(false))
         })
     }
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()> {
         record_metrics("cache", prefix_name, "remove").end_with(|| {
-            let compose = compose_key(prefix_name.to_string(), key)?;
-            self.cache.lock().pop(&compose);
+            self.with_column(prefix_name, |column| column.pop(key.as_slice()));
             Ok(())
         })
     }
@@ -113,22 +218,66 @@ impl InnerStore for CacheStorage {
         })
     }
 
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()> {
+        for (prefix_name, batch) in batch.rows {
+            self.write_batch(prefix_name.as_str(), batch)?;
+        }
+        Ok(())
+    }
+
     fn get_len(&self) -> Result<u64, Error> {
-        Ok(self.cache.lock().len() as u64)
+        Ok(self
+            .columns
+            .lock()
+            .values()
+            .map(|column| column.cache.len() as u64)
+            .sum())
     }
 
     fn keys(&self) -> Result<Vec<Vec<u8>>, Error> {
         let mut all_keys = vec![];
-        for (key, _) in self.cache.lock().iter() {
-            all_keys.push(key.to_vec());
+        for (prefix_name, column) in self.columns.lock().iter() {
+            for (key, _) in column.cache.iter() {
+                all_keys.push(compose_key(prefix_name.clone(), key.to_vec())?);
+            }
         }
         Ok(all_keys)
     }
+
+    fn iter<'a>(&'a self, prefix_name: &str, options: IterOptions) -> Result<KVIterator<'a>> {
+        let mut items: Vec<(Vec<u8>, Vec<u8>)> = self
+            .with_column(prefix_name, |column| {
+                column
+                    .cache
+                    .iter()
+                    .filter_map(|(key, obj)| match obj {
+                        CacheObject::Value(value) => Some((key.clone(), value.clone())),
+                        CacheObject::None => None,
+                    })
+                    .filter(|(key, _)| options.contains(key.as_slice()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        items.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        if options.direction == IterDirection::Reverse {
+            items.reverse();
+        }
+        Ok(Box::new(items.into_iter().map(Ok)))
+    }
 }
 
-fn compose_key(prefix_name: String, source_key: Vec<u8>) -> Result<Vec<u8>> {
+/// Compose the key with the length of prefix name, the prefix name and the source key,
+/// the length avoids a column family's keys being mixed up with another one whose name
+/// starts with it.
+pub(crate) fn compose_key(prefix_name: String, source_key: Vec<u8>) -> Result<Vec<u8>> {
     let temp_vec = prefix_name.as_bytes().to_vec();
-    let mut compose = Vec::with_capacity(temp_vec.len() + source_key.len());
+    ensure!(
+        temp_vec.len() <= u8::max_value() as usize,
+        "prefix name {} is too long",
+        prefix_name
+    );
+    let mut compose = Vec::with_capacity(1 + temp_vec.len() + source_key.len());
+    compose.push(temp_vec.len() as u8);
     compose.extend(temp_vec);
     compose.extend(source_key);
     Ok(compose)
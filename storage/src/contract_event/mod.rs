@@ -0,0 +1,250 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::batch::WriteBatch;
+use crate::define_storage;
+use crate::storage::{CodecStorage, IterOptions, KeyCodec, StorageInstance, ValueCodec};
+use crate::upgrade::{get_cf_value, master_block_ids, MigrationWriter};
+use crate::{
+    BLOCK_EVENT_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_TRANSACTIONS_PREFIX_NAME,
+    BLOCK_TRANSACTION_INFOS_PREFIX_NAME, CONTRACT_EVENT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME,
+};
+use anyhow::{ensure, format_err, Error, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use crypto::HashValue;
+use rocksdb::{IteratorMode, DB};
+use scs::SCSCodec;
+use starcoin_types::block::BlockHeader;
+use starcoin_types::contract_event::ContractEventInfo;
+use starcoin_types::event::EventKey;
+use starcoin_types::transaction::TransactionInfo;
+use std::convert::TryFrom;
+use std::mem::size_of;
+use std::sync::Arc;
+
+pub trait ContractEventStore {
+    /// Save all events emitted by block `block_id`, indexed by block.
+    fn save_contract_events(
+        &self,
+        block_id: HashValue,
+        events: Vec<ContractEventInfo>,
+    ) -> Result<()>;
+    /// Update the event key index when master changes, remove the events of `retracted`
+    /// blocks and then add the events of `enacted` blocks.
+    fn index_contract_events(
+        &self,
+        retracted: Vec<ContractEventInfo>,
+        enacted: Vec<ContractEventInfo>,
+    ) -> Result<()>;
+    /// Get events of block `block_id`, return None if the block's events is not indexed.
+    fn get_contract_events_by_block(
+        &self,
+        block_id: HashValue,
+    ) -> Result<Option<Vec<ContractEventInfo>>>;
+    /// Get the event with `seq_number` of `event_key` emitted by a master block.
+    fn get_contract_event(
+        &self,
+        event_key: EventKey,
+        seq_number: u64,
+    ) -> Result<Option<ContractEventInfo>>;
+    /// Get at most `limit` events of `event_key` with sequence number less than `before_seq`,
+    /// from the newest to the oldest, `before_seq` None means from the latest event.
+    /// Only events emitted by master blocks are indexed.
+    fn get_contract_events_by_key(
+        &self,
+        event_key: EventKey,
+        before_seq: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<ContractEventInfo>>;
+}
+
+/// Key of event index: event key and event sequence number.
+pub type EventIndexKey = (EventKey, u64);
+
+define_storage!(
+    ContractEventInnerStorage,
+    EventIndexKey,
+    ContractEventInfo,
+    CONTRACT_EVENT_PREFIX_NAME
+);
+
+define_storage!(
+    BlockEventStorage,
+    HashValue,
+    Vec<ContractEventInfo>,
+    BLOCK_EVENT_PREFIX_NAME
+);
+
+pub struct ContractEventStorage {
+    event_store: ContractEventInnerStorage,
+    block_event_store: BlockEventStorage,
+}
+
+impl ContractEventStorage {
+    pub fn new(instance: StorageInstance) -> Self {
+        Self {
+            event_store: ContractEventInnerStorage::new(instance.clone()),
+            block_event_store: BlockEventStorage::new(instance),
+        }
+    }
+}
+
+impl KeyCodec for EventIndexKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (event_key, seq_number) = self;
+        let mut encoded_key = event_key.as_bytes().to_vec();
+        encoded_key.extend_from_slice(&seq_number.to_be_bytes());
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self, Error> {
+        ensure!(
+            data.len() > size_of::<u64>(),
+            "Unexpected event index key len {}.",
+            data.len()
+        );
+        let split = data.len() - size_of::<u64>();
+        let event_key = EventKey::try_from(&data[..split])?;
+        let seq_number = (&data[split..]).read_u64::<BigEndian>()?;
+        Ok((event_key, seq_number))
+    }
+}
+
+impl ValueCodec for ContractEventInfo {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+impl ValueCodec for Vec<ContractEventInfo> {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+impl ContractEventStore for ContractEventStorage {
+    fn save_contract_events(
+        &self,
+        block_id: HashValue,
+        events: Vec<ContractEventInfo>,
+    ) -> Result<()> {
+        self.block_event_store.put(block_id, events)
+    }
+
+    fn index_contract_events(
+        &self,
+        retracted: Vec<ContractEventInfo>,
+        enacted: Vec<ContractEventInfo>,
+    ) -> Result<()> {
+        let mut batch = WriteBatch::new();
+        for event_info in retracted {
+            batch.delete((*event_info.event.key(), event_info.event.sequence_number()))?;
+        }
+        // enacted events share keys with retracted ones, and later writes win in the batch.
+        for event_info in enacted {
+            batch.put(
+                (*event_info.event.key(), event_info.event.sequence_number()),
+                event_info,
+            )?;
+        }
+        self.event_store.write_batch(batch)
+    }
+
+    fn get_contract_events_by_block(
+        &self,
+        block_id: HashValue,
+    ) -> Result<Option<Vec<ContractEventInfo>>> {
+        self.block_event_store.get(block_id)
+    }
+
+    fn get_contract_event(
+        &self,
+        event_key: EventKey,
+        seq_number: u64,
+    ) -> Result<Option<ContractEventInfo>> {
+        self.event_store.get((event_key, seq_number))
+    }
+
+    fn get_contract_events_by_key(
+        &self,
+        event_key: EventKey,
+        before_seq: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<ContractEventInfo>> {
+        let mut opts = IterOptions::reverse().prefix(event_key.as_bytes().to_vec());
+        if let Some(before_seq) = before_seq {
+            opts = opts.end((event_key, before_seq).encode_key()?);
+        }
+        self.event_store
+            .iter(opts)?
+            .take(limit)
+            .map(|item| item.map(|(_, event_info)| event_info))
+            .collect()
+    }
+}
+
+/// Backfill the event index of blocks saved before schema version 2, all blocks are indexed
+/// by block, and only master blocks are indexed by event key and sequence number.
+pub(crate) fn backfill_contract_events(db: &DB) -> Result<()> {
+    let block_txn_infos = db
+        .cf_handle(BLOCK_TRANSACTION_INFOS_PREFIX_NAME)
+        .ok_or_else(|| {
+            format_err!(
+                "DB::cf_handle not found for column family name: {}",
+                BLOCK_TRANSACTION_INFOS_PREFIX_NAME
+            )
+        })?;
+    let mut writer = MigrationWriter::new(db);
+    for (key, value) in db.iterator_cf(block_txn_infos, IteratorMode::Start) {
+        let block_id = HashValue::decode_key(&key)?;
+        let txn_info_ids = Vec::<HashValue>::decode_value(&value)?;
+        let events = block_events(db, block_id, txn_info_ids)?;
+        writer.put(BLOCK_EVENT_PREFIX_NAME, &block_id, &events)?;
+    }
+    writer.flush()?;
+    for block_id in master_block_ids(db)? {
+        let events: Vec<ContractEventInfo> =
+            get_cf_value(db, BLOCK_EVENT_PREFIX_NAME, &block_id)?.unwrap_or_default();
+        for event_info in &events {
+            writer.put(
+                CONTRACT_EVENT_PREFIX_NAME,
+                &(*event_info.event.key(), event_info.event.sequence_number()),
+                event_info,
+            )?;
+        }
+    }
+    writer.flush()
+}
+
+fn block_events(
+    db: &DB,
+    block_id: HashValue,
+    txn_info_ids: Vec<HashValue>,
+) -> Result<Vec<ContractEventInfo>> {
+    let header: BlockHeader = get_cf_value(db, BLOCK_HEADER_PREFIX_NAME, &block_id)?
+        .ok_or_else(|| format_err!("Can not find block header {:?}", block_id))?;
+    let txn_ids: Vec<HashValue> = get_cf_value(db, BLOCK_TRANSACTIONS_PREFIX_NAME, &block_id)?
+        .ok_or_else(|| format_err!("Can not find transactions of block {:?}", block_id))?;
+    ensure!(
+        txn_ids.len() == txn_info_ids.len(),
+        "block txns' length should be equal to txn infos' length"
+    );
+    let mut events = vec![];
+    for (idx, (txn_id, txn_info_id)) in txn_ids.into_iter().zip(txn_info_ids).enumerate() {
+        let txn_info: TransactionInfo =
+            get_cf_value(db, TRANSACTION_INFO_PREFIX_NAME, &txn_info_id)?
+                .ok_or_else(|| format_err!("Can not find transaction info {:?}", txn_info_id))?;
+        events.extend(txn_info.events().iter().cloned().map(|event| {
+            ContractEventInfo::new(block_id, header.number(), txn_id, idx as u64, event)
+        }));
+    }
+    Ok(events)
+}
@@ -1,13 +1,22 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{WriteBatch, WriteBatchWithColumn};
+use crate::cache_storage::compose_key;
 use crate::metrics::record_metrics;
-use crate::storage::{ColumnFamilyName, InnerStore, WriteOp};
+use crate::storage::{InnerStore, IterDirection, IterOptions, KVIterator, WriteOp};
+use crate::upgrade::{
+    read_schema_version, write_schema_version, MigrationRegistry, SchemaVersion, StorageInitError,
+    CURRENT_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION,
+};
 use crate::{DEFAULT_PREFIX_NAME, VEC_PREFIX_NAME};
 use anyhow::{ensure, format_err, Error, Result};
 use logger::prelude::*;
-use rocksdb::{WriteBatch as DBWriteBatch, WriteOptions, DB};
+use rocksdb::{
+    BlockBasedOptions, DBCompressionType, Direction, IteratorMode, WriteBatch as DBWriteBatch,
+    WriteOptions, DB,
+};
+use starcoin_config::{CompressionType, StorageConfig};
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -21,72 +30,210 @@ impl DBStorage {
         Self::open(path, false).expect("Unable to open StarcoinDB")
     }
     pub fn open(path: impl AsRef<Path>, readonly: bool) -> Result<Self> {
-        let column_families = VEC_PREFIX_NAME.to_vec();
-        let cfs_set: HashSet<_> = column_families.iter().collect();
+        Self::open_with_config(path, readonly, &StorageConfig::default())
+    }
+
+    /// Open db with the RocksDB options in `config`.
+    pub fn open_with_config(
+        path: impl AsRef<Path>,
+        readonly: bool,
+        config: &StorageConfig,
+    ) -> Result<Self> {
+        Self::open_inner(path, readonly, config, &MigrationRegistry::default())
+    }
+
+    /// Open db and upgrade its schema with migrations in `registry` if needed.
+    /// Return `StorageInitError` if the db can not be opened by current schema.
+    pub fn open_with_registry(
+        path: impl AsRef<Path>,
+        readonly: bool,
+        registry: &MigrationRegistry,
+    ) -> Result<Self> {
+        Self::open_inner(path, readonly, &StorageConfig::default(), registry)
+    }
+
+    fn open_inner(
+        path: impl AsRef<Path>,
+        readonly: bool,
+        config: &StorageConfig,
+        registry: &MigrationRegistry,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let column_families: Vec<String> =
+            VEC_PREFIX_NAME.iter().map(|cf| (*cf).to_string()).collect();
+        let cfs_set: HashSet<&str> = VEC_PREFIX_NAME.iter().cloned().collect();
         {
             ensure!(
                 cfs_set.len() == column_families.len(),
                 "Duplicate column family name found.",
             );
         }
-        if Self::db_exists(path.as_ref()) {
-            let cf_vec = Self::list_cf(path.as_ref())?;
-            for cf in cf_vec {
-                if cf != DEFAULT_PREFIX_NAME && cfs_set.get(&cf.as_str()).is_none() {
-                    error!(
-                        "db path cf: {:?} is not equal,please clear dir: {:?}",
-                        path.as_ref(),
-                        cf
-                    );
-                    std::process::exit(100);
-                }
-            }
-        }
+        let db_exists = Self::db_exists(path);
+        let exist_cfs = if db_exists {
+            Self::list_cf(path)?
+        } else {
+            vec![]
+        };
 
-        let db = if readonly {
-            Self::open_readonly(path.as_ref(), column_families)?
+        let table_opts = Self::table_options(config);
+        let mut db = if readonly {
+            Self::open_readonly(config, path, &exist_cfs)?
         } else {
-            let mut db_opts = rocksdb::Options::default();
+            // the column families missing in an existing db are created by migrations.
+            let all_cfs = if db_exists {
+                exist_cfs.clone()
+            } else {
+                column_families
+            };
+            let mut db_opts = Self::db_options(config);
             db_opts.create_if_missing(true);
             db_opts.create_missing_column_families(true);
             // For now we set the max total WAL size to be 1G. This config can be useful when column
             // families are updated at non-uniform frequencies.
             db_opts.set_max_total_wal_size(1 << 30);
-            Self::open_inner(&db_opts, path.as_ref(), column_families)?
+            Self::open_with_cfs(config, &db_opts, &table_opts, path, &all_cfs)?
+        };
+
+        let version = match read_schema_version(&db)? {
+            Some(version) => version,
+            None if db_exists => LEGACY_SCHEMA_VERSION,
+            None => {
+                write_schema_version(&db, CURRENT_SCHEMA_VERSION)?;
+                CURRENT_SCHEMA_VERSION
+            }
         };
+        if version > CURRENT_SCHEMA_VERSION {
+            return Err(StorageInitError::SchemaVersionTooNew {
+                found: version,
+                supported: CURRENT_SCHEMA_VERSION,
+            }
+            .into());
+        }
+        if version < CURRENT_SCHEMA_VERSION {
+            if readonly {
+                return Err(StorageInitError::UpgradeRequired {
+                    found: version,
+                    required: CURRENT_SCHEMA_VERSION,
+                }
+                .into());
+            }
+            registry.upgrade(&mut db, version, CURRENT_SCHEMA_VERSION, &|cf: &str| {
+                Self::cf_options(config, cf, &table_opts)
+            })?;
+        }
+        if let Some(cf) = VEC_PREFIX_NAME.iter().find(|cf| db.cf_handle(cf).is_none()) {
+            return Err(StorageInitError::MissingColumnFamily(cf.to_string()).into());
+        }
+
+        for cf in exist_cfs {
+            if cf != DEFAULT_PREFIX_NAME
+                && !cfs_set.contains(cf.as_str())
+                && db.cf_handle(&cf).is_some()
+            {
+                error!("db path {:?} contains unknown cf: {:?}", path, cf);
+                return Err(StorageInitError::UnknownColumnFamily {
+                    path: path.to_path_buf(),
+                    cf,
+                }
+                .into());
+            }
+        }
 
         Ok(DBStorage { db })
     }
 
-    fn open_inner(
+    fn db_options(config: &StorageConfig) -> rocksdb::Options {
+        let mut db_opts = rocksdb::Options::default();
+        db_opts.set_max_open_files(config.max_open_files());
+        db_opts.set_max_background_jobs(config.max_background_jobs());
+        db_opts
+    }
+
+    /// Options of column family `cf_name`, `table_opts` is shared by all column families,
+    /// so is the block cache in it.
+    fn cf_options(
+        config: &StorageConfig,
+        cf_name: &str,
+        table_opts: &BlockBasedOptions,
+    ) -> rocksdb::Options {
+        let mut cf_opts = rocksdb::Options::default();
+        cf_opts.set_compression_type(match config.column_compression(cf_name) {
+            CompressionType::None => DBCompressionType::None,
+            CompressionType::Snappy => DBCompressionType::Snappy,
+            CompressionType::Lz4 => DBCompressionType::Lz4,
+            CompressionType::Zstd => DBCompressionType::Zstd,
+        });
+        cf_opts.set_write_buffer_size(config.write_buffer_size());
+        cf_opts.set_block_based_table_factory(table_opts);
+        cf_opts
+    }
+
+    fn table_options(config: &StorageConfig) -> BlockBasedOptions {
+        let mut table_opts = BlockBasedOptions::default();
+        table_opts.set_lru_cache(config.block_cache_size());
+        table_opts
+    }
+
+    fn open_with_cfs(
+        config: &StorageConfig,
         opts: &rocksdb::Options,
+        table_opts: &BlockBasedOptions,
         path: impl AsRef<Path>,
-        column_families: Vec<ColumnFamilyName>,
+        column_families: &[String],
     ) -> Result<DB> {
         let inner = rocksdb::DB::open_cf_descriptors(
             opts,
             path,
             column_families.iter().map(|cf_name| {
-                let mut cf_opts = rocksdb::Options::default();
-                cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
-                rocksdb::ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts)
+                rocksdb::ColumnFamilyDescriptor::new(
+                    cf_name.clone(),
+                    Self::cf_options(config, cf_name, table_opts),
+                )
             }),
         )?;
         Ok(inner)
     }
 
-    fn open_readonly(path: impl AsRef<Path>, column_families: Vec<ColumnFamilyName>) -> Result<DB> {
-        let db_opts = rocksdb::Options::default();
+    fn open_readonly(
+        config: &StorageConfig,
+        path: impl AsRef<Path>,
+        column_families: &[String],
+    ) -> Result<DB> {
+        let mut db_opts = Self::db_options(config);
+        db_opts.set_block_based_table_factory(&Self::table_options(config));
         let error_if_log_file_exists = false;
         let inner = rocksdb::DB::open_cf_for_read_only(
             &db_opts,
             path,
-            &column_families,
+            column_families,
             error_if_log_file_exists,
         )?;
         Ok(inner)
     }
 
+    /// Get the RocksDB property `name` of column family `cf_name`, such as
+    /// `rocksdb.estimate-num-keys` and `rocksdb.stats`.
+    pub fn property_value(&self, cf_name: &str, name: &str) -> Result<Option<String>> {
+        let cf_handle = self.get_cf_handle(cf_name)?;
+        Ok(self.db.property_value_cf(cf_handle, name)?)
+    }
+
+    /// Get the RocksDB property `name` of column family `cf_name` as an integer.
+    pub fn property_int_value(&self, cf_name: &str, name: &str) -> Result<Option<u64>> {
+        self.property_value(cf_name, name)?
+            .map(|value| {
+                value.trim().parse::<u64>().map_err(|e| {
+                    format_err!("Invalid integer property {} of {}: {}", name, cf_name, e)
+                })
+            })
+            .transpose()
+    }
+
+    /// Current schema version of the db.
+    pub fn schema_version(&self) -> Result<Option<SchemaVersion>> {
+        read_schema_version(&self.db)
+    }
+
     pub fn drop_cf(&mut self) -> Result<(), Error> {
         for cf in &VEC_PREFIX_NAME.to_vec() {
             self.db.drop_cf(cf)?;
@@ -181,11 +328,91 @@ impl InnerStore for DBStorage {
         })
     }
 
+    /// Writes records of multiple column families in one rocksdb WriteBatch.
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()> {
+        record_metrics("db", "batch", "with_column").end_with(|| {
+            let mut db_batch = DBWriteBatch::default();
+            for (prefix_name, batch) in &batch.rows {
+                let cf_handle = self.get_cf_handle(prefix_name)?;
+                for (key, write_op) in &batch.rows {
+                    match write_op {
+                        WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
+                        WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                    };
+                }
+            }
+            self.db
+                .write_opt(db_batch, &Self::default_write_options())?;
+            Ok(())
+        })
+    }
+
+    /// Count the rows of all column families by a full scan, only for tests and tools.
     fn get_len(&self) -> Result<u64> {
-        unimplemented!()
+        let mut len = 0;
+        for cf in VEC_PREFIX_NAME.iter() {
+            for item in self.iter(cf, IterOptions::forward())? {
+                item?;
+                len += 1;
+            }
+        }
+        Ok(len)
     }
 
+    /// Keys of all column families composed with the column family name, like `CacheStorage`,
+    /// by a full scan, only for tests and tools.
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
-        unimplemented!()
+        let mut keys = vec![];
+        for cf in VEC_PREFIX_NAME.iter() {
+            for item in self.iter(cf, IterOptions::forward())? {
+                let (key, _) = item?;
+                keys.push(compose_key(cf.to_string(), key)?);
+            }
+        }
+        Ok(keys)
+    }
+
+    fn iter<'a>(&'a self, prefix_name: &str, options: IterOptions) -> Result<KVIterator<'a>> {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let lower = options.lower_bound();
+        let upper = options.upper_bound();
+        let iter: KVIterator<'a> = match options.direction {
+            IterDirection::Forward => {
+                let mode = match &lower {
+                    Some(lower) => IteratorMode::From(lower.as_slice(), Direction::Forward),
+                    None => IteratorMode::Start,
+                };
+                Box::new(
+                    self.db
+                        .iterator_cf(cf_handle, mode)
+                        .take_while(move |(key, _)| match &upper {
+                            Some(upper) => key.as_ref() < upper.as_slice(),
+                            None => true,
+                        })
+                        .map(|(key, value)| Ok((key.to_vec(), value.to_vec()))),
+                )
+            }
+            IterDirection::Reverse => {
+                let mode = match &upper {
+                    Some(upper) => IteratorMode::From(upper.as_slice(), Direction::Reverse),
+                    None => IteratorMode::End,
+                };
+                Box::new(
+                    self.db
+                        .iterator_cf(cf_handle, mode)
+                        // reverse seek stops at the upper bound itself, which is exclusive.
+                        .skip_while(move |(key, _)| match &upper {
+                            Some(upper) => key.as_ref() >= upper.as_slice(),
+                            None => false,
+                        })
+                        .take_while(move |(key, _)| match &lower {
+                            Some(lower) => key.as_ref() >= lower.as_slice(),
+                            None => true,
+                        })
+                        .map(|(key, value)| Ok((key.to_vec(), value.to_vec()))),
+                )
+            }
+        };
+        Ok(iter)
     }
 }
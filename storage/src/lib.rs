@@ -1,22 +1,29 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::account_transaction::{AccountTransactionStorage, AccountTransactionStore};
 use crate::accumulator::AccumulatorStorage;
 use crate::block::BlockStorage;
 use crate::block_info::{BlockInfoStorage, BlockInfoStore};
-use crate::state_node::StateStorage;
-use crate::storage::{ColumnFamilyName, InnerStorage, KVStore, StorageInstance};
+use crate::contract_event::{ContractEventStorage, ContractEventStore};
+use crate::state_node::{StaleStateNodeStore, StateStorage};
+use crate::storage::{ColumnFamilyName, InnerStorage, InnerStore, KVStore, StorageInstance};
 use crate::transaction::TransactionStorage;
 use crate::transaction_info::TransactionInfoStorage;
 use anyhow::{bail, ensure, format_err, Error, Result};
 use crypto::HashValue;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::{
     AccumulatorNode, AccumulatorReader, AccumulatorTreeStore, AccumulatorWriter,
 };
 use starcoin_state_store_api::{StateNode, StateNodeStore};
-use starcoin_types::block::BlockState;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_transaction::AccountTransactionInfo;
+use starcoin_types::block::{BlockNumber, BlockState};
+use starcoin_types::contract_event::ContractEventInfo;
+use starcoin_types::event::EventKey;
 use starcoin_types::transaction::Transaction;
 use starcoin_types::{
     block::{Block, BlockBody, BlockHeader, BlockInfo},
@@ -27,24 +34,30 @@ use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::sync::Arc;
 
+pub mod account_transaction;
 pub mod accumulator;
 pub mod batch;
 pub mod block;
 pub mod block_info;
 pub mod cache_storage;
+pub mod contract_event;
 pub mod db_storage;
 mod metrics;
+pub mod recovery;
 pub mod state_node;
 pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod transaction;
 pub mod transaction_info;
+pub mod upgrade;
 
 #[macro_use]
 pub mod storage_macros;
 pub const DEFAULT_PREFIX_NAME: ColumnFamilyName = "default";
 pub const ACCUMULATOR_NODE_PREFIX_NAME: ColumnFamilyName = "acc_node";
+pub const ACCOUNT_TRANSACTION_PREFIX_NAME: ColumnFamilyName = "account_txn";
+pub const ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME: ColumnFamilyName = "account_txn_count";
 pub const BLOCK_PREFIX_NAME: ColumnFamilyName = "block";
 pub const BLOCK_HEADER_PREFIX_NAME: ColumnFamilyName = "block_header";
 pub const BLOCK_SONS_PREFIX_NAME: ColumnFamilyName = "block_sons";
@@ -53,7 +66,12 @@ pub const BLOCK_NUM_PREFIX_NAME: ColumnFamilyName = "block_num";
 pub const BLOCK_INFO_PREFIX_NAME: ColumnFamilyName = "block_info";
 pub const BLOCK_TRANSACTIONS_PREFIX_NAME: ColumnFamilyName = "block_txns";
 pub const BLOCK_TRANSACTION_INFOS_PREFIX_NAME: ColumnFamilyName = "block_txn_infos";
+pub const BLOCK_EVENT_PREFIX_NAME: ColumnFamilyName = "block_events";
+pub const CONTRACT_EVENT_PREFIX_NAME: ColumnFamilyName = "contract_event";
 pub const STATE_NODE_PREFIX_NAME: ColumnFamilyName = "state_node";
+pub const STATE_NODE_REF_PREFIX_NAME: ColumnFamilyName = "state_node_ref";
+pub const STALE_STATE_NODE_PREFIX_NAME: ColumnFamilyName = "stale_state_node";
+pub const NEW_STATE_NODE_PREFIX_NAME: ColumnFamilyName = "new_state_node";
 pub const STARTUP_INFO_PREFIX_NAME: ColumnFamilyName = "startup_info";
 pub const TRANSACTION_PREFIX_NAME: ColumnFamilyName = "transaction";
 pub const TRANSACTION_INFO_PREFIX_NAME: ColumnFamilyName = "transaction_info";
@@ -62,6 +80,8 @@ pub const TRANSACTION_INFO_PREFIX_NAME: ColumnFamilyName = "transaction_info";
 pub static VEC_PREFIX_NAME: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
     vec![
         ACCUMULATOR_NODE_PREFIX_NAME,
+        ACCOUNT_TRANSACTION_PREFIX_NAME,
+        ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME,
         BLOCK_PREFIX_NAME,
         BLOCK_HEADER_PREFIX_NAME,
         BLOCK_SONS_PREFIX_NAME,
@@ -70,7 +90,12 @@ pub static VEC_PREFIX_NAME: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
         BLOCK_INFO_PREFIX_NAME,
         BLOCK_TRANSACTIONS_PREFIX_NAME,
         BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+        BLOCK_EVENT_PREFIX_NAME,
+        CONTRACT_EVENT_PREFIX_NAME,
         STATE_NODE_PREFIX_NAME,
+        STATE_NODE_REF_PREFIX_NAME,
+        STALE_STATE_NODE_PREFIX_NAME,
+        NEW_STATE_NODE_PREFIX_NAME,
         STARTUP_INFO_PREFIX_NAME,
         TRANSACTION_PREFIX_NAME,
         TRANSACTION_INFO_PREFIX_NAME,
@@ -95,6 +120,9 @@ pub trait BlockStore {
 
     fn get_branch_hashes(&self, block_id: HashValue) -> Result<Vec<HashValue>>;
 
+    /// get ids of blocks whose parent is block `block_id`, include blocks not on master.
+    fn get_block_sons(&self, block_id: HashValue) -> Result<Vec<HashValue>>;
+
     fn get_latest_block_header(&self) -> Result<Option<BlockHeader>>;
 
     fn get_latest_block(&self) -> Result<Option<Block>>;
@@ -144,25 +172,53 @@ pub trait TransactionStore {
     fn save_transaction_batch(&self, txn_vec: Vec<Transaction>) -> Result<()>;
 }
 
+pub trait AtomicWriteStore {
+    /// Run `f` with a store which stages all writes, and write them in one atomic write if `f`
+    /// succeeds. The staged writes are only visible through the store passed to `f`, and an
+    /// atomic write can not be nested in another one.
+    fn atomic_write<R, F>(&self, f: F) -> Result<R>
+    where
+        Self: Sized,
+        F: FnOnce(Arc<Self>) -> Result<R>;
+}
+
 pub struct Storage {
+    instance: StorageInstance,
+    state_pruning: bool,
+    /// Serialize atomic writes, block execution and state pruning both read and update the
+    /// reference count of state nodes in atomic writes.
+    atomic_write_lock: Mutex<()>,
     transaction_info_storage: TransactionInfoStorage,
     transaction_storage: TransactionStorage,
     block_storage: BlockStorage,
     state_node_storage: StateStorage,
     accumulator_storage: AccumulatorStorage,
     block_info_storage: BlockInfoStorage,
+    contract_event_storage: ContractEventStorage,
+    account_transaction_storage: AccountTransactionStorage,
     startup_info_storage: Arc<dyn KVStore>,
 }
 
 impl Storage {
     pub fn new(instance: StorageInstance) -> Result<Self> {
+        Self::new_with_state_pruning(instance, true)
+    }
+
+    /// Create a storage which skips the bookkeeping for state pruning if `state_pruning` is
+    /// false, for archive nodes.
+    pub fn new_with_state_pruning(instance: StorageInstance, state_pruning: bool) -> Result<Self> {
         Ok(Self {
+            instance: instance.clone(),
+            state_pruning,
+            atomic_write_lock: Mutex::new(()),
             transaction_info_storage: TransactionInfoStorage::new(instance.clone()),
             transaction_storage: TransactionStorage::new(instance.clone()),
             block_storage: BlockStorage::new(instance.clone()),
-            state_node_storage: StateStorage::new(instance.clone()),
+            state_node_storage: StateStorage::new(instance.clone(), state_pruning),
             accumulator_storage: AccumulatorStorage::new(instance.clone()),
             block_info_storage: BlockInfoStorage::new(instance.clone()),
+            contract_event_storage: ContractEventStorage::new(instance.clone()),
+            account_transaction_storage: AccountTransactionStorage::new(instance.clone()),
             startup_info_storage: Arc::new(InnerStorage::new(instance, STARTUP_INFO_PREFIX_NAME)),
         })
     }
@@ -170,7 +226,7 @@ impl Storage {
 
 impl StateNodeStore for Storage {
     fn get(&self, hash: &HashValue) -> Result<Option<StateNode>> {
-        self.state_node_storage.get(*hash)
+        self.state_node_storage.get(hash)
     }
 
     fn put(&self, key: HashValue, node: StateNode) -> Result<()> {
@@ -182,6 +238,46 @@ impl StateNodeStore for Storage {
     }
 }
 
+impl StaleStateNodeStore for Storage {
+    fn save_stale_state_nodes(
+        &self,
+        block_id: HashValue,
+        stale_nodes: Vec<HashValue>,
+    ) -> Result<()> {
+        self.state_node_storage
+            .save_stale_state_nodes(block_id, stale_nodes)
+    }
+
+    fn get_stale_state_nodes(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>> {
+        self.state_node_storage.get_stale_state_nodes(block_id)
+    }
+
+    fn save_new_state_nodes(&self, block_id: HashValue, new_nodes: Vec<HashValue>) -> Result<()> {
+        self.state_node_storage
+            .save_new_state_nodes(block_id, new_nodes)
+    }
+
+    fn get_new_state_nodes(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>> {
+        self.state_node_storage.get_new_state_nodes(block_id)
+    }
+
+    fn prune_stale_state_nodes(&self, block_id: HashValue) -> Result<u64> {
+        self.state_node_storage.prune_stale_state_nodes(block_id)
+    }
+
+    fn prune_side_state_nodes(&self, block_id: HashValue) -> Result<u64> {
+        self.state_node_storage.prune_side_state_nodes(block_id)
+    }
+
+    fn get_state_prune_progress(&self) -> Result<Option<BlockNumber>> {
+        self.state_node_storage.get_state_prune_progress()
+    }
+
+    fn save_state_prune_progress(&self, number: BlockNumber) -> Result<()> {
+        self.state_node_storage.save_state_prune_progress(number)
+    }
+}
+
 impl BlockStore for Storage {
     fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
         self.startup_info_storage
@@ -227,6 +323,10 @@ impl BlockStore for Storage {
         self.block_storage.get_branch_hashes(block_id)
     }
 
+    fn get_block_sons(&self, block_id: HashValue) -> Result<Vec<HashValue>> {
+        self.block_storage.get_block_sons(block_id)
+    }
+
     fn get_latest_block_header(&self) -> Result<Option<BlockHeader>> {
         self.block_storage.get_latest_block_header()
     }
@@ -285,6 +385,21 @@ impl BlockStore for Storage {
     }
 }
 
+impl AtomicWriteStore for Storage {
+    fn atomic_write<R, F>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(Arc<Self>) -> Result<R>,
+    {
+        let _guard = self.atomic_write_lock.lock();
+        self.instance.atomic_write(|instance| {
+            f(Arc::new(Storage::new_with_state_pruning(
+                instance.clone(),
+                self.state_pruning,
+            )?))
+        })
+    }
+}
+
 impl AccumulatorTreeStore for Storage {}
 impl AccumulatorReader for Storage {
     ///get node by node hash
@@ -340,6 +455,87 @@ impl BlockInfoStore for Storage {
     }
 }
 
+impl ContractEventStore for Storage {
+    fn save_contract_events(
+        &self,
+        block_id: HashValue,
+        events: Vec<ContractEventInfo>,
+    ) -> Result<()> {
+        self.contract_event_storage
+            .save_contract_events(block_id, events)
+    }
+
+    fn index_contract_events(
+        &self,
+        retracted: Vec<ContractEventInfo>,
+        enacted: Vec<ContractEventInfo>,
+    ) -> Result<()> {
+        self.contract_event_storage
+            .index_contract_events(retracted, enacted)
+    }
+
+    fn get_contract_events_by_block(
+        &self,
+        block_id: HashValue,
+    ) -> Result<Option<Vec<ContractEventInfo>>> {
+        self.contract_event_storage
+            .get_contract_events_by_block(block_id)
+    }
+
+    fn get_contract_event(
+        &self,
+        event_key: EventKey,
+        seq_number: u64,
+    ) -> Result<Option<ContractEventInfo>> {
+        self.contract_event_storage
+            .get_contract_event(event_key, seq_number)
+    }
+
+    fn get_contract_events_by_key(
+        &self,
+        event_key: EventKey,
+        before_seq: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<ContractEventInfo>> {
+        self.contract_event_storage
+            .get_contract_events_by_key(event_key, before_seq, limit)
+    }
+}
+
+impl AccountTransactionStore for Storage {
+    fn append_account_transactions(
+        &self,
+        transactions: Vec<(AccountAddress, AccountTransactionInfo)>,
+    ) -> Result<()> {
+        self.account_transaction_storage
+            .append_account_transactions(transactions)
+    }
+
+    fn revert_account_transactions(
+        &self,
+        block_id: HashValue,
+        addresses: Vec<AccountAddress>,
+    ) -> Result<()> {
+        self.account_transaction_storage
+            .revert_account_transactions(block_id, addresses)
+    }
+
+    fn get_account_transaction_count(&self, address: AccountAddress) -> Result<u64> {
+        self.account_transaction_storage
+            .get_account_transaction_count(address)
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<AccountTransactionInfo>> {
+        self.account_transaction_storage
+            .get_account_transactions(address, start, limit)
+    }
+}
+
 impl TransactionInfoStore for Storage {
     fn get_transaction_info(&self, txn_hash: HashValue) -> Result<Option<TransactionInfo>> {
         self.transaction_info_storage.get(txn_hash)
@@ -373,6 +569,10 @@ pub trait Store:
     + BlockInfoStore
     + TransactionStore
     + TransactionInfoStore
+    + ContractEventStore
+    + AccountTransactionStore
+    + StaleStateNodeStore
+    + AtomicWriteStore
     + IntoSuper<dyn StateNodeStore>
     + IntoSuper<dyn AccumulatorTreeStore>
 {
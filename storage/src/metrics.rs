@@ -3,8 +3,8 @@
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use starcoin_metrics::{
-    self, register_histogram_vec, register_int_counter_vec, register_uint_gauge, HistogramTimer,
-    HistogramVec, IntCounterVec, UIntGauge,
+    self, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramTimer,
+    HistogramVec, IntCounterVec, IntGaugeVec,
 };
 
 pub static STORAGE_COUNTERS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -25,8 +25,32 @@ pub static STORAGE_TIMES: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
-pub static CACHE_ITEMS: Lazy<UIntGauge> =
-    Lazy::new(|| register_uint_gauge!("starcoin_cache_items", "How many items in cache").unwrap());
+pub static CACHE_ITEMS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "starcoin_cache_items",
+        "How many items in cache of each column family",
+        &["key_type"]
+    )
+    .unwrap()
+});
+
+pub static CACHE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "starcoin_cache_bytes",
+        "How many bytes in cache of each column family",
+        &["key_type"]
+    )
+    .unwrap()
+});
+
+pub static CACHE_COUNTERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "starcoin_cache_counters",
+        "Counters of cache hit and miss of each column family",
+        &["key_type", "result"]
+    )
+    .unwrap()
+});
 
 pub enum ResultType {
     NONE,
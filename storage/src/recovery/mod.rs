@@ -0,0 +1,106 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Store;
+use anyhow::{ensure, format_err, Result};
+use crypto::HashValue;
+use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use logger::prelude::*;
+use starcoin_accumulator::node::{AccumulatorStoreType, ACCUMULATOR_PLACEHOLDER_HASH};
+use starcoin_types::startup_info::StartupInfo;
+
+/// Check whether all data of block `block_id` is persisted: the block, its block info,
+/// its transaction info ids, the accumulator roots and the state root.
+/// The genesis block does not save transaction info ids.
+pub fn is_block_complete(store: &dyn Store, block_id: HashValue) -> Result<bool> {
+    let block = match store.get_block(block_id)? {
+        Some(block) => block,
+        None => {
+            warn!("block {:?} is missing", block_id);
+            return Ok(false);
+        }
+    };
+    let block_info = match store.get_block_info(block_id)? {
+        Some(block_info) => block_info,
+        None => {
+            warn!("block info of block {:?} is missing", block_id);
+            return Ok(false);
+        }
+    };
+    if block.header().number() > 0 && store.get_block_txn_info_ids(block_id).is_err() {
+        warn!("txn info ids of block {:?} are missing", block_id);
+        return Ok(false);
+    }
+    for (store_type, root) in vec![
+        (
+            AccumulatorStoreType::Block,
+            *block_info
+                .get_block_accumulator_info()
+                .get_accumulator_root(),
+        ),
+        (
+            AccumulatorStoreType::Transaction,
+            *block_info.get_txn_accumulator_info().get_accumulator_root(),
+        ),
+    ] {
+        if root != *ACCUMULATOR_PLACEHOLDER_HASH
+            && store.get_node(store_type.clone(), root)?.is_none()
+        {
+            warn!(
+                "{:?} accumulator root {:?} of block {:?} is missing",
+                store_type, root, block_id
+            );
+            return Ok(false);
+        }
+    }
+    let state_root = block.header().state_root();
+    if state_root != *SPARSE_MERKLE_PLACEHOLDER_HASH && store.get(&state_root)?.is_none() {
+        warn!(
+            "state root {:?} of block {:?} is missing",
+            state_root, block_id
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Walk back from `block_id` to the latest completely persisted block.
+fn latest_complete_ancestor(store: &dyn Store, block_id: HashValue) -> Result<HashValue> {
+    let mut current = block_id;
+    while !is_block_complete(store, current)? {
+        let header = store
+            .get_block_header_by_hash(current)?
+            .ok_or_else(|| format_err!("Can not find block header by hash {:?}", current))?;
+        ensure!(
+            header.number() > 0,
+            "Genesis block {:?} is not completely persisted",
+            current
+        );
+        current = header.parent_hash();
+    }
+    Ok(current)
+}
+
+/// Check the heads of `startup_info` are completely persisted, a crash in the middle of block
+/// persistence by an older version may leave a half written head.
+/// The master is rolled back to its latest complete ancestor, half written branch heads are
+/// rolled back in the same way. The repaired startup info is saved if anything changed.
+pub fn recover_startup_info(store: &dyn Store, startup_info: StartupInfo) -> Result<StartupInfo> {
+    let master = latest_complete_ancestor(store, startup_info.master)?;
+    let mut branches = vec![];
+    for branch in &startup_info.branches {
+        let head = latest_complete_ancestor(store, *branch)?;
+        if head != master && !branches.contains(&head) {
+            branches.push(head);
+        }
+    }
+    let repaired = StartupInfo::new(master, branches);
+    if repaired != startup_info {
+        warn!(
+            "Roll back half written startup info {} to {}",
+            startup_info, repaired
+        );
+        store.save_startup_info(repaired.clone())?;
+    }
+    Ok(repaired)
+}
@@ -3,16 +3,158 @@
 
 use crate::batch::WriteBatch;
 use crate::define_storage;
-use crate::storage::{CodecStorage, ValueCodec};
-use crate::STATE_NODE_PREFIX_NAME;
+use crate::storage::{CodecStorage, InnerStorage, KVStore, StorageInstance, ValueCodec};
+use crate::{
+    DEFAULT_PREFIX_NAME, NEW_STATE_NODE_PREFIX_NAME, STALE_STATE_NODE_PREFIX_NAME,
+    STATE_NODE_PREFIX_NAME, STATE_NODE_REF_PREFIX_NAME,
+};
 use anyhow::{Error, Result};
+use byteorder::{BigEndian, ReadBytesExt};
 use crypto::HashValue;
 use forkable_jellyfish_merkle::node_type::Node;
 use starcoin_state_store_api::{StateNode, StateNodeStore};
+use starcoin_types::block::BlockNumber;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
-define_storage!(StateStorage, HashValue, StateNode, STATE_NODE_PREFIX_NAME);
+/// State nodes are keyed by node hash, so the same node may be shared by different state trees
+/// and different versions of a tree. A node is only deleted when every write of it is released,
+/// by a master block which marked it stale, or by the side block which wrote it, and these
+/// blocks are out of the prune window.
+pub trait StaleStateNodeStore {
+    /// Save the state nodes which become stale in block `block_id`.
+    fn save_stale_state_nodes(
+        &self,
+        block_id: HashValue,
+        stale_nodes: Vec<HashValue>,
+    ) -> Result<()>;
+    fn get_stale_state_nodes(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>>;
+    /// Save the state nodes written in block `block_id`.
+    fn save_new_state_nodes(&self, block_id: HashValue, new_nodes: Vec<HashValue>) -> Result<()>;
+    fn get_new_state_nodes(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>>;
+    /// Release the stale state nodes of master block `block_id`, delete the nodes which are not
+    /// referenced by any tree any more, and return the count of deleted nodes.
+    /// After this, the state of blocks before `block_id` may be unreadable.
+    fn prune_stale_state_nodes(&self, block_id: HashValue) -> Result<u64>;
+    /// Release the state nodes written by block `block_id`, which is not on master and can never
+    /// be, delete the nodes which are not referenced by any tree any more, and return the count
+    /// of deleted nodes. After this, the state of block `block_id` is unreadable.
+    fn prune_side_state_nodes(&self, block_id: HashValue) -> Result<u64>;
+    /// Get the number of the last master block whose stale state nodes are pruned.
+    fn get_state_prune_progress(&self) -> Result<Option<BlockNumber>>;
+    fn save_state_prune_progress(&self, number: BlockNumber) -> Result<()>;
+}
+
+/// Key of state prune progress in the default column family.
+const STATE_PRUNE_PROGRESS_KEY: &[u8] = b"state_prune_progress";
+
+/// Reference count of nodes written before reference counting was introduced.
+/// We don't know which trees reference them, so they are never pruned.
+const PINNED_REF_COUNT: u64 = std::u64::MAX;
+
+define_storage!(
+    StateNodeInnerStorage,
+    HashValue,
+    StateNode,
+    STATE_NODE_PREFIX_NAME
+);
+
+define_storage!(
+    StateNodeRefStorage,
+    HashValue,
+    u64,
+    STATE_NODE_REF_PREFIX_NAME
+);
+
+define_storage!(
+    StaleStateNodeStorage,
+    HashValue,
+    Vec<HashValue>,
+    STALE_STATE_NODE_PREFIX_NAME
+);
+
+define_storage!(
+    NewStateNodeStorage,
+    HashValue,
+    Vec<HashValue>,
+    NEW_STATE_NODE_PREFIX_NAME
+);
+
+pub struct StateStorage {
+    node_store: StateNodeInnerStorage,
+    ref_store: StateNodeRefStorage,
+    stale_store: StaleStateNodeStorage,
+    new_store: NewStateNodeStorage,
+    progress_store: InnerStorage,
+    /// Count references and record stale and new nodes, only needed when state is pruned.
+    /// Nodes written without reference count are pinned if counting is turned on later.
+    ref_counting: bool,
+}
+
+impl StateStorage {
+    pub fn new(instance: StorageInstance, ref_counting: bool) -> Self {
+        Self {
+            node_store: StateNodeInnerStorage::new(instance.clone()),
+            ref_store: StateNodeRefStorage::new(instance.clone()),
+            stale_store: StaleStateNodeStorage::new(instance.clone()),
+            new_store: NewStateNodeStorage::new(instance.clone()),
+            progress_store: InnerStorage::new(instance, DEFAULT_PREFIX_NAME),
+            ref_counting,
+        }
+    }
+
+    /// Increase the reference count of nodes which are going to be written.
+    fn add_refs<'a>(&self, keys: impl Iterator<Item = &'a HashValue>) -> Result<()> {
+        if !self.ref_counting {
+            return Ok(());
+        }
+        let mut batch = WriteBatch::new();
+        for key in keys {
+            let ref_count = match self.ref_store.get(*key)? {
+                Some(PINNED_REF_COUNT) => continue,
+                Some(ref_count) => ref_count + 1,
+                None => {
+                    if self.node_store.get(*key)?.is_some() {
+                        PINNED_REF_COUNT
+                    } else {
+                        1
+                    }
+                }
+            };
+            batch.put(*key, ref_count)?;
+        }
+        self.ref_store.write_batch(batch)
+    }
+
+    /// Release a reference of every node in `keys`, delete the nodes which are not referenced
+    /// any more, and return the count of deleted nodes.
+    fn release_refs(&self, keys: Vec<HashValue>) -> Result<u64> {
+        // a node may be released several times in one block.
+        let mut releases = BTreeMap::new();
+        for key in keys {
+            *releases.entry(key).or_insert(0u64) += 1;
+        }
+        let mut ref_batch = WriteBatch::new();
+        let mut node_batch = WriteBatch::new();
+        let mut pruned = 0;
+        for (key, release) in releases {
+            match self.ref_store.get(key)? {
+                None | Some(PINNED_REF_COUNT) => {}
+                Some(ref_count) if ref_count > release => {
+                    ref_batch.put(key, ref_count - release)?;
+                }
+                Some(_) => {
+                    ref_batch.delete(key)?;
+                    node_batch.delete(key)?;
+                    pruned += 1;
+                }
+            }
+        }
+        self.ref_store.write_batch(ref_batch)?;
+        self.node_store.write_batch(node_batch)?;
+        Ok(pruned)
+    }
+}
 
 impl ValueCodec for StateNode {
     fn encode_value(&self) -> Result<Vec<u8>> {
@@ -27,18 +169,87 @@ impl ValueCodec for StateNode {
 impl StateNodeStore for StateStorage {
     fn get(&self, hash: &HashValue) -> Result<Option<StateNode>> {
         //TODO use ref as key
-        self.store.get(*hash)
+        self.node_store.get(*hash)
     }
 
     fn put(&self, key: HashValue, node: StateNode) -> Result<()> {
-        self.store.put(key, node)
+        self.add_refs(std::iter::once(&key))?;
+        self.node_store.put(key, node)
     }
 
     fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<(), Error> {
+        self.add_refs(nodes.keys())?;
         let mut batch = WriteBatch::new();
         for (key, node) in nodes.iter() {
             batch.put(*key, node.clone())?;
         }
-        self.store.write_batch(batch)
+        self.node_store.write_batch(batch)
+    }
+}
+
+impl StaleStateNodeStore for StateStorage {
+    fn save_stale_state_nodes(
+        &self,
+        block_id: HashValue,
+        stale_nodes: Vec<HashValue>,
+    ) -> Result<()> {
+        if stale_nodes.is_empty() || !self.ref_counting {
+            return Ok(());
+        }
+        self.stale_store.put(block_id, stale_nodes)
+    }
+
+    fn get_stale_state_nodes(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>> {
+        self.stale_store.get(block_id)
+    }
+
+    fn save_new_state_nodes(&self, block_id: HashValue, new_nodes: Vec<HashValue>) -> Result<()> {
+        if new_nodes.is_empty() || !self.ref_counting {
+            return Ok(());
+        }
+        self.new_store.put(block_id, new_nodes)
+    }
+
+    fn get_new_state_nodes(&self, block_id: HashValue) -> Result<Option<Vec<HashValue>>> {
+        self.new_store.get(block_id)
+    }
+
+    fn prune_stale_state_nodes(&self, block_id: HashValue) -> Result<u64> {
+        // the nodes written by a master block are released by the master blocks which mark
+        // them stale later.
+        self.new_store.remove(block_id)?;
+        let stale_nodes = match self.stale_store.get(block_id)? {
+            Some(stale_nodes) => stale_nodes,
+            None => return Ok(0),
+        };
+        // the record is removed, so pruning the block again never releases twice.
+        self.stale_store.remove(block_id)?;
+        self.release_refs(stale_nodes)
+    }
+
+    fn prune_side_state_nodes(&self, block_id: HashValue) -> Result<u64> {
+        // the stale nodes of a side block are nodes of its parent's trees, which are released
+        // by master blocks, or by the side blocks which wrote them.
+        self.stale_store.remove(block_id)?;
+        let new_nodes = match self.new_store.get(block_id)? {
+            Some(new_nodes) => new_nodes,
+            None => return Ok(0),
+        };
+        self.new_store.remove(block_id)?;
+        self.release_refs(new_nodes)
+    }
+
+    fn get_state_prune_progress(&self) -> Result<Option<BlockNumber>> {
+        match self.progress_store.get(STATE_PRUNE_PROGRESS_KEY)? {
+            Some(bytes) => Ok(Some(bytes.as_slice().read_u64::<BigEndian>()?)),
+            None => Ok(None),
+        }
+    }
+
+    fn save_state_prune_progress(&self, number: BlockNumber) -> Result<()> {
+        self.progress_store.put(
+            STATE_PRUNE_PROGRESS_KEY.to_vec(),
+            number.to_be_bytes().to_vec(),
+        )
     }
 }
@@ -1,14 +1,17 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{WriteBatch, WriteBatchWithColumn};
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use crypto::HashValue;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::iter::Peekable;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -21,6 +24,111 @@ pub enum WriteOp {
     Deletion,
 }
 
+/// Iterator of raw key value pairs.
+pub type KVIterator<'a> = Box<dyn Iterator<Item = Result<(Vec<u8>, Vec<u8>)>> + 'a>;
+/// Iterator of decoded key value pairs.
+pub type TypedIterator<'a, K, V> = Box<dyn Iterator<Item = Result<(K, V)>> + 'a>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IterDirection {
+    Forward,
+    Reverse,
+}
+
+/// Define the range and order of an iteration, keys are compared in bytes.
+/// `start` is inclusive, `end` is exclusive, and only keys begin with `prefix` are returned.
+#[derive(Clone, Debug)]
+pub struct IterOptions {
+    pub direction: IterDirection,
+    pub start: Option<Vec<u8>>,
+    pub end: Option<Vec<u8>>,
+    pub prefix: Option<Vec<u8>>,
+}
+
+impl Default for IterOptions {
+    fn default() -> Self {
+        Self::forward()
+    }
+}
+
+impl IterOptions {
+    pub fn forward() -> Self {
+        Self {
+            direction: IterDirection::Forward,
+            start: None,
+            end: None,
+            prefix: None,
+        }
+    }
+
+    pub fn reverse() -> Self {
+        Self {
+            direction: IterDirection::Reverse,
+            ..Self::forward()
+        }
+    }
+
+    pub fn start(mut self, start: Vec<u8>) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    pub fn end(mut self, end: Vec<u8>) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn prefix(mut self, prefix: Vec<u8>) -> Self {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// The inclusive lower bound of keys.
+    pub fn lower_bound(&self) -> Option<Vec<u8>> {
+        match (&self.start, &self.prefix) {
+            (Some(start), Some(prefix)) => Some(std::cmp::max(start, prefix).clone()),
+            (Some(bound), None) | (None, Some(bound)) => Some(bound.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// The exclusive upper bound of keys.
+    pub fn upper_bound(&self) -> Option<Vec<u8>> {
+        let prefix_end = self
+            .prefix
+            .as_ref()
+            .and_then(|prefix| prefix_successor(prefix));
+        match (&self.end, prefix_end) {
+            (Some(end), Some(prefix_end)) => Some(std::cmp::min(end.clone(), prefix_end)),
+            (Some(bound), None) => Some(bound.clone()),
+            (None, bound) => bound,
+        }
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.lower_bound()
+            .map(|lower| key >= lower.as_slice())
+            .unwrap_or(true)
+            && self
+                .upper_bound()
+                .map(|upper| key < upper.as_slice())
+                .unwrap_or(true)
+    }
+}
+
+/// The smallest key which is greater than all keys begin with `prefix`,
+/// None if there is no such key.
+fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+    while let Some(last) = successor.pop() {
+        if last < u8::max_value() {
+            successor.push(last + 1);
+            return Some(successor);
+        }
+    }
+    None
+}
+
 pub trait KVStore: Send + Sync {
     fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
     fn put(&self, key: Vec<u8>, value: Vec<u8>) -> Result<()>;
@@ -29,6 +137,7 @@ pub trait KVStore: Send + Sync {
     fn write_batch(&self, batch: WriteBatch) -> Result<()>;
     fn get_len(&self) -> Result<u64>;
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
+    fn iter(&self, options: IterOptions) -> Result<KVIterator<'_>>;
 }
 
 pub trait InnerStore: Send + Sync {
@@ -37,8 +146,12 @@ pub trait InnerStore: Send + Sync {
     fn contains_key(&self, prefix_name: &str, key: Vec<u8>) -> Result<bool>;
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()>;
     fn write_batch(&self, prefix_name: &str, batch: WriteBatch) -> Result<()>;
+    /// Writes a group of records of multiple column families atomically.
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()>;
     fn get_len(&self) -> Result<u64>;
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
+    /// Iterate the key value pairs of column family `prefix_name` in `options`.
+    fn iter<'a>(&'a self, prefix_name: &str, options: IterOptions) -> Result<KVIterator<'a>>;
 }
 
 pub static CACHE_NONE_OBJECT: Lazy<CacheObject> = Lazy::new(|| CacheObject::None);
@@ -71,6 +184,13 @@ pub enum StorageInstance {
         cache: Arc<CacheStorage>,
         db: Arc<dyn InnerStore>,
     },
+    /// The handle of an atomic write on `base`, created by `StorageInstance::atomic_write`.
+    /// Writes are staged in `batch` and only visible to reads through the handle, the batch is
+    /// None once the atomic write is finished.
+    Staged {
+        base: Arc<StorageInstance>,
+        batch: Arc<Mutex<Option<WriteBatchWithColumn>>>,
+    },
 }
 
 impl StorageInstance {
@@ -85,6 +205,59 @@ impl StorageInstance {
     pub fn new_cache_and_db_instance(cache: Arc<CacheStorage>, db: Arc<DBStorage>) -> Self {
         Self::CacheAndDb { cache, db }
     }
+
+    /// Run `f` with a handle of this instance which stages all writes in one batch, the batch
+    /// is written atomically if `f` succeeds, and dropped if `f` fails. The staged writes are
+    /// only visible to reads through the handle, and the handle rejects writes after `f`
+    /// returns. An atomic write can not be nested in another one.
+    pub fn atomic_write<R, F>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&StorageInstance) -> Result<R>,
+    {
+        ensure!(
+            !matches!(self, StorageInstance::Staged { .. }),
+            "Can not begin an atomic write in another atomic write."
+        );
+        let batch = Arc::new(Mutex::new(Some(WriteBatchWithColumn::new())));
+        let handle = StorageInstance::Staged {
+            base: Arc::new(self.clone()),
+            batch: batch.clone(),
+        };
+        let result = f(&handle);
+        let staged = batch.lock().take().unwrap_or_default();
+        let result = result?;
+        if !staged.is_empty() {
+            self.write_batch_with_column(staged)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Get the staged write of `key` in column family `prefix_name`.
+fn get_staged(
+    batch: &Mutex<Option<WriteBatchWithColumn>>,
+    prefix_name: &str,
+    key: &[u8],
+) -> Option<WriteOp> {
+    batch
+        .lock()
+        .as_ref()
+        .and_then(|staged| staged.get(prefix_name, key).cloned())
+}
+
+/// Stage the writes of `batch` of column family `prefix_name`.
+fn stage(
+    staged: &Mutex<Option<WriteBatchWithColumn>>,
+    prefix_name: &str,
+    batch: WriteBatch,
+) -> Result<()> {
+    match staged.lock().as_mut() {
+        Some(staged) => {
+            staged.extend(prefix_name, batch);
+            Ok(())
+        }
+        None => bail!("The atomic write is finished, can not write to it any more."),
+    }
 }
 
 impl InnerStore for StorageInstance {
@@ -113,6 +286,13 @@ impl InnerStore for StorageInstance {
                     }
                 }
             }
+            StorageInstance::Staged { base, batch } => {
+                match get_staged(batch, prefix_name, key.as_slice()) {
+                    Some(WriteOp::Value(value)) => Ok(Some(value)),
+                    Some(WriteOp::Deletion) => Ok(None),
+                    None => base.get(prefix_name, key),
+                }
+            }
         }
     }
 
@@ -120,9 +300,15 @@ impl InnerStore for StorageInstance {
         match self {
             StorageInstance::CACHE { cache } => cache.put(prefix_name, key, value),
             StorageInstance::DB { db } => db.put(prefix_name, key, value),
-            StorageInstance::CacheAndDb { cache, db } => db
-                .put(prefix_name, key.clone(), value.clone())
-                .and_then(|_| cache.put_obj(prefix_name, key, CacheObject::Value(value))),
+            StorageInstance::CacheAndDb { cache, db } => {
+                db.put(prefix_name, key.clone(), value.clone())?;
+                cache.put_obj(prefix_name, key, CacheObject::Value(value))
+            }
+            StorageInstance::Staged { base: _, batch } => {
+                let mut write_batch = WriteBatch::new();
+                write_batch.rows.insert(key, WriteOp::Value(value));
+                stage(batch, prefix_name, write_batch)
+            }
         }
     }
 
@@ -139,6 +325,12 @@ impl InnerStore for StorageInstance {
                     _ => db.contains_key(prefix_name, key),
                 }
             }
+            StorageInstance::Staged { base, batch } => {
+                match get_staged(batch, prefix_name, key.as_slice()) {
+                    Some(write_op) => Ok(matches!(write_op, WriteOp::Value(_))),
+                    None => base.contains_key(prefix_name, key),
+                }
+            }
         }
     }
 
@@ -152,6 +344,11 @@ impl InnerStore for StorageInstance {
                     _ => bail!("db storage remove error."),
                 }
             }
+            StorageInstance::Staged { base: _, batch } => {
+                let mut write_batch = WriteBatch::new();
+                write_batch.rows.insert(key, WriteOp::Deletion);
+                stage(batch, prefix_name, write_batch)
+            }
         }
     }
 
@@ -165,21 +362,128 @@ impl InnerStore for StorageInstance {
                     Err(err) => bail!("write batch db error: {}", err),
                 }
             }
+            StorageInstance::Staged {
+                base: _,
+                batch: staged,
+            } => stage(staged, prefix_name, batch),
         }
     }
+    fn write_batch_with_column(&self, batch: WriteBatchWithColumn) -> Result<()> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.write_batch_with_column(batch),
+            StorageInstance::DB { db } => db.write_batch_with_column(batch),
+            StorageInstance::CacheAndDb { cache, db } => {
+                match db.write_batch_with_column(batch.clone()) {
+                    Ok(_) => {
+                        for (prefix_name, batch) in batch.rows {
+                            cache.write_batch_obj(prefix_name.as_str(), batch)?;
+                        }
+                        Ok(())
+                    }
+                    Err(err) => bail!("write batch with column db error: {}", err),
+                }
+            }
+            StorageInstance::Staged {
+                base: _,
+                batch: staged,
+            } => {
+                for (prefix_name, batch) in batch.rows {
+                    stage(staged, prefix_name.as_str(), batch)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     fn get_len(&self) -> Result<u64> {
         match self {
             StorageInstance::CACHE { cache } => cache.get_len(),
-            StorageInstance::CacheAndDb { cache, db: _ } => cache.get_len(),
-            _ => bail!("DB instance not support get length method!"),
+            StorageInstance::DB { db } => db.get_len(),
+            // the cache only holds part of the data.
+            StorageInstance::CacheAndDb { cache: _, db } => db.get_len(),
+            // staged writes are not counted.
+            StorageInstance::Staged { base, batch: _ } => base.get_len(),
         }
     }
 
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
         match self {
             StorageInstance::CACHE { cache } => cache.keys(),
-            StorageInstance::CacheAndDb { cache, db: _ } => cache.keys(),
-            _ => bail!("DB instance not support keys method!"),
+            StorageInstance::DB { db } => db.keys(),
+            StorageInstance::CacheAndDb { cache: _, db } => db.keys(),
+            StorageInstance::Staged { base, batch: _ } => base.keys(),
+        }
+    }
+
+    fn iter<'a>(&'a self, prefix_name: &str, options: IterOptions) -> Result<KVIterator<'a>> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.iter(prefix_name, options),
+            StorageInstance::DB { db } => db.iter(prefix_name, options),
+            // all writes go through the db, so the db has the complete data.
+            StorageInstance::CacheAndDb { cache: _, db } => db.iter(prefix_name, options),
+            StorageInstance::Staged { base, batch } => {
+                let mut staged: Vec<(Vec<u8>, WriteOp)> = batch
+                    .lock()
+                    .as_ref()
+                    .and_then(|staged| staged.rows.get(prefix_name))
+                    .map(|column| {
+                        column
+                            .rows
+                            .iter()
+                            .filter(|(key, _)| options.contains(key.as_slice()))
+                            .map(|(key, write_op)| (key.clone(), write_op.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if options.direction == IterDirection::Reverse {
+                    staged.reverse();
+                }
+                Ok(Box::new(StagedIterator {
+                    direction: options.direction,
+                    base: base.iter(prefix_name, options)?.peekable(),
+                    staged: staged.into_iter().peekable(),
+                }))
+            }
+        }
+    }
+}
+
+/// Iterator of the base instance of an atomic write, overlaid with the staged writes of it.
+/// Both iterators are in the same order, and a staged write hides the base value of its key.
+struct StagedIterator<'a> {
+    direction: IterDirection,
+    base: Peekable<KVIterator<'a>>,
+    staged: Peekable<std::vec::IntoIter<(Vec<u8>, WriteOp)>>,
+}
+
+impl<'a> Iterator for StagedIterator<'a> {
+    type Item = Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // which one comes first, the base item or the staged item.
+            let order = match (self.base.peek(), self.staged.peek()) {
+                (None, None) => return None,
+                (Some(Err(_)), _) | (Some(Ok(_)), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(Ok((base_key, _))), Some((staged_key, _))) => {
+                    let order = base_key.cmp(staged_key);
+                    match self.direction {
+                        IterDirection::Forward => order,
+                        IterDirection::Reverse => order.reverse(),
+                    }
+                }
+            };
+            match order {
+                Ordering::Less => return self.base.next(),
+                Ordering::Equal => {
+                    self.base.next();
+                }
+                Ordering::Greater => {}
+            }
+            if let Some((key, WriteOp::Value(value))) = self.staged.next() {
+                return Some(Ok((key, value)));
+            }
         }
     }
 }
@@ -227,6 +531,10 @@ impl KVStore for InnerStorage {
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
         self.instance.keys()
     }
+
+    fn iter(&self, options: IterOptions) -> Result<KVIterator<'_>> {
+        self.instance.iter(self.prefix_name, options)
+    }
 }
 
 pub trait KeyCodec: Sized + PartialEq + Debug {
@@ -292,6 +600,23 @@ where
     pub fn keys(&self) -> Result<Vec<Vec<u8>>> {
         self.store.keys()
     }
+
+    /// Iterate the decoded key value pairs in `options`.
+    pub fn iter<'a>(&'a self, options: IterOptions) -> Result<TypedIterator<'a, K, V>>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let iter = self.store.iter(options)?.map(|item| {
+            item.and_then(|(key, value)| {
+                Ok((
+                    K::decode_key(key.as_slice())?,
+                    V::decode_value(value.as_slice())?,
+                ))
+            })
+        });
+        Ok(Box::new(iter))
+    }
 }
 
 impl KeyCodec for HashValue {
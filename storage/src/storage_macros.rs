@@ -39,6 +39,12 @@ macro_rules! define_storage {
             pub fn keys(&self) -> Result<Vec<Vec<u8>>> {
                 self.store.keys()
             }
+            pub fn iter(
+                &self,
+                options: crate::storage::IterOptions,
+            ) -> Result<crate::storage::TypedIterator<'_, $key_type, $value_type>> {
+                self.store.iter(options)
+            }
         }
     };
 }
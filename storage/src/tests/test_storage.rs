@@ -3,14 +3,30 @@
 
 extern crate chrono;
 
+use crate::account_transaction::{AccountTransactionInnerStorage, AccountTransactionStore};
+use crate::batch::WriteBatchWithColumn;
 use crate::cache_storage::CacheStorage;
+use crate::contract_event::ContractEventStore;
 use crate::db_storage::DBStorage;
-use crate::storage::{InnerStore, StorageInstance, ValueCodec, CACHE_NONE_OBJECT};
-use crate::{Storage, TransactionInfoStore, DEFAULT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME};
+use crate::state_node::StaleStateNodeStore;
+use crate::storage::{InnerStore, IterOptions, StorageInstance, ValueCodec, CACHE_NONE_OBJECT};
+use crate::{
+    AtomicWriteStore, Storage, TransactionInfoStore, DEFAULT_PREFIX_NAME,
+    TRANSACTION_INFO_PREFIX_NAME, TRANSACTION_PREFIX_NAME,
+};
 use anyhow::Result;
 use crypto::{hash::PlainCryptoHash, HashValue};
+use forkable_jellyfish_merkle::node_type::Node;
+use starcoin_config::StorageConfig;
+use starcoin_state_store_api::{StateNode, StateNodeStore};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_transaction::{AccountTransactionInfo, AccountTransactionKind};
+use starcoin_types::contract_event::{ContractEvent, ContractEventInfo};
+use starcoin_types::event::EventKey;
+use starcoin_types::language_storage::TypeTag;
 use starcoin_types::transaction::TransactionInfo;
 use starcoin_types::vm_error::StatusCode;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[test]
@@ -196,3 +212,573 @@ fn test_missing_key_handle() -> Result<()> {
     assert_eq!(contains, false);
     Ok(())
 }
+
+#[test]
+fn test_contract_event_index() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_db_instance(db_storage))?;
+    let block_id = HashValue::random();
+    let txn_hash = HashValue::random();
+    let event_key = EventKey::new_from_address(&AccountAddress::random(), 0);
+    let events: Vec<ContractEventInfo> = (0..3)
+        .map(|seq| {
+            ContractEventInfo::new(
+                block_id,
+                1,
+                txn_hash,
+                0,
+                ContractEvent::new(event_key, seq, TypeTag::Bool, vec![seq as u8]),
+            )
+        })
+        .collect();
+    storage.save_contract_events(block_id, events.clone())?;
+    assert_eq!(
+        storage.get_contract_events_by_block(block_id)?,
+        Some(events.clone())
+    );
+    // events are indexed by key only when the block is on master.
+    assert_eq!(storage.get_contract_event(event_key, 1)?, None);
+    storage.index_contract_events(vec![], events.clone())?;
+    assert_eq!(
+        storage.get_contract_event(event_key, 1)?,
+        Some(events[1].clone())
+    );
+    assert_eq!(storage.get_contract_event(event_key, 3)?, None);
+    assert_eq!(
+        storage.get_contract_events_by_block(HashValue::random())?,
+        None
+    );
+    assert_eq!(
+        storage.get_contract_events_by_key(event_key, None, 2)?,
+        vec![events[2].clone(), events[1].clone()]
+    );
+    assert_eq!(
+        storage.get_contract_events_by_key(event_key, Some(1), 2)?,
+        vec![events[0].clone()]
+    );
+    let other_key = EventKey::new_from_address(&AccountAddress::random(), 0);
+    assert!(storage
+        .get_contract_events_by_key(other_key, None, 2)?
+        .is_empty());
+
+    // retract the block, and enact a fork block with the first two events of the key.
+    let fork_events: Vec<ContractEventInfo> = events[..2]
+        .iter()
+        .map(|event_info| {
+            ContractEventInfo::new(
+                HashValue::random(),
+                1,
+                txn_hash,
+                0,
+                event_info.event.clone(),
+            )
+        })
+        .collect();
+    storage.index_contract_events(events.clone(), fork_events.clone())?;
+    assert_eq!(
+        storage.get_contract_events_by_key(event_key, None, 3)?,
+        vec![fork_events[1].clone(), fork_events[0].clone()]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_account_transaction_index() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_db_instance(db_storage))?;
+    let sender = AccountAddress::random();
+    let receiver = AccountAddress::random();
+    let block_ids: Vec<HashValue> = (0..3).map(|_| HashValue::random()).collect();
+    for (number, block_id) in block_ids.iter().enumerate() {
+        let txn_hash = HashValue::random();
+        storage.append_account_transactions(vec![
+            (
+                sender,
+                AccountTransactionInfo::new(
+                    AccountTransactionKind::Sent,
+                    *block_id,
+                    number as u64,
+                    txn_hash,
+                    1,
+                ),
+            ),
+            (
+                receiver,
+                AccountTransactionInfo::new(
+                    AccountTransactionKind::Received,
+                    *block_id,
+                    number as u64,
+                    txn_hash,
+                    1,
+                ),
+            ),
+        ])?;
+    }
+    assert_eq!(storage.get_account_transaction_count(sender)?, 3);
+    let txns = storage.get_account_transactions(sender, None, 2)?;
+    assert_eq!(txns.len(), 2);
+    assert_eq!(txns[0].index, 2);
+    assert_eq!(txns[0].block_hash, block_ids[2]);
+    assert_eq!(txns[1].index, 1);
+    let txns = storage.get_account_transactions(receiver, Some(0), 10)?;
+    assert_eq!(txns.len(), 1);
+    assert_eq!(txns[0].kind, AccountTransactionKind::Received);
+    assert_eq!(txns[0].block_hash, block_ids[0]);
+
+    // revert the last block, as if it is retracted by a reorg.
+    storage.revert_account_transactions(block_ids[2], vec![sender, receiver])?;
+    // only the tail of history can be reverted.
+    storage.revert_account_transactions(block_ids[0], vec![sender, receiver])?;
+    assert_eq!(storage.get_account_transaction_count(sender)?, 2);
+    assert_eq!(storage.get_account_transaction_count(receiver)?, 2);
+    let txns = storage.get_account_transactions(sender, None, 10)?;
+    assert_eq!(
+        txns.iter().map(|txn| txn.block_hash).collect::<Vec<_>>(),
+        vec![block_ids[1], block_ids[0]]
+    );
+    assert!(storage
+        .get_account_transactions(AccountAddress::random(), None, 10)?
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_prune_stale_state_nodes() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_db_instance(db_storage))?;
+    let shared_node = StateNode(Node::new_leaf(HashValue::random(), vec![1u8].into()));
+    let shared_key = shared_node.inner().hash();
+    let owned_node = StateNode(Node::new_leaf(HashValue::random(), vec![2u8].into()));
+    let owned_key = owned_node.inner().hash();
+
+    // two trees write the shared node, only one of them writes the owned node.
+    let mut nodes = BTreeMap::new();
+    nodes.insert(shared_key, shared_node.clone());
+    nodes.insert(owned_key, owned_node);
+    storage.write_nodes(nodes)?;
+    StateNodeStore::put(&storage, shared_key, shared_node.clone())?;
+
+    let block_id = HashValue::random();
+    storage.save_stale_state_nodes(block_id, vec![shared_key, owned_key])?;
+    assert_eq!(storage.get_stale_state_nodes(block_id)?.unwrap().len(), 2);
+    assert_eq!(storage.prune_stale_state_nodes(block_id)?, 1);
+    assert!(StateNodeStore::get(&storage, &owned_key)?.is_none());
+    assert_eq!(
+        StateNodeStore::get(&storage, &shared_key)?,
+        Some(shared_node)
+    );
+    // stale record is removed after pruned, prune again should not release twice.
+    assert!(storage.get_stale_state_nodes(block_id)?.is_none());
+    assert_eq!(storage.prune_stale_state_nodes(block_id)?, 0);
+
+    let block_id = HashValue::random();
+    storage.save_stale_state_nodes(block_id, vec![shared_key])?;
+    assert_eq!(storage.prune_stale_state_nodes(block_id)?, 1);
+    assert!(StateNodeStore::get(&storage, &shared_key)?.is_none());
+
+    assert_eq!(storage.get_state_prune_progress()?, None);
+    storage.save_state_prune_progress(10)?;
+    assert_eq!(storage.get_state_prune_progress()?, Some(10));
+
+    // archive storage keeps no reference count and no stale record, nothing is pruned.
+    let tmpdir = starcoin_config::temp_path();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage =
+        Storage::new_with_state_pruning(StorageInstance::new_db_instance(db_storage), false)?;
+    let node = StateNode(Node::new_leaf(HashValue::random(), vec![3u8].into()));
+    let key = node.inner().hash();
+    StateNodeStore::put(&storage, key, node.clone())?;
+    let block_id = HashValue::random();
+    storage.save_stale_state_nodes(block_id, vec![key])?;
+    assert!(storage.get_stale_state_nodes(block_id)?.is_none());
+    assert_eq!(storage.prune_stale_state_nodes(block_id)?, 0);
+    assert_eq!(StateNodeStore::get(&storage, &key)?, Some(node));
+    Ok(())
+}
+
+#[test]
+fn test_prune_side_state_nodes() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_db_instance(db_storage))?;
+    let master_node = StateNode(Node::new_leaf(HashValue::random(), vec![1u8].into()));
+    let master_key = master_node.inner().hash();
+    let side_node = StateNode(Node::new_leaf(HashValue::random(), vec![2u8].into()));
+    let side_key = side_node.inner().hash();
+
+    // the master block and the side block both write the master node, and the side block
+    // marks a node of its parent stale.
+    StateNodeStore::put(&storage, master_key, master_node.clone())?;
+    let mut nodes = BTreeMap::new();
+    nodes.insert(master_key, master_node.clone());
+    nodes.insert(side_key, side_node);
+    storage.write_nodes(nodes)?;
+    let master_block_id = HashValue::random();
+    storage.save_new_state_nodes(master_block_id, vec![master_key])?;
+    let side_block_id = HashValue::random();
+    storage.save_new_state_nodes(side_block_id, vec![master_key, side_key])?;
+    storage.save_stale_state_nodes(side_block_id, vec![master_key])?;
+
+    // the new record of a master block is dropped, its nodes are released by later blocks.
+    assert_eq!(storage.prune_stale_state_nodes(master_block_id)?, 0);
+    assert!(storage.get_new_state_nodes(master_block_id)?.is_none());
+
+    // the stale record of a side block is dropped without release.
+    assert_eq!(storage.prune_side_state_nodes(side_block_id)?, 1);
+    assert!(StateNodeStore::get(&storage, &side_key)?.is_none());
+    assert_eq!(
+        StateNodeStore::get(&storage, &master_key)?,
+        Some(master_node)
+    );
+    assert!(storage.get_new_state_nodes(side_block_id)?.is_none());
+    assert!(storage.get_stale_state_nodes(side_block_id)?.is_none());
+    assert_eq!(storage.prune_side_state_nodes(side_block_id)?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_write_batch_with_column() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = DBStorage::new(tmpdir.path());
+    let key1 = HashValue::random().to_vec();
+    let key2 = HashValue::random().to_vec();
+    db.put(DEFAULT_PREFIX_NAME, key2.clone(), vec![1])?;
+
+    let mut batch = WriteBatchWithColumn::new();
+    batch.put(DEFAULT_PREFIX_NAME, key1.clone(), vec![2]);
+    batch.put(TRANSACTION_INFO_PREFIX_NAME, key1.clone(), vec![3]);
+    batch.delete(DEFAULT_PREFIX_NAME, key2.clone());
+    db.write_batch_with_column(batch)?;
+
+    assert_eq!(db.get(DEFAULT_PREFIX_NAME, key1.clone())?, Some(vec![2]));
+    assert_eq!(db.get(TRANSACTION_INFO_PREFIX_NAME, key1)?, Some(vec![3]));
+    assert_eq!(db.get(DEFAULT_PREFIX_NAME, key2)?, None);
+    Ok(())
+}
+
+#[test]
+fn test_atomic_write() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_db_instance(db.clone()))?;
+    let txn_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::zero(),
+        HashValue::zero(),
+        vec![],
+        0,
+        StatusCode::ABORTED,
+    );
+    let txn_info_id = txn_info.crypto_hash();
+
+    // writes of a failed atomic write are dropped.
+    let result: Result<()> = storage.atomic_write(|staged| {
+        staged.save_transaction_info(txn_info.clone())?;
+        assert!(staged.get_transaction_info(txn_info_id)?.is_some());
+        anyhow::bail!("abort")
+    });
+    assert!(result.is_err());
+    assert!(storage.get_transaction_info(txn_info_id)?.is_none());
+
+    // staged writes are only visible through the atomic write before it finishes.
+    storage.atomic_write(|staged| {
+        staged.save_transaction_info(txn_info.clone())?;
+        assert!(storage.get_transaction_info(txn_info_id)?.is_none());
+        assert!(db
+            .get(TRANSACTION_INFO_PREFIX_NAME, txn_info_id.to_vec())?
+            .is_none());
+        Ok(())
+    })?;
+    assert_eq!(storage.get_transaction_info(txn_info_id)?, Some(txn_info));
+
+    // the cache in front of the db only sees committed writes.
+    let tmpdir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new(tmpdir.path()));
+    let cache = Arc::new(CacheStorage::new());
+    let instance = StorageInstance::new_cache_and_db_instance(cache.clone(), db);
+    let key = HashValue::random().to_vec();
+    instance.put(TRANSACTION_INFO_PREFIX_NAME, key.clone(), vec![1])?;
+
+    let result: Result<()> = instance.atomic_write(|staged| {
+        staged.put(TRANSACTION_INFO_PREFIX_NAME, key.clone(), vec![2])?;
+        assert_eq!(
+            staged.get(TRANSACTION_INFO_PREFIX_NAME, key.clone())?,
+            Some(vec![2])
+        );
+        assert_eq!(
+            instance.get(TRANSACTION_INFO_PREFIX_NAME, key.clone())?,
+            Some(vec![1])
+        );
+        anyhow::bail!("abort")
+    });
+    assert!(result.is_err());
+    assert_eq!(
+        instance.get(TRANSACTION_INFO_PREFIX_NAME, key.clone())?,
+        Some(vec![1])
+    );
+    assert_eq!(
+        cache.get(TRANSACTION_INFO_PREFIX_NAME, key.clone())?,
+        Some(vec![1])
+    );
+
+    instance.atomic_write(|staged| {
+        staged.remove(TRANSACTION_INFO_PREFIX_NAME, key.clone())?;
+        assert!(!staged.contains_key(TRANSACTION_INFO_PREFIX_NAME, key.clone())?);
+        assert_eq!(
+            cache.get(TRANSACTION_INFO_PREFIX_NAME, key.clone())?,
+            Some(vec![1])
+        );
+        Ok(())
+    })?;
+    assert_eq!(cache.get(TRANSACTION_INFO_PREFIX_NAME, key.clone())?, None);
+    assert_eq!(instance.get(TRANSACTION_INFO_PREFIX_NAME, key)?, None);
+    Ok(())
+}
+
+#[test]
+fn test_atomic_write_handle() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let instance = StorageInstance::new_db_instance(Arc::new(DBStorage::new(tmpdir.path())));
+
+    // an atomic write can not be nested.
+    let nested = instance.atomic_write(|staged| staged.atomic_write(|_| Ok(())));
+    assert!(nested.is_err());
+
+    // the handle rejects writes after the atomic write is finished.
+    let handle = instance.atomic_write(|staged| Ok(staged.clone()))?;
+    assert!(handle.put(DEFAULT_PREFIX_NAME, vec![1], vec![1]).is_err());
+    assert_eq!(instance.get(DEFAULT_PREFIX_NAME, vec![1])?, None);
+    Ok(())
+}
+
+#[test]
+fn test_atomic_write_iter() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let instance = StorageInstance::new_db_instance(Arc::new(DBStorage::new(tmpdir.path())));
+    for key in 1u8..=4 {
+        instance.put(DEFAULT_PREFIX_NAME, vec![key], vec![key])?;
+    }
+    instance.atomic_write(|staged| {
+        staged.put(DEFAULT_PREFIX_NAME, vec![0], vec![0])?;
+        staged.put(DEFAULT_PREFIX_NAME, vec![2], vec![20])?;
+        staged.remove(DEFAULT_PREFIX_NAME, vec![3])?;
+        staged.put(DEFAULT_PREFIX_NAME, vec![5], vec![5])?;
+        let forward = staged
+            .iter(DEFAULT_PREFIX_NAME, IterOptions::forward())?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            forward,
+            vec![
+                (vec![0], vec![0]),
+                (vec![1], vec![1]),
+                (vec![2], vec![20]),
+                (vec![4], vec![4]),
+                (vec![5], vec![5]),
+            ]
+        );
+        let reverse = staged
+            .iter(
+                DEFAULT_PREFIX_NAME,
+                IterOptions::reverse().start(vec![1]).end(vec![5]),
+            )?
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(reverse, vec![vec![4], vec![2], vec![1]]);
+        Ok(())
+    })?;
+    assert_eq!(
+        instance
+            .iter(DEFAULT_PREFIX_NAME, IterOptions::forward())?
+            .count(),
+        5
+    );
+    Ok(())
+}
+
+#[test]
+fn test_db_len_and_keys() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = Arc::new(DBStorage::new(tmpdir.path()));
+    let instance = StorageInstance::new_db_instance(db);
+    let base_len = instance.get_len()?;
+    let key = HashValue::random().to_vec();
+    instance.put(TRANSACTION_INFO_PREFIX_NAME, key.clone(), vec![1])?;
+    instance.put(TRANSACTION_PREFIX_NAME, key.clone(), vec![2])?;
+    assert_eq!(instance.get_len()?, base_len + 2);
+    let keys = instance.keys()?;
+    assert_eq!(keys.len() as u64, base_len + 2);
+    let mut composed = vec![TRANSACTION_INFO_PREFIX_NAME.len() as u8];
+    composed.extend_from_slice(TRANSACTION_INFO_PREFIX_NAME.as_bytes());
+    composed.extend_from_slice(key.as_slice());
+    assert!(keys.contains(&composed));
+    Ok(())
+}
+
+fn check_iter(store: &dyn InnerStore) -> Result<()> {
+    let keys: Vec<Vec<u8>> = vec![
+        vec![1],
+        vec![1, 1],
+        vec![1, 2],
+        vec![2],
+        vec![2, 0xff],
+        vec![3],
+    ];
+    for key in &keys {
+        store.put(TRANSACTION_INFO_PREFIX_NAME, key.clone(), key.clone())?;
+    }
+    // keys of another column family should not be iterated.
+    store.put(DEFAULT_PREFIX_NAME, vec![1, 3], vec![])?;
+    let collect = |options: IterOptions| -> Result<Vec<Vec<u8>>> {
+        store
+            .iter(TRANSACTION_INFO_PREFIX_NAME, options)?
+            .map(|item| item.map(|(key, _)| key))
+            .collect()
+    };
+
+    assert_eq!(collect(IterOptions::forward())?, keys);
+    let mut reversed = keys.clone();
+    reversed.reverse();
+    assert_eq!(collect(IterOptions::reverse())?, reversed);
+    assert_eq!(
+        collect(IterOptions::forward().start(vec![1, 2]).end(vec![2, 0xff]))?,
+        vec![vec![1, 2], vec![2]]
+    );
+    assert_eq!(
+        collect(IterOptions::reverse().start(vec![1, 2]).end(vec![2, 0xff]))?,
+        vec![vec![2], vec![1, 2]]
+    );
+    assert_eq!(
+        collect(IterOptions::forward().prefix(vec![1]))?,
+        vec![vec![1], vec![1, 1], vec![1, 2]]
+    );
+    assert_eq!(
+        collect(IterOptions::reverse().prefix(vec![2]))?,
+        vec![vec![2, 0xff], vec![2]]
+    );
+    assert_eq!(
+        collect(IterOptions::reverse().prefix(vec![1]).start(vec![1, 1]))?,
+        vec![vec![1, 2], vec![1, 1]]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_db_iter() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = DBStorage::new(tmpdir.path());
+    check_iter(&db)
+}
+
+#[test]
+fn test_cache_iter() -> Result<()> {
+    check_iter(&CacheStorage::new())
+}
+
+#[test]
+fn test_typed_iter() -> Result<()> {
+    let store = AccountTransactionInnerStorage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ));
+    let address = AccountAddress::random();
+    let other_address = AccountAddress::random();
+    for index in 0..3u64 {
+        for address in &[address, other_address] {
+            let txn_info = AccountTransactionInfo::new(
+                AccountTransactionKind::Sent,
+                HashValue::random(),
+                index,
+                HashValue::random(),
+                0,
+            );
+            store.put((*address, index), txn_info)?;
+        }
+    }
+    let keys = store
+        .iter(IterOptions::reverse().prefix(address.to_vec()))?
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(keys, vec![(address, 2), (address, 1), (address, 0)]);
+    Ok(())
+}
+
+#[test]
+fn test_cache_capacity() -> Result<()> {
+    // every entry takes 1 byte key, 100 bytes value and the entry overhead.
+    let cache = CacheStorage::new_with_capacity(400);
+    for i in 0..3u8 {
+        cache.put(DEFAULT_PREFIX_NAME, vec![i], vec![i; 100])?;
+    }
+    cache.put(TRANSACTION_INFO_PREFIX_NAME, vec![0], vec![0; 100])?;
+    assert_eq!(cache.get(DEFAULT_PREFIX_NAME, vec![0])?, None);
+    assert_eq!(cache.get(DEFAULT_PREFIX_NAME, vec![1])?, Some(vec![1; 100]));
+    assert_eq!(cache.get(DEFAULT_PREFIX_NAME, vec![2])?, Some(vec![2; 100]));
+    assert_eq!(
+        cache.get(TRANSACTION_INFO_PREFIX_NAME, vec![0])?,
+        Some(vec![0; 100])
+    );
+    assert_eq!(cache.get_len()?, 3);
+
+    // entry 1 is the least recently used one now.
+    cache.get(DEFAULT_PREFIX_NAME, vec![2])?;
+    cache.put(DEFAULT_PREFIX_NAME, vec![3], vec![3; 100])?;
+    assert_eq!(cache.get(DEFAULT_PREFIX_NAME, vec![1])?, None);
+    assert_eq!(cache.get(DEFAULT_PREFIX_NAME, vec![2])?, Some(vec![2; 100]));
+
+    // an entry larger than the capacity is not cached.
+    cache.put(DEFAULT_PREFIX_NAME, vec![4], vec![4; 400])?;
+    assert_eq!(cache.get(DEFAULT_PREFIX_NAME, vec![4])?, None);
+    Ok(())
+}
+
+#[test]
+fn test_cache_disabled_column() -> Result<()> {
+    let config = StorageConfig::default();
+    assert_eq!(config.column_cache_size(TRANSACTION_INFO_PREFIX_NAME), 0);
+    let cache_storage = Arc::new(CacheStorage::new_with_config(&config));
+    let tmpdir = starcoin_config::temp_path();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        cache_storage.clone(),
+        db_storage,
+    ))?;
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::zero(),
+        HashValue::zero(),
+        vec![],
+        0,
+        StatusCode::ABORTED,
+    );
+    let id = transaction_info.crypto_hash();
+    storage.save_transaction_infos(vec![transaction_info.clone()])?;
+    assert_eq!(storage.get_transaction_info(id)?, Some(transaction_info));
+    assert_eq!(
+        cache_storage.get_obj(TRANSACTION_INFO_PREFIX_NAME, id.to_vec())?,
+        None
+    );
+    assert_eq!(cache_storage.get_len()?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_db_property() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = DBStorage::open_with_config(
+        tmpdir.path().join("starcoindb"),
+        false,
+        &StorageConfig::default(),
+    )?;
+    for i in 0..10u8 {
+        db.put(DEFAULT_PREFIX_NAME, vec![i], vec![i; 100])?;
+    }
+    db.flush_all()?;
+    assert!(db.property_int_value(DEFAULT_PREFIX_NAME, "rocksdb.estimate-num-keys")? > Some(0));
+    assert!(db.property_int_value(DEFAULT_PREFIX_NAME, "rocksdb.total-sst-files-size")? > Some(0));
+    assert!(db
+        .property_value(DEFAULT_PREFIX_NAME, "rocksdb.cfstats")?
+        .is_some());
+    Ok(())
+}
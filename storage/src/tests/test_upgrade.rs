@@ -0,0 +1,161 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_storage::DBStorage;
+use crate::storage::InnerStore;
+use crate::upgrade::{
+    default_migrations, write_schema_version, Migration, MigrationRegistry, MigrationStep,
+    StorageInitError, CURRENT_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION, SCHEMA_VERSION_KEY,
+};
+use crate::{
+    BLOCK_INFO_PREFIX_NAME, CONTRACT_EVENT_PREFIX_NAME, DEFAULT_PREFIX_NAME,
+    STATE_NODE_REF_PREFIX_NAME, VEC_PREFIX_NAME,
+};
+use anyhow::Result;
+use crypto::HashValue;
+
+/// Create a db with the legacy layout, which has no schema version and an extra cf.
+fn create_legacy_db(path: &std::path::Path, extra_cf: &str) -> Result<()> {
+    let mut db_opts = rocksdb::Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.create_missing_column_families(true);
+    let mut cfs: Vec<&str> = VEC_PREFIX_NAME.to_vec();
+    cfs.push(extra_cf);
+    let db = rocksdb::DB::open_cf(&db_opts, path, cfs)?;
+    let cf_handle = db.cf_handle(extra_cf).unwrap();
+    db.put_cf(cf_handle, b"key", b"value")?;
+    Ok(())
+}
+
+/// The default migrations, with the legacy one replaced by `steps`.
+fn registry_with_legacy_steps(steps: Vec<MigrationStep>) -> MigrationRegistry {
+    let mut migrations = default_migrations();
+    migrations[0] = Migration::new(LEGACY_SCHEMA_VERSION, "test legacy migration", steps);
+    MigrationRegistry::new(migrations)
+}
+
+#[test]
+fn test_new_db_schema_version() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let db = DBStorage::new(tmpdir.path());
+    assert_eq!(db.schema_version()?, Some(CURRENT_SCHEMA_VERSION));
+    Ok(())
+}
+
+#[test]
+fn test_upgrade_legacy_db() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let key = HashValue::random();
+    {
+        let db = DBStorage::new(tmpdir.path());
+        db.put(BLOCK_INFO_PREFIX_NAME, key.to_vec(), key.to_vec())?;
+        db.remove(DEFAULT_PREFIX_NAME, SCHEMA_VERSION_KEY.to_vec())?;
+        assert_eq!(db.schema_version()?, None);
+    }
+    let db = DBStorage::new(tmpdir.path());
+    assert_eq!(db.schema_version()?, Some(CURRENT_SCHEMA_VERSION));
+    assert_eq!(
+        db.get(BLOCK_INFO_PREFIX_NAME, key.to_vec())?,
+        Some(key.to_vec())
+    );
+    Ok(())
+}
+
+#[test]
+fn test_refuse_newer_schema() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    {
+        let db = DBStorage::new(tmpdir.path());
+        db.put(
+            DEFAULT_PREFIX_NAME,
+            SCHEMA_VERSION_KEY.to_vec(),
+            (CURRENT_SCHEMA_VERSION + 1).to_be_bytes().to_vec(),
+        )?;
+    }
+    let result = DBStorage::open(tmpdir.path().join("starcoindb"), false);
+    match result {
+        Err(e) => match e.downcast_ref::<StorageInitError>() {
+            Some(StorageInitError::SchemaVersionTooNew { found, .. }) => {
+                assert_eq!(*found, CURRENT_SCHEMA_VERSION + 1)
+            }
+            _ => panic!("unexpected error: {:?}", e),
+        },
+        Ok(_) => panic!("open db with newer schema version should fail."),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_refuse_unknown_cf() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let path = tmpdir.path().join("starcoindb");
+    create_legacy_db(path.as_path(), "unknown_cf")?;
+    let registry = registry_with_legacy_steps(vec![]);
+    match DBStorage::open_with_registry(path, false, &registry) {
+        Err(e) => assert!(matches!(
+            e.downcast_ref::<StorageInitError>(),
+            Some(StorageInitError::UnknownColumnFamily { .. })
+        )),
+        Ok(_) => panic!("open db with unknown cf should fail."),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rename_cf_migration() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let path = tmpdir.path().join("starcoindb");
+    create_legacy_db(path.as_path(), "old_block_info")?;
+    let registry = registry_with_legacy_steps(vec![MigrationStep::RenameColumnFamily {
+        from: "old_block_info",
+        to: BLOCK_INFO_PREFIX_NAME,
+    }]);
+    let db = DBStorage::open_with_registry(path.as_path(), false, &registry)?;
+    assert_eq!(db.schema_version()?, Some(CURRENT_SCHEMA_VERSION));
+    assert_eq!(
+        db.get(BLOCK_INFO_PREFIX_NAME, b"key".to_vec())?,
+        Some(b"value".to_vec())
+    );
+    drop(db);
+    assert!(!DBStorage::list_cf(path)?.contains(&"old_block_info".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_upgrade_adds_column_families() -> Result<()> {
+    let tmpdir = starcoin_config::temp_path();
+    let path = tmpdir.path().join("starcoindb");
+    {
+        // a db of schema version 1, without the column families added later.
+        let legacy_cfs: Vec<&str> = default_migrations()[0]
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                MigrationStep::AddColumnFamily(cf) => Some(*cf),
+                _ => None,
+            })
+            .collect();
+        assert!(legacy_cfs.contains(&BLOCK_INFO_PREFIX_NAME));
+        let mut db_opts = rocksdb::Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let db = rocksdb::DB::open_cf(&db_opts, path.as_path(), legacy_cfs)?;
+        write_schema_version(&db, 1)?;
+    }
+    match DBStorage::open(path.as_path(), true) {
+        Err(e) => assert!(matches!(
+            e.downcast_ref::<StorageInitError>(),
+            Some(StorageInitError::UpgradeRequired { found: 1, .. })
+        )),
+        Ok(_) => panic!("open db need upgrade in read only mode should fail."),
+    }
+    let db = DBStorage::open(path.as_path(), false)?;
+    assert_eq!(db.schema_version()?, Some(CURRENT_SCHEMA_VERSION));
+    drop(db);
+    let cfs = DBStorage::list_cf(path.as_path())?;
+    assert!(cfs.contains(&CONTRACT_EVENT_PREFIX_NAME.to_string()));
+    assert!(cfs.contains(&STATE_NODE_REF_PREFIX_NAME.to_string()));
+    let db = DBStorage::open(path, true)?;
+    assert_eq!(db.get(CONTRACT_EVENT_PREFIX_NAME, b"key".to_vec())?, None);
+    Ok(())
+}
@@ -0,0 +1,472 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Schema versioning and in place migration of the db column families.
+//!
+//! The schema version is persisted in the default column family. When an old db is opened,
+//! every registered migration between the persisted version and `CURRENT_SCHEMA_VERSION` is
+//! applied in order, and the new version is recorded after each migration succeeds.
+
+use crate::account_transaction::backfill_account_transactions;
+use crate::contract_event::backfill_contract_events;
+use crate::storage::{ColumnFamilyName, KeyCodec, ValueCodec};
+use crate::{
+    ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME, ACCOUNT_TRANSACTION_PREFIX_NAME,
+    ACCUMULATOR_NODE_PREFIX_NAME, BLOCK_BODY_PREFIX_NAME, BLOCK_EVENT_PREFIX_NAME,
+    BLOCK_HEADER_PREFIX_NAME, BLOCK_INFO_PREFIX_NAME, BLOCK_NUM_PREFIX_NAME, BLOCK_PREFIX_NAME,
+    BLOCK_SONS_PREFIX_NAME, BLOCK_TRANSACTIONS_PREFIX_NAME, BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+    CONTRACT_EVENT_PREFIX_NAME, DEFAULT_PREFIX_NAME, NEW_STATE_NODE_PREFIX_NAME,
+    STALE_STATE_NODE_PREFIX_NAME, STARTUP_INFO_PREFIX_NAME, STATE_NODE_PREFIX_NAME,
+    STATE_NODE_REF_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME, TRANSACTION_PREFIX_NAME,
+};
+use anyhow::{format_err, Result};
+use crypto::HashValue;
+use logger::prelude::*;
+use rocksdb::{IteratorMode, WriteBatch as DBWriteBatch, WriteOptions, DB};
+use starcoin_types::block::BlockHeader;
+use starcoin_types::startup_info::StartupInfo;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use thiserror::Error;
+
+pub type SchemaVersion = u64;
+
+/// The schema version of a db created before schema versioning was introduced.
+pub const LEGACY_SCHEMA_VERSION: SchemaVersion = 0;
+/// The schema version this build reads and writes.
+/// 1: the column families before schema versioning.
+/// 2: contract event index.
+/// 3: account transaction index.
+/// 4: reference count, stale and new records of state nodes.
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = 4;
+/// The key of schema version in default column family.
+pub const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// How many rows are written in one batch when a migration copies or rewrites a column family.
+const MIGRATION_BATCH_SIZE: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum StorageInitError {
+    #[error("unknown column family {cf} found in db {path:?}, please clear the db dir")]
+    UnknownColumnFamily { path: PathBuf, cf: String },
+    #[error("db schema version {found} is newer than supported version {supported}")]
+    SchemaVersionTooNew {
+        found: SchemaVersion,
+        supported: SchemaVersion,
+    },
+    #[error(
+        "db schema version {found} need upgrade to {required}, can not upgrade in read only mode"
+    )]
+    UpgradeRequired {
+        found: SchemaVersion,
+        required: SchemaVersion,
+    },
+    #[error("no migration registered for db schema version {0}")]
+    MissingMigration(SchemaVersion),
+    #[error("migrate db schema from version {from} to {to} failed, cause: {cause:?}")]
+    MigrationFailed {
+        from: SchemaVersion,
+        to: SchemaVersion,
+        cause: anyhow::Error,
+    },
+    #[error("invalid db schema version data: {0:?}")]
+    InvalidSchemaVersion(Vec<u8>),
+    #[error("column family {0} is not created by any migration")]
+    MissingColumnFamily(String),
+}
+
+/// Rewrite function of a row, return None to delete the row.
+pub type RowRewriter = fn(&[u8], &[u8]) -> Result<Option<(Vec<u8>, Vec<u8>)>>;
+/// Fill a column family from the data of others, such as building a new index of old blocks.
+pub type Backfiller = fn(&DB) -> Result<()>;
+/// Options of a column family created by a migration, by column family name.
+pub type ColumnFamilyOptions<'a> = &'a dyn Fn(&str) -> rocksdb::Options;
+
+pub enum MigrationStep {
+    /// Create a new empty column family.
+    AddColumnFamily(ColumnFamilyName),
+    /// Move all rows of column family `from` to `to`, then drop `from`.
+    RenameColumnFamily {
+        from: ColumnFamilyName,
+        to: ColumnFamilyName,
+    },
+    /// Drop a column family and all its rows.
+    DropColumnFamily(ColumnFamilyName),
+    /// Rewrite every row of a column family, for example re-encode values.
+    RewriteColumnFamily {
+        cf: ColumnFamilyName,
+        rewriter: RowRewriter,
+    },
+    /// Fill column family `cf` with `backfiller`, `cf` must exist.
+    Backfill {
+        cf: ColumnFamilyName,
+        backfiller: Backfiller,
+    },
+}
+
+impl std::fmt::Display for MigrationStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationStep::AddColumnFamily(cf) => write!(f, "add column family {}", cf),
+            MigrationStep::RenameColumnFamily { from, to } => {
+                write!(f, "rename column family {} to {}", from, to)
+            }
+            MigrationStep::DropColumnFamily(cf) => write!(f, "drop column family {}", cf),
+            MigrationStep::RewriteColumnFamily { cf, .. } => {
+                write!(f, "rewrite column family {}", cf)
+            }
+            MigrationStep::Backfill { cf, .. } => write!(f, "backfill column family {}", cf),
+        }
+    }
+}
+
+/// A migration upgrade the db from schema version `from` to `from + 1`.
+pub struct Migration {
+    pub from: SchemaVersion,
+    pub description: &'static str,
+    pub steps: Vec<MigrationStep>,
+}
+
+impl Migration {
+    pub fn new(from: SchemaVersion, description: &'static str, steps: Vec<MigrationStep>) -> Self {
+        Self {
+            from,
+            description,
+            steps,
+        }
+    }
+
+    pub fn to(&self) -> SchemaVersion {
+        self.from + 1
+    }
+
+    fn apply(&self, db: &mut DB, cf_options: ColumnFamilyOptions) -> Result<()> {
+        for step in &self.steps {
+            info!("Migration {} -> {}: {}", self.from, self.to(), step);
+            match step {
+                MigrationStep::AddColumnFamily(cf) => add_cf(db, cf, cf_options)?,
+                MigrationStep::RenameColumnFamily { from, to } => {
+                    add_cf(db, to, cf_options)?;
+                    rewrite_cf(db, from, to, |key, value| {
+                        Ok(Some((key.to_vec(), value.to_vec())))
+                    })?;
+                    db.drop_cf(from)?;
+                }
+                MigrationStep::DropColumnFamily(cf) => {
+                    if db.cf_handle(cf).is_some() {
+                        db.drop_cf(cf)?;
+                    }
+                }
+                MigrationStep::RewriteColumnFamily { cf, rewriter } => {
+                    rewrite_cf(db, cf, cf, *rewriter)?;
+                }
+                MigrationStep::Backfill { cf, backfiller } => {
+                    db.cf_handle(cf).ok_or_else(|| {
+                        format_err!("DB::cf_handle not found for column family name: {}", cf)
+                    })?;
+                    backfiller(db)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// All registered migrations, a migration must be registered here when the schema changes and
+/// `CURRENT_SCHEMA_VERSION` is increased.
+pub struct MigrationRegistry {
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRegistry {
+    pub fn new(migrations: Vec<Migration>) -> Self {
+        Self { migrations }
+    }
+
+    pub fn get(&self, from: SchemaVersion) -> Option<&Migration> {
+        self.migrations.iter().find(|m| m.from == from)
+    }
+
+    /// Upgrade db from `version` to `target` and persist the version after every migration,
+    /// the column families created by migrations use the options from `cf_options`.
+    pub fn upgrade(
+        &self,
+        db: &mut DB,
+        mut version: SchemaVersion,
+        target: SchemaVersion,
+        cf_options: ColumnFamilyOptions,
+    ) -> Result<SchemaVersion, StorageInitError> {
+        while version < target {
+            let migration = self
+                .get(version)
+                .ok_or(StorageInitError::MissingMigration(version))?;
+            info!(
+                "Upgrade db schema from version {} to {}: {}",
+                migration.from,
+                migration.to(),
+                migration.description
+            );
+            migration
+                .apply(db, cf_options)
+                .and_then(|_| write_schema_version(db, migration.to()))
+                .map_err(|cause| StorageInitError::MigrationFailed {
+                    from: migration.from,
+                    to: migration.to(),
+                    cause,
+                })?;
+            version = migration.to();
+        }
+        Ok(version)
+    }
+}
+
+/// The migrations of every schema version, in order.
+pub fn default_migrations() -> Vec<Migration> {
+    vec![
+        Migration::new(
+            LEGACY_SCHEMA_VERSION,
+            "record schema version",
+            vec![
+                ACCUMULATOR_NODE_PREFIX_NAME,
+                BLOCK_PREFIX_NAME,
+                BLOCK_HEADER_PREFIX_NAME,
+                BLOCK_SONS_PREFIX_NAME,
+                BLOCK_BODY_PREFIX_NAME,
+                BLOCK_NUM_PREFIX_NAME,
+                BLOCK_INFO_PREFIX_NAME,
+                BLOCK_TRANSACTIONS_PREFIX_NAME,
+                BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+                STATE_NODE_PREFIX_NAME,
+                STARTUP_INFO_PREFIX_NAME,
+                TRANSACTION_PREFIX_NAME,
+                TRANSACTION_INFO_PREFIX_NAME,
+            ]
+            .into_iter()
+            .map(MigrationStep::AddColumnFamily)
+            .collect(),
+        ),
+        Migration::new(
+            1,
+            "add contract event index",
+            vec![
+                MigrationStep::AddColumnFamily(CONTRACT_EVENT_PREFIX_NAME),
+                MigrationStep::AddColumnFamily(BLOCK_EVENT_PREFIX_NAME),
+                MigrationStep::Backfill {
+                    cf: CONTRACT_EVENT_PREFIX_NAME,
+                    backfiller: backfill_contract_events,
+                },
+            ],
+        ),
+        Migration::new(
+            2,
+            "add account transaction index",
+            vec![
+                MigrationStep::AddColumnFamily(ACCOUNT_TRANSACTION_PREFIX_NAME),
+                MigrationStep::AddColumnFamily(ACCOUNT_TRANSACTION_COUNT_PREFIX_NAME),
+                MigrationStep::Backfill {
+                    cf: ACCOUNT_TRANSACTION_PREFIX_NAME,
+                    backfiller: backfill_account_transactions,
+                },
+            ],
+        ),
+        // state nodes written before this version have no reference count, they are pinned
+        // by the state node store and never pruned, so nothing to backfill.
+        Migration::new(
+            3,
+            "add state node reference count",
+            vec![
+                MigrationStep::AddColumnFamily(STATE_NODE_REF_PREFIX_NAME),
+                MigrationStep::AddColumnFamily(STALE_STATE_NODE_PREFIX_NAME),
+                MigrationStep::AddColumnFamily(NEW_STATE_NODE_PREFIX_NAME),
+            ],
+        ),
+    ]
+}
+
+impl Default for MigrationRegistry {
+    fn default() -> Self {
+        Self::new(default_migrations())
+    }
+}
+
+pub fn read_schema_version(db: &DB) -> Result<Option<SchemaVersion>> {
+    let cf_handle = db
+        .cf_handle(DEFAULT_PREFIX_NAME)
+        .ok_or_else(|| format_err!("DB::cf_handle not found for default column family"))?;
+    match db.get_cf(cf_handle, SCHEMA_VERSION_KEY)? {
+        Some(data) => {
+            if data.len() != std::mem::size_of::<SchemaVersion>() {
+                return Err(StorageInitError::InvalidSchemaVersion(data).into());
+            }
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(data.as_slice());
+            Ok(Some(SchemaVersion::from_be_bytes(bytes)))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn write_schema_version(db: &DB, version: SchemaVersion) -> Result<()> {
+    let cf_handle = db
+        .cf_handle(DEFAULT_PREFIX_NAME)
+        .ok_or_else(|| format_err!("DB::cf_handle not found for default column family"))?;
+    db.put_cf_opt(
+        cf_handle,
+        SCHEMA_VERSION_KEY,
+        &version.to_be_bytes(),
+        &sync_write_options(),
+    )?;
+    Ok(())
+}
+
+fn sync_write_options() -> WriteOptions {
+    let mut opts = WriteOptions::new();
+    opts.set_sync(true);
+    opts
+}
+
+fn add_cf(db: &mut DB, cf: &str, cf_options: ColumnFamilyOptions) -> Result<()> {
+    if db.cf_handle(cf).is_none() {
+        db.create_cf(cf, &cf_options(cf))?;
+    }
+    Ok(())
+}
+
+/// Rewrite all rows of `from` with `rewriter` into `to`, `from` and `to` can be the same cf.
+fn rewrite_cf<F>(db: &DB, from: &str, to: &str, rewriter: F) -> Result<()>
+where
+    F: Fn(&[u8], &[u8]) -> Result<Option<(Vec<u8>, Vec<u8>)>>,
+{
+    let from_handle = db
+        .cf_handle(from)
+        .ok_or_else(|| format_err!("DB::cf_handle not found for column family name: {}", from))?;
+    let to_handle = db
+        .cf_handle(to)
+        .ok_or_else(|| format_err!("DB::cf_handle not found for column family name: {}", to))?;
+    let same_cf = from == to;
+    let mut batch = DBWriteBatch::default();
+    let mut batch_len = 0usize;
+    let mut total = 0u64;
+    for (key, value) in db.iterator_cf(from_handle, IteratorMode::Start) {
+        match rewriter(&key, &value)? {
+            Some((new_key, new_value)) => {
+                if same_cf && new_key.as_slice() != &*key {
+                    batch.delete_cf(to_handle, &key);
+                }
+                batch.put_cf(to_handle, new_key, new_value);
+            }
+            None => {
+                if same_cf {
+                    batch.delete_cf(to_handle, &key);
+                }
+            }
+        }
+        batch_len += 1;
+        total += 1;
+        if batch_len >= MIGRATION_BATCH_SIZE {
+            db.write_opt(batch, &sync_write_options())?;
+            batch = DBWriteBatch::default();
+            batch_len = 0;
+            info!(
+                "Migrate column family {} -> {}: {} rows done",
+                from, to, total
+            );
+        }
+    }
+    if batch_len > 0 {
+        db.write_opt(batch, &sync_write_options())?;
+    }
+    info!(
+        "Migrate column family {} -> {} finished, {} rows",
+        from, to, total
+    );
+    Ok(())
+}
+
+fn cf_handle<'a>(db: &'a DB, cf: &str) -> Result<&'a rocksdb::ColumnFamily> {
+    db.cf_handle(cf)
+        .ok_or_else(|| format_err!("DB::cf_handle not found for column family name: {}", cf))
+}
+
+/// Get the decoded value of `key` in column family `cf`, for backfillers.
+pub(crate) fn get_cf_value<K, V>(db: &DB, cf: &str, key: &K) -> Result<Option<V>>
+where
+    K: KeyCodec,
+    V: ValueCodec,
+{
+    db.get_cf(cf_handle(db, cf)?, key.encode_key()?)?
+        .map(|value| V::decode_value(value.as_slice()))
+        .transpose()
+}
+
+/// Ids of the master blocks from genesis to head, return empty if the db has no startup info.
+pub(crate) fn master_block_ids(db: &DB) -> Result<Vec<HashValue>> {
+    let startup_info = match db.get_cf(
+        cf_handle(db, STARTUP_INFO_PREFIX_NAME)?,
+        STARTUP_INFO_PREFIX_NAME.as_bytes(),
+    )? {
+        Some(bytes) => StartupInfo::try_from(bytes)?,
+        None => return Ok(vec![]),
+    };
+    let mut block_ids = vec![];
+    let mut block_id = startup_info.master;
+    loop {
+        let header: BlockHeader = get_cf_value(db, BLOCK_HEADER_PREFIX_NAME, &block_id)?
+            .ok_or_else(|| format_err!("Can not find block header {:?}", block_id))?;
+        block_ids.push(block_id);
+        if header.number() == 0 {
+            break;
+        }
+        block_id = header.parent_hash();
+    }
+    block_ids.reverse();
+    Ok(block_ids)
+}
+
+/// Writes of a backfiller, flushed every `MIGRATION_BATCH_SIZE` rows.
+pub(crate) struct MigrationWriter<'a> {
+    db: &'a DB,
+    batch: DBWriteBatch,
+    batch_len: usize,
+    total: u64,
+}
+
+impl<'a> MigrationWriter<'a> {
+    pub fn new(db: &'a DB) -> Self {
+        Self {
+            db,
+            batch: DBWriteBatch::default(),
+            batch_len: 0,
+            total: 0,
+        }
+    }
+
+    pub fn put<K, V>(&mut self, cf: &str, key: &K, value: &V) -> Result<()>
+    where
+        K: KeyCodec,
+        V: ValueCodec,
+    {
+        self.batch.put_cf(
+            cf_handle(self.db, cf)?,
+            key.encode_key()?,
+            value.encode_value()?,
+        );
+        self.batch_len += 1;
+        self.total += 1;
+        if self.batch_len >= MIGRATION_BATCH_SIZE {
+            self.flush()?;
+            info!("Backfill: {} rows done", self.total);
+        }
+        Ok(())
+    }
+
+    /// Write the buffered rows, they are visible to reads of the db after this.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.batch_len > 0 {
+            let batch = std::mem::take(&mut self.batch);
+            self.db.write_opt(batch, &sync_write_options())?;
+            self.batch_len = 0;
+        }
+        Ok(())
+    }
+}
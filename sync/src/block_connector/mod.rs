@@ -4,7 +4,7 @@ use logger::prelude::*;
 use parking_lot::RwLock;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use traits::{is_ok, ChainAsyncService, ConnectBlockError, Consensus};
+use traits::{is_ok, BlockVerifyError, ChainAsyncService, ConnectBlockError, Consensus};
 use types::block::{Block, BlockInfo, BlockNumber};
 
 struct FutureBlockPool {
@@ -114,18 +114,32 @@ where
         *self.pivot.read()
     }
 
-    pub async fn do_block_and_child(&self, block: Block, block_info: Option<BlockInfo>) {
+    /// Connect `block` and its future children, return the verify error if `block` is invalid.
+    pub async fn do_block_and_child(
+        &self,
+        block: Block,
+        block_info: Option<BlockInfo>,
+    ) -> Option<BlockVerifyError> {
         let block_id = block.header().id();
-        if self.do_block_connect(block, block_info).await {
-            if let Some(child) = self.future_blocks.take_child(&block_id) {
-                for (son_block, son_block_info) in child {
-                    let _ = self.do_block_connect(son_block, son_block_info).await;
+        match self.do_block_connect(block, block_info).await {
+            Ok(true) => {
+                if let Some(child) = self.future_blocks.take_child(&block_id) {
+                    for (son_block, son_block_info) in child {
+                        let _ = self.do_block_connect(son_block, son_block_info).await;
+                    }
                 }
+                None
             }
+            Ok(false) => None,
+            Err(verify_err) => Some(verify_err),
         }
     }
 
-    async fn do_block_connect(&self, block: Block, block_info: Option<BlockInfo>) -> bool {
+    async fn do_block_connect(
+        &self,
+        block: Block,
+        block_info: Option<BlockInfo>,
+    ) -> Result<bool, BlockVerifyError> {
         let pivot = self.get_pivot();
         let connect_result = if pivot.is_none() {
             self.chain_reader.clone().try_connect(block.clone()).await
@@ -139,7 +153,7 @@ where
                             .try_connect_with_block_info(block.clone(), info)
                             .await
                     }
-                    None => return false,
+                    None => return Ok(false),
                 }
             } else {
                 self.chain_reader.clone().try_connect(block.clone()).await
@@ -150,14 +164,15 @@ where
         match connect_result {
             Ok(connect) => {
                 if is_ok(&connect) {
-                    return true;
+                    return Ok(true);
                 } else if let Err(err) = connect {
                     match err {
                         ConnectBlockError::FutureBlock => {
                             self.future_blocks.add_future_block(block, block_info)
                         }
-                        ConnectBlockError::VerifyFailed => {
-                            error!("Connect block {:?} verify failed.", block_id)
+                        ConnectBlockError::VerifyFailed(verify_err) => {
+                            error!("Connect block {:?} verify failed: {}", block_id, verify_err);
+                            return Err(verify_err);
                         }
                         _ => debug!("Connect block {:?} failed, because : {:?}", block_id, err),
                     }
@@ -166,6 +181,6 @@ where
             Err(e) => error!("Connect block {:?} failed : {:?}", block_id, e),
         }
 
-        false
+        Ok(false)
     }
 }
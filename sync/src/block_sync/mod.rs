@@ -222,6 +222,7 @@ where
 
         let next = self.next.0;
         let network = self.network.clone();
+        let downloader = self.downloader.clone();
         Arbiter::spawn(async move {
             // sync header
             if sync_header_flag {
@@ -231,7 +232,10 @@ where
                     .with_label_values(&[LABEL_HASH])
                     .start_timer();
                 let event = match get_headers(&network, get_headers_req).await {
-                    Ok(headers) => SyncDataEvent::new_header_event(headers),
+                    // check pow of the batch in parallel before the blocks are executed one by one.
+                    Ok(headers) => SyncDataEvent::new_header_event(
+                        downloader.verify_headers_pow(headers).await,
+                    ),
                     Err(e) => {
                         error!("Sync headers err: {:?}", e);
                         Delay::new(Duration::from_secs(1)).await;
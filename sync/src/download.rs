@@ -12,18 +12,19 @@ use bus::{Broadcast, BusActor, Subscription};
 use chain::ChainActorRef;
 use config::NodeConfig;
 use crypto::HashValue;
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use futures_timer::Delay;
 use logger::prelude::*;
 use network::NetworkAsyncService;
-use network_api::NetworkService;
+use network_api::{NetworkService, ReputationChange};
+use rayon::prelude::*;
 use starcoin_storage::Store;
 use starcoin_sync_api::{BlockBody, GetBlockHeaders, SyncNotify};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use traits::ChainAsyncService;
 use traits::Consensus;
+use traits::{BlockVerifyError, ChainAsyncService};
 use types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState},
     peer_info::PeerId,
@@ -37,6 +38,8 @@ pub enum SyncEvent {
 }
 
 const _MIN_PEER_SIZE: usize = 5;
+/// Reputation change when a peer sends a block which fails verification.
+const BAD_BLOCK: ReputationChange = ReputationChange::new(-(1 << 12), "Bad block");
 
 pub struct DownloadActor<C>
 where
@@ -72,7 +75,7 @@ where
             let (sync_event_sender, sync_event_receiver) = mpsc::channel(100);
             ctx.add_message_stream(sync_event_receiver);
             DownloadActor {
-                downloader: Arc::new(Downloader::new(chain_reader)),
+                downloader: Arc::new(Downloader::new(node_config.clone(), chain_reader)),
                 self_peer_id: peer_id,
                 network,
                 bus,
@@ -207,7 +210,9 @@ where
                 self.sync_task.activate_tasks();
                 debug!("new peer: {:?}", peer_id);
             }
-            SyncNotify::NewHeadBlock(_peer_id, block) => self.do_block_and_child(*block, None),
+            SyncNotify::NewHeadBlock(peer_id, block) => {
+                self.do_block_and_child(*block, None, Some(peer_id))
+            }
             SyncNotify::ClosePeerMsg(peer_id) => {
                 debug!("close peer: {:?}", peer_id);
             }
@@ -459,10 +464,26 @@ where
         }
     }
 
-    pub fn do_block_and_child(&self, block: Block, block_info: Option<BlockInfo>) {
+    /// Connect the block and its children, and penalise `peer_id` if the block is invalid.
+    pub fn do_block_and_child(
+        &self,
+        block: Block,
+        block_info: Option<BlockInfo>,
+        peer_id: Option<PeerId>,
+    ) {
         let downloader = self.downloader.clone();
+        let network = self.network.clone();
         Arbiter::spawn(async move {
-            downloader.connect_block_and_child(block, block_info).await;
+            let block_id = block.id();
+            if let Some(verify_err) = downloader.connect_block_and_child(block, block_info).await {
+                if let Some(peer_id) = peer_id {
+                    warn!(
+                        "Peer {:?} sent invalid block {:?}: {}",
+                        peer_id, block_id, verify_err
+                    );
+                    network.report_peer(peer_id.into(), BAD_BLOCK);
+                }
+            }
         });
     }
 }
@@ -472,6 +493,7 @@ pub struct Downloader<C>
 where
     C: Consensus + Sync + Send + 'static + Clone,
 {
+    config: Arc<NodeConfig>,
     chain_reader: ChainActorRef<C>,
     block_connector: BlockConnector<C>,
 }
@@ -479,12 +501,48 @@ where
 const MIN_BLOCKS_BEHIND: u64 = 10;
 const MAIN_MIN_BLOCKS_BEHIND: u64 = 100;
 
+/// Check the proof of work of `headers` in parallel on the rayon thread pool, and return the
+/// headers before the first invalid one. The verified headers are cached by the consensus,
+/// so applying them later does not compute the proof of work again.
+pub async fn verify_headers_pow<C>(
+    config: Arc<NodeConfig>,
+    headers: Vec<BlockHeader>,
+) -> Vec<BlockHeader>
+where
+    C: Consensus + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    rayon::spawn(move || {
+        let invalid = headers
+            .par_iter()
+            .position_first(|header| C::verify_pow(config.clone(), header).is_err());
+        let _ = sender.send((headers, invalid));
+    });
+    match receiver.await {
+        Ok((mut headers, Some(invalid))) => {
+            warn!(
+                "Verify pow of header {:?} failed, drop it and {} headers after it.",
+                headers[invalid].id(),
+                headers.len() - invalid - 1
+            );
+            headers.truncate(invalid);
+            headers
+        }
+        Ok((headers, None)) => headers,
+        Err(e) => {
+            error!("Verify pow of headers failed: {:?}", e);
+            Vec::new()
+        }
+    }
+}
+
 impl<C> Downloader<C>
 where
     C: Consensus + Sync + Send + 'static + Clone,
 {
-    pub fn new(chain_reader: ChainActorRef<C>) -> Self {
+    pub fn new(config: Arc<NodeConfig>, chain_reader: ChainActorRef<C>) -> Self {
         Downloader {
+            config,
             block_connector: BlockConnector::new(chain_reader.clone()),
             chain_reader,
         }
@@ -599,6 +657,11 @@ where
         }
     }
 
+    /// See `verify_headers_pow`.
+    pub async fn verify_headers_pow(&self, headers: Vec<BlockHeader>) -> Vec<BlockHeader> {
+        verify_headers_pow::<C>(self.config.clone(), headers).await
+    }
+
     pub async fn do_blocks(
         &self,
         headers: Vec<BlockHeader>,
@@ -620,10 +683,14 @@ where
         }
     }
 
-    pub async fn connect_block_and_child(&self, block: Block, block_info: Option<BlockInfo>) {
+    pub async fn connect_block_and_child(
+        &self,
+        block: Block,
+        block_info: Option<BlockInfo>,
+    ) -> Option<BlockVerifyError> {
         self.block_connector
             .do_block_and_child(block, block_info)
-            .await;
+            .await
     }
 
     fn set_pivot(&self, pivot: Option<BlockNumber>) {
@@ -8,7 +8,7 @@ mod sync;
 mod sync_metrics;
 mod sync_task;
 mod txn_sync;
-pub use download::Downloader;
+pub use download::{verify_headers_pow, Downloader};
 pub use process::ProcessActor;
 use std::time::Duration;
 pub use sync::SyncActor;
@@ -53,6 +53,7 @@ fn test_network_actor_rpc() {
             let best_block_id = *startup_info_1.get_master();
             TxPool::start(
                 node_config_1.tx_pool.clone(),
+                node_config_1.time_service(),
                 storage_1.clone(),
                 best_block_id,
                 bus_1.clone(),
@@ -154,6 +155,7 @@ fn test_network_actor_rpc() {
             let best_block_id = *startup_info_2.get_master();
             TxPool::start(
                 node_config_2.tx_pool.clone(),
+                node_config_2.time_service(),
                 storage_2.clone(),
                 best_block_id,
                 bus_2.clone(),
@@ -258,6 +260,7 @@ fn test_network_actor_rpc_2() {
             let best_block_id = *startup_info_1.get_master();
             TxPool::start(
                 node_config_1.tx_pool.clone(),
+                node_config_1.time_service(),
                 storage_1.clone(),
                 best_block_id,
                 bus_1.clone(),
@@ -337,6 +340,7 @@ fn test_network_actor_rpc_2() {
             let best_block_id = *startup_info_2.get_master();
             TxPool::start(
                 node_config_2.tx_pool.clone(),
+                node_config_2.time_service(),
                 storage_2.clone(),
                 best_block_id,
                 bus_2.clone(),
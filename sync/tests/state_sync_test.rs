@@ -56,6 +56,7 @@ fn test_state_sync() {
             let best_block_id = *startup_info_1.get_master();
             TxPool::start(
                 node_config_1.tx_pool.clone(),
+                node_config_1.time_service(),
                 storage_1.clone(),
                 best_block_id,
                 bus_1.clone(),
@@ -164,6 +165,7 @@ fn test_state_sync() {
             let best_block_id = *startup_info_2.get_master();
             TxPool::start(
                 node_config_2.tx_pool.clone(),
+                node_config_2.time_service(),
                 storage_2.clone(),
                 best_block_id,
                 bus_2.clone(),
@@ -55,6 +55,7 @@ fn test_txn_sync_actor() {
             let best_block_id = *startup_info_1.get_master();
             TxPool::start(
                 node_config_1.tx_pool.clone(),
+                node_config_1.time_service(),
                 storage_1.clone(),
                 best_block_id,
                 bus_1.clone(),
@@ -129,6 +130,7 @@ fn test_txn_sync_actor() {
             let best_block_id = *startup_info_2.get_master();
             TxPool::start(
                 node_config_2.tx_pool.clone(),
+                node_config_2.time_service(),
                 storage_2.clone(),
                 best_block_id,
                 bus_2.clone(),
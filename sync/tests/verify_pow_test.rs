@@ -0,0 +1,51 @@
+use config::NodeConfig;
+use consensus::argon::ArgonConsensus;
+use starcoin_sync::verify_headers_pow;
+use std::sync::Arc;
+use traits::Consensus;
+use types::block::BlockHeader;
+
+fn mined_header(config: &NodeConfig) -> BlockHeader {
+    let mut header = BlockHeader::random();
+    header.difficulty = 8.into();
+    let pow_header = header.pow_header(config.pow_version(header.number()));
+    header.consensus_header =
+        ArgonConsensus::solve_consensus_header(&pow_header, header.difficulty()).into();
+    header
+}
+
+/// Replace the nonce of `header` with one which does not solve it.
+fn make_invalid(config: Arc<NodeConfig>, header: &mut BlockHeader) {
+    let mut nonce = 0u64;
+    loop {
+        header.consensus_header = nonce.to_le_bytes().to_vec();
+        if ArgonConsensus::verify_pow(config.clone(), header).is_err() {
+            break;
+        }
+        nonce += 1;
+    }
+}
+
+#[stest::test]
+async fn test_verify_headers_pow() {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let headers: Vec<BlockHeader> = (0..8).map(|_| mined_header(&config)).collect();
+    assert_eq!(
+        verify_headers_pow::<ArgonConsensus>(config.clone(), headers.clone()).await,
+        headers
+    );
+
+    // truncate at the first invalid header, the valid headers after it are dropped too.
+    let mut headers = headers;
+    make_invalid(config.clone(), &mut headers[3]);
+    make_invalid(config.clone(), &mut headers[6]);
+    assert_eq!(
+        verify_headers_pow::<ArgonConsensus>(config.clone(), headers.clone()).await,
+        headers[..3].to_vec()
+    );
+
+    make_invalid(config.clone(), &mut headers[0]);
+    assert!(verify_headers_pow::<ArgonConsensus>(config, headers)
+        .await
+        .is_empty());
+}
@@ -5,7 +5,10 @@ use anyhow::Result;
 use futures_channel::mpsc;
 use starcoin_crypto::hash::HashValue;
 use starcoin_types::{
-    account_address::AccountAddress, block::Block, transaction, transaction::SignedUserTransaction,
+    account_address::AccountAddress,
+    block::Block,
+    transaction,
+    transaction::{SignedUserTransaction, TransactionOutput},
 };
 use std::sync::Arc;
 
@@ -35,4 +38,21 @@ pub trait TxPoolSyncService: Clone + Send + Sync + Unpin {
 
     /// rollback
     fn rollback(&self, enacted: Vec<Block>, retracted: Vec<Block>) -> Result<()>;
+
+    /// Execute the txn against the state of current chain header, the output is not committed.
+    /// Skip the signature check if `check_signature` is false.
+    fn dry_run(&self, txn: SignedUserTransaction, check_signature: bool) -> TransactionOutput;
+
+    /// Same as `dry_run`, but if the sender can not pay the max gas amount of the txn by its
+    /// balance, the max gas amount is lowered to the affordable one and the signature is not
+    /// checked, as the txn is changed.
+    fn dry_run_affordable(
+        &self,
+        txn: SignedUserTransaction,
+        check_signature: bool,
+    ) -> Result<TransactionOutput>;
+
+    /// Suggest a gas price from the txns of recent blocks, it is not lower than the minimal gas
+    /// price of txpool.
+    fn gas_price(&self) -> Result<u64>;
 }
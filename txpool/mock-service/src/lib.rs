@@ -10,7 +10,10 @@ use std::{
     sync::{Arc, Mutex},
 };
 use types::{
-    account_address::AccountAddress, block::Block, transaction, transaction::SignedUserTransaction,
+    account_address::AccountAddress,
+    block::Block,
+    transaction,
+    transaction::{SignedUserTransaction, TransactionOutput},
 };
 
 #[derive(Clone, Default)]
@@ -82,6 +85,22 @@ impl TxPoolSyncService for MockTxPoolService {
     fn rollback(&self, _enacted: Vec<Block>, _retracted: Vec<Block>) -> Result<()> {
         todo!()
     }
+
+    fn dry_run(&self, _txn: SignedUserTransaction, _check_signature: bool) -> TransactionOutput {
+        todo!()
+    }
+
+    fn dry_run_affordable(
+        &self,
+        _txn: SignedUserTransaction,
+        _check_signature: bool,
+    ) -> Result<TransactionOutput> {
+        todo!()
+    }
+
+    fn gas_price(&self) -> Result<u64> {
+        todo!()
+    }
 }
 
 #[cfg(test)]
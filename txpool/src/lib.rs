@@ -14,6 +14,7 @@ use actix::prelude::*;
 use common_crypto::hash::HashValue;
 use starcoin_bus::{Bus, BusActor};
 use starcoin_config::TxPoolConfig;
+use starcoin_time_service::TimeService;
 use starcoin_txpool_api::TxnStatusFullEvent;
 use std::{fmt::Debug, sync::Arc};
 use storage::Store;
@@ -43,6 +44,7 @@ impl TxPool {
     // TODO: use static dispatching instead of dynamic dispatch for storage instance.
     pub fn start(
         pool_config: TxPoolConfig,
+        time_service: Arc<dyn TimeService>,
         storage: Arc<dyn Store>,
         best_block_hash: HashValue,
         bus: actix::Addr<BusActor>,
@@ -56,7 +58,7 @@ impl TxPool {
             Ok(Some(block)) => block,
         };
         let best_block_header = best_block.into_inner().0;
-        let service = TxPoolService::new(pool_config, storage, best_block_header);
+        let service = TxPoolService::new(pool_config, storage, best_block_header, time_service);
         let inner = service.get_inner();
         let pool = TxPoolActor::new(inner.clone(), bus);
         let pool_addr = pool.start();
@@ -72,7 +74,12 @@ impl TxPool {
         best_block_header: types::block::BlockHeader,
         bus: actix::Addr<BusActor>,
     ) -> Self {
-        let service = TxPoolService::new(TxPoolConfig::default(), storage, best_block_header);
+        let service = TxPoolService::new(
+            TxPoolConfig::default(),
+            storage,
+            best_block_header,
+            Arc::new(starcoin_time_service::RealTimeService::new()),
+        );
         let inner = service.get_inner();
         let pool = TxPoolActor::new(inner.clone(), bus);
         let pool_addr = pool.start();
@@ -422,8 +422,8 @@ impl TransactionQueue {
         (pending_readiness, state_readiness)
     }
 
-    /// Culls all stalled transactions from the pool.
-    pub fn cull<C: client::AccountSeqNumberClient>(&self, client: C) {
+    /// Culls all stalled and expired transactions from the pool, `now` is the current time in seconds.
+    pub fn cull<C: client::AccountSeqNumberClient>(&self, client: C, now: u64) {
         trace_time!("pool::cull");
         // We don't care about future transactions, so nonce_cap is not important.
         let nonce_cap = None;
@@ -448,8 +448,13 @@ impl TransactionQueue {
         };
         for chunk in senders.chunks(CULL_SENDERS_CHUNK) {
             trace_time!("pool::cull::chunk");
+            // block number conditions are not checked, only expiration is.
+            let pending_readiness = ready::Condition::new(u64::max_value(), now);
             let state_readiness = ready::State::new(client.clone(), stale_id, nonce_cap);
-            removed += self.pool.write().cull(Some(chunk), state_readiness);
+            removed += self
+                .pool
+                .write()
+                .cull(Some(chunk), (pending_readiness, state_readiness));
         }
         debug!(target: "txqueue", "Removed {} stalled transactions. {}", removed, self.status());
     }
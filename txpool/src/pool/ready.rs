@@ -89,6 +89,7 @@ impl<C: AccountSeqNumberClient> tx_pool::Ready<VerifiedTransaction> for State<C>
 }
 
 /// Checks readines of Pending transactions by comparing it with current time and block number.
+/// Transactions expired at the current time are stale.
 #[derive(Debug)]
 pub struct Condition {
     block_number: u64,
@@ -104,6 +105,9 @@ impl Condition {
 
 impl tx_pool::Ready<VerifiedTransaction> for Condition {
     fn is_ready(&mut self, tx: &VerifiedTransaction) -> tx_pool::Readiness {
+        if tx.signed().expiration_time().as_secs() <= self.now {
+            return tx_pool::Readiness::Stale;
+        }
         match tx.transaction.condition {
             Some(transaction::Condition::Number(block)) if block > self.block_number => {
                 tx_pool::Readiness::Future
@@ -108,6 +108,8 @@ impl AccountSeqNumberClient for CachedSeqNumberClient {
 pub struct PoolClient {
     best_block_header: BlockHeader,
     nonce_client: CachedSeqNumberClient,
+    /// Current time in seconds, expired transactions are rejected.
+    now: u64,
 }
 
 impl std::fmt::Debug for PoolClient {
@@ -117,13 +119,19 @@ impl std::fmt::Debug for PoolClient {
 }
 
 impl PoolClient {
-    pub fn new(best_block_header: BlockHeader, storage: Arc<dyn Store>, cache: NonceCache) -> Self {
+    pub fn new(
+        best_block_header: BlockHeader,
+        storage: Arc<dyn Store>,
+        cache: NonceCache,
+        now: u64,
+    ) -> Self {
         let root_hash = best_block_header.state_root();
         let statedb = ChainStateDB::new(storage.into_super_arc(), Some(root_hash));
         let nonce_client = CachedSeqNumberClient::new(statedb, cache);
         Self {
             best_block_header,
             nonce_client,
+            now,
         }
     }
 }
@@ -140,6 +148,13 @@ impl crate::pool::Client for PoolClient {
         tx: UnverifiedUserTransaction,
     ) -> Result<transaction::SignatureCheckedTransaction, transaction::TransactionError> {
         let txn = SignedUserTransaction::from(tx);
+        let expiration = txn.expiration_time().as_secs();
+        if expiration <= self.now {
+            return Err(TransactionError::Expired {
+                expiration,
+                now: self.now,
+            });
+        }
         let checked_txn = txn
             .clone()
             .check_signature()
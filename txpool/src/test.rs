@@ -1,18 +1,26 @@
 use super::test_helper;
 use crate::pool::AccountSeqNumberClient;
+use crate::tx_pool_service_impl::cap_max_gas_amount;
 use anyhow::Result;
-use common_crypto::{hash::PlainCryptoHash, keygen::KeyGen};
+use common_crypto::{ed25519::Ed25519Signature, hash::PlainCryptoHash, keygen::KeyGen};
 use parking_lot::RwLock;
 use starcoin_open_block::OpenedBlock;
 use starcoin_state_api::ChainStateWriter;
 use starcoin_statedb::ChainStateDB;
+use starcoin_time_service::{MockTimeService, RealTimeService, TimeService};
 use starcoin_txpool_api::TxPoolSyncService;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
 use storage::BlockStore;
 use types::{
     account_address::{self, AccountAddress},
     account_config,
-    transaction::{authenticator::AuthenticationKey, SignedUserTransaction, Transaction},
+    block::{Block, BlockHeader, BlockState},
+    transaction::{
+        authenticator::{AuthenticationKey, TransactionAuthenticator},
+        RawUserTransaction, Script, SignedUserTransaction, Transaction, TransactionPayload,
+        TransactionStatus,
+    },
+    vm_error::{StatusCode, VMStatus},
     U256,
 };
 
@@ -64,6 +72,165 @@ async fn test_tx_pool() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+async fn test_txn_expired() -> Result<()> {
+    let time_service = Arc::new(MockTimeService::new_with_real_time());
+    let (pool, _storage) = test_helper::start_txpool_with_time_service(time_service.clone());
+    let txpool_service = pool.get_service();
+    let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    let account_address = account_address::from_public_key(&public_key);
+    let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+    let txn =
+        starcoin_executor::build_transfer_from_association(account_address, auth_prefix, 0, 10000);
+    let txn = txn.as_signed_user_txn()?.clone();
+    let mut result = txpool_service.add_txns(vec![txn.clone()]);
+    assert!(result.pop().unwrap().is_ok());
+    assert_eq!(txpool_service.get_pending_txns(Some(10)).len(), 1);
+
+    time_service.set(txn.expiration_time().as_secs() * 1000);
+    assert_eq!(txpool_service.get_pending_txns(Some(10)).len(), 0);
+    let mut result = txpool_service.add_txns(vec![txn]);
+    assert!(result.pop().unwrap().is_err());
+    Ok(())
+}
+
+#[stest::test]
+async fn test_dry_run() -> Result<()> {
+    let (pool, _storage) = test_helper::start_txpool();
+    let txpool_service = pool.get_service();
+    let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    let account_address = account_address::from_public_key(&public_key);
+    let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+    let txn =
+        starcoin_executor::build_transfer_from_association(account_address, auth_prefix, 0, 10000);
+    let txn = txn.as_signed_user_txn()?.clone();
+
+    let output = txpool_service.dry_run(txn.clone(), true);
+    assert_eq!(
+        output.status().vm_status().major_status,
+        StatusCode::EXECUTED
+    );
+    assert!(output.gas_used() > 0);
+    assert!(output.write_set().iter().next().is_some());
+    // nothing is committed.
+    assert!(txpool_service.get_pending_txns(None).is_empty());
+    assert_eq!(txpool_service.dry_run(txn.clone(), true), output);
+
+    let sender_public_key = match txn.authenticator() {
+        TransactionAuthenticator::Ed25519 { public_key, .. } => public_key,
+        TransactionAuthenticator::MultiEd25519 { .. } => unreachable!(),
+    };
+    let unsigned_txn = SignedUserTransaction::new(
+        txn.raw_txn().clone(),
+        sender_public_key,
+        Ed25519Signature::try_from(&[0u8; 64][..])?,
+    );
+    let output = txpool_service.dry_run(unsigned_txn.clone(), true);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+    );
+    let output = txpool_service.dry_run(unsigned_txn, false);
+    assert_eq!(
+        output.status().vm_status().major_status,
+        StatusCode::EXECUTED
+    );
+    Ok(())
+}
+
+#[stest::test]
+async fn test_dry_run_affordable() -> Result<()> {
+    let (pool, _storage) = test_helper::start_txpool();
+    let txpool_service = pool.get_service();
+    let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    let account_address = account_address::from_public_key(&public_key);
+    let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+    let txn =
+        starcoin_executor::build_transfer_from_association(account_address, auth_prefix, 0, 10000);
+    let txn = txn.as_signed_user_txn()?.clone();
+    // the association can pay the txn, so it is not changed.
+    assert_eq!(
+        txpool_service.dry_run_affordable(txn.clone(), true)?,
+        txpool_service.dry_run(txn, true)
+    );
+    Ok(())
+}
+
+fn txn_with_gas(max_gas_amount: u64, gas_price: u64) -> SignedUserTransaction {
+    let (private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    RawUserTransaction::new(
+        account_address::from_public_key(&public_key),
+        0,
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        max_gas_amount,
+        gas_price,
+        Duration::from_secs(0),
+    )
+    .sign(&private_key, public_key)
+    .unwrap()
+    .into_inner()
+}
+
+#[test]
+fn test_cap_max_gas_amount() {
+    let txn = txn_with_gas(1000, 2);
+    assert!(cap_max_gas_amount(&txn, 2000).is_none());
+    assert!(cap_max_gas_amount(&txn, 10000).is_none());
+
+    let capped_txn = cap_max_gas_amount(&txn, 1999).unwrap();
+    assert_eq!(capped_txn.max_gas_amount(), 999);
+    assert_eq!(capped_txn.gas_unit_price(), txn.gas_unit_price());
+    assert_eq!(capped_txn.sender(), txn.sender());
+    assert_eq!(capped_txn.sequence_number(), txn.sequence_number());
+    assert_eq!(capped_txn.payload(), txn.payload());
+    assert_eq!(capped_txn.authenticator(), txn.authenticator());
+
+    assert_eq!(cap_max_gas_amount(&txn, 0).unwrap().max_gas_amount(), 0);
+    // a txn with zero gas price is always affordable.
+    assert!(cap_max_gas_amount(&txn_with_gas(1000, 0), 0).is_none());
+}
+
+#[stest::test]
+async fn test_gas_price() -> Result<()> {
+    let (pool, storage) = test_helper::start_txpool();
+    let txpool_service = pool.get_service();
+    // no txn in the genesis block.
+    assert_eq!(txpool_service.gas_price()?, 1);
+
+    let mut parent = storage
+        .get_block_header_by_hash(storage.get_startup_info()?.unwrap().master)?
+        .unwrap();
+    let mut append_block = |gas_prices: Vec<u64>| -> Result<()> {
+        let mut header = BlockHeader::random();
+        header.parent_hash = parent.id();
+        header.number = parent.number() + 1;
+        let txns = gas_prices
+            .into_iter()
+            .map(|gas_price| txn_with_gas(0, gas_price))
+            .collect::<Vec<_>>();
+        storage.commit_block(Block::new(header.clone(), txns), BlockState::Executed)?;
+        txpool_service
+            .get_inner()
+            .notify_new_chain_header(header.clone());
+        parent = header;
+        Ok(())
+    };
+
+    // the txns of the oldest block are not sampled, as it is out of the recent 20 blocks.
+    append_block(vec![1000; 10])?;
+    for gas_price in 1..=20 {
+        append_block(vec![gas_price])?;
+    }
+    // the 60th percentile of gas price 1 to 20.
+    assert_eq!(txpool_service.gas_price()?, 12);
+
+    append_block(vec![])?;
+    append_block(vec![100, 200, 300, 400])?;
+    // gas price 3 to 20 and 100 to 400 are sampled.
+    assert_eq!(txpool_service.gas_price()?, 15);
+    Ok(())
+}
+
 #[stest::test]
 async fn test_subscribe_txns() {
     let (pool, _storage) = test_helper::start_txpool();
@@ -112,6 +279,7 @@ async fn test_rollback() -> Result<()> {
         let mut open_block = OpenedBlock::new(
             storage,
             block_header,
+            RealTimeService.now_secs(),
             u64::MAX,
             account_address,
             Some(auth_prefix),
@@ -2,10 +2,17 @@ use crate::TxPool;
 use starcoin_bus::BusActor;
 use starcoin_config::{NodeConfig, TxPoolConfig};
 use starcoin_genesis::Genesis;
+use starcoin_time_service::{RealTimeService, TimeService};
 use std::sync::Arc;
 use storage::{cache_storage::CacheStorage, storage::StorageInstance, Storage};
 
 pub fn start_txpool() -> (TxPool, Arc<Storage>) {
+    start_txpool_with_time_service(Arc::new(RealTimeService::new()))
+}
+
+pub fn start_txpool_with_time_service(
+    time_service: Arc<dyn TimeService>,
+) -> (TxPool, Arc<Storage>) {
     let cache_storage = CacheStorage::new();
     let storage =
         Arc::new(Storage::new(StorageInstance::new_cache_instance(cache_storage)).unwrap());
@@ -17,6 +24,7 @@ pub fn start_txpool() -> (TxPool, Arc<Storage>) {
 
     let pool = TxPool::start(
         TxPoolConfig::default(),
+        time_service,
         storage.clone(),
         *startup_info.get_master(),
         bus,
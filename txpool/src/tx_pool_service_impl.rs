@@ -16,6 +16,9 @@ use common_crypto::hash::{HashValue, PlainCryptoHash};
 use futures_channel::mpsc;
 use parking_lot::RwLock;
 use starcoin_config::TxPoolConfig;
+use starcoin_state_api::AccountStateReader;
+use starcoin_statedb::ChainStateDB;
+use starcoin_time_service::TimeService;
 use starcoin_txpool_api::TxPoolSyncService;
 use std::sync::Arc;
 use storage::Store;
@@ -23,7 +26,10 @@ use types::{
     account_address::AccountAddress,
     block::{Block, BlockHeader},
     transaction,
-    transaction::SignedUserTransaction,
+    transaction::{
+        authenticator::TransactionAuthenticator, RawUserTransaction, SignedUserTransaction,
+        TransactionOutput,
+    },
 };
 
 #[derive(Clone, Debug)]
@@ -35,6 +41,7 @@ impl TxPoolService {
         pool_config: TxPoolConfig,
         storage: Arc<dyn Store>,
         chain_header: BlockHeader,
+        time_service: Arc<dyn TimeService>,
     ) -> Self {
         let verifier_options = pool::VerifierOptions {
             minimal_gas_price: pool_config.minimal_gas_price,
@@ -57,6 +64,8 @@ impl TxPoolService {
             storage,
             chain_header: Arc::new(RwLock::new(chain_header)),
             sequence_number_cache: NonceCache::new(128),
+            time_service,
+            minimal_gas_price: pool_config.minimal_gas_price,
         };
 
         Self { inner }
@@ -132,6 +141,69 @@ impl TxPoolSyncService for TxPoolService {
             .start_timer();
         self.inner.chain_new_block(enacted, retracted)
     }
+
+    fn dry_run(&self, txn: SignedUserTransaction, check_signature: bool) -> TransactionOutput {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["dry_run"])
+            .start_timer();
+        self.inner.dry_run(txn, check_signature)
+    }
+
+    fn dry_run_affordable(
+        &self,
+        txn: SignedUserTransaction,
+        check_signature: bool,
+    ) -> Result<TransactionOutput> {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["dry_run_affordable"])
+            .start_timer();
+        self.inner.dry_run_affordable(txn, check_signature)
+    }
+
+    fn gas_price(&self) -> Result<u64> {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["gas_price"])
+            .start_timer();
+        self.inner.gas_price()
+    }
+}
+
+/// How many recent blocks are sampled to suggest the gas price.
+const GAS_PRICE_SAMPLE_BLOCKS: u64 = 20;
+/// The suggested gas price is the percentile of the gas prices in the sampled blocks.
+const GAS_PRICE_PERCENTILE: usize = 60;
+/// Suggested if there is no txn in the sampled blocks.
+const DEFAULT_GAS_PRICE: u64 = 1;
+
+/// Returns a copy of `txn` whose max gas amount is lowered to what `balance` can pay at its gas
+/// price, or `None` if the txn is affordable already. The signature is kept as it is, so it does
+/// not match the copy.
+pub(crate) fn cap_max_gas_amount(
+    txn: &SignedUserTransaction,
+    balance: u64,
+) -> Option<SignedUserTransaction> {
+    let gas_price = txn.gas_unit_price();
+    if gas_price == 0 || txn.max_gas_amount() <= balance / gas_price {
+        return None;
+    }
+    let raw_txn = RawUserTransaction::new(
+        txn.sender(),
+        txn.sequence_number(),
+        txn.payload().clone(),
+        balance / gas_price,
+        gas_price,
+        txn.expiration_time(),
+    );
+    Some(match txn.authenticator() {
+        TransactionAuthenticator::Ed25519 {
+            public_key,
+            signature,
+        } => SignedUserTransaction::new(raw_txn, public_key, signature),
+        TransactionAuthenticator::MultiEd25519 {
+            public_key,
+            signature,
+        } => SignedUserTransaction::multi_ed25519(raw_txn, public_key, signature),
+    })
 }
 
 pub(crate) type TxnQueue = pool::TransactionQueue;
@@ -141,6 +213,8 @@ pub(crate) struct Inner {
     chain_header: Arc<RwLock<BlockHeader>>,
     storage: Arc<dyn Store>,
     sequence_number_cache: NonceCache,
+    time_service: Arc<dyn TimeService>,
+    minimal_gas_price: u64,
 }
 impl std::fmt::Debug for Inner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -174,7 +248,8 @@ impl Inner {
         // we need to remove invalid txn here.
         // In fact, it would be better if caller can make it into one.
         // In this situation, we don't need to reimport invalid txn on chain_new_block.
-        self.queue.cull(self.get_pool_client())
+        self.queue
+            .cull(self.get_pool_client(), self.time_service.now_secs())
     }
 
     pub(crate) fn import_txns(
@@ -199,7 +274,7 @@ impl Inner {
     pub(crate) fn get_pending(&self, max_len: u64) -> Vec<Arc<VerifiedTransaction>> {
         let pending_settings = PendingSettings {
             block_number: u64::max_value(),
-            current_timestamp: u64::max_value(),
+            current_timestamp: self.time_service.now_secs(),
             nonce_cap: None,
             max_len: max_len as usize,
             ordering: PendingOrdering::Priority,
@@ -267,11 +342,63 @@ impl Inner {
         Ok(())
     }
 
+    pub(crate) fn dry_run(
+        &self,
+        txn: SignedUserTransaction,
+        check_signature: bool,
+    ) -> TransactionOutput {
+        let state_root = self.chain_header.read().state_root();
+        let statedb = ChainStateDB::new(self.storage.clone().into_super_arc(), Some(state_root));
+        starcoin_executor::dry_run(&statedb, txn, check_signature)
+    }
+
+    pub(crate) fn dry_run_affordable(
+        &self,
+        txn: SignedUserTransaction,
+        check_signature: bool,
+    ) -> Result<TransactionOutput> {
+        let state_root = self.chain_header.read().state_root();
+        let statedb = ChainStateDB::new(self.storage.clone().into_super_arc(), Some(state_root));
+        let balance = AccountStateReader::new(&statedb)
+            .get_balance(&txn.sender())?
+            .unwrap_or(0);
+        let (txn, check_signature) = match cap_max_gas_amount(&txn, balance) {
+            // the signature of the capped txn is invalid.
+            Some(capped_txn) => (capped_txn, false),
+            None => (txn, check_signature),
+        };
+        Ok(starcoin_executor::dry_run(&statedb, txn, check_signature))
+    }
+
+    pub(crate) fn gas_price(&self) -> Result<u64> {
+        let mut gas_prices = vec![];
+        let mut block_id = self.chain_header.read().id();
+        for _ in 0..GAS_PRICE_SAMPLE_BLOCKS {
+            let block = match self.storage.get_block_by_hash(block_id)? {
+                Some(block) => block,
+                None => break,
+            };
+            gas_prices.extend(block.transactions().iter().map(|t| t.gas_unit_price()));
+            if block.header().number() == 0 {
+                break;
+            }
+            block_id = block.header().parent_hash();
+        }
+        gas_prices.sort_unstable();
+        let gas_price = if gas_prices.is_empty() {
+            DEFAULT_GAS_PRICE
+        } else {
+            gas_prices[(gas_prices.len() - 1) * GAS_PRICE_PERCENTILE / 100]
+        };
+        Ok(gas_price.max(self.minimal_gas_price))
+    }
+
     fn get_pool_client(&self) -> PoolClient {
         PoolClient::new(
             self.chain_header.read().clone(),
             self.storage.clone(),
             self.sequence_number_cache.clone(),
+            self.time_service.now_secs(),
         )
     }
 }
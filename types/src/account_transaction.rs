@@ -0,0 +1,47 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block::BlockNumber;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+
+/// How an account is involved in a transaction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AccountTransactionKind {
+    /// The account is the sender of the transaction, or a payer of it.
+    Sent,
+    /// The account received a payment in the transaction.
+    Received,
+}
+
+/// A transaction in the history of an account, with the location where it was included.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountTransactionInfo {
+    /// index in the account's history, starting from 0.
+    pub index: u64,
+    pub kind: AccountTransactionKind,
+    pub block_hash: HashValue,
+    pub block_number: BlockNumber,
+    pub transaction_hash: HashValue,
+    /// txn index in block
+    pub transaction_index: u64,
+}
+
+impl AccountTransactionInfo {
+    pub fn new(
+        kind: AccountTransactionKind,
+        block_hash: HashValue,
+        block_number: BlockNumber,
+        transaction_hash: HashValue,
+        transaction_index: u64,
+    ) -> Self {
+        Self {
+            index: 0,
+            kind,
+            block_hash,
+            block_number,
+            transaction_hash,
+            transaction_index,
+        }
+    }
+}
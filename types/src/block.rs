@@ -20,8 +20,34 @@ use std::cmp::PartialOrd;
 /// Type for block number.
 pub type BlockNumber = u64;
 
-/// block timestamp allowed future times
-pub const ALLOWED_FUTURE_BLOCKTIME: u64 = 15 * 1000; // 15 Second;
+/// block timestamp allowed future times, in seconds as the block timestamp.
+pub const ALLOWED_FUTURE_BLOCKTIME: u64 = 15; // 15 Second;
+
+/// How many recent blocks are used to calculate the median timestamp, a new block must not be older than the median.
+pub const MEDIAN_TIME_BLOCK_COUNT: u64 = 11;
+
+/// Length of the nonce placeholder at the end of the mining header blob.
+pub const POW_NONCE_LENGTH: usize = 8;
+
+/// The version of the data which the proof of work commits to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PowVersion {
+    /// Only the hex of the parent hash, the block content is not committed.
+    ParentHash,
+    /// The mining header blob, which commits to all header fields except `consensus_header`.
+    HeaderBlob,
+}
+
+impl PowVersion {
+    /// The version of block `number` if the header blob is committed from block `header_blob_pow_number`.
+    pub fn of(number: BlockNumber, header_blob_pow_number: BlockNumber) -> Self {
+        if number >= header_blob_pow_number {
+            PowVersion::HeaderBlob
+        } else {
+            PowVersion::ParentHash
+        }
+    }
+}
 
 #[derive(
     Default,
@@ -133,6 +159,25 @@ impl BlockHeader {
         self.crypto_hash()
     }
 
+    /// The canonical mining header blob: the SCS encoding of the header with an empty
+    /// `consensus_header`, followed by a zero nonce placeholder of `POW_NONCE_LENGTH` bytes,
+    /// the miner replaces the placeholder with the nonce.
+    pub fn mining_header_blob(&self) -> Vec<u8> {
+        let mut header = self.clone();
+        header.consensus_header = vec![];
+        let mut blob = scs::to_bytes(&header).expect("Serialize BlockHeader should success.");
+        blob.extend_from_slice(&[0u8; POW_NONCE_LENGTH]);
+        blob
+    }
+
+    /// The data which the proof of work of `version` commits to.
+    pub fn pow_header(&self, version: PowVersion) -> Vec<u8> {
+        match version {
+            PowVersion::ParentHash => self.parent_hash.to_hex().into_bytes(),
+            PowVersion::HeaderBlob => self.mining_header_blob(),
+        }
+    }
+
     pub fn parent_hash(&self) -> HashValue {
         self.parent_hash
     }
@@ -538,6 +583,25 @@ impl BlockTemplate {
             body: self.body,
         }
     }
+    /// The data to mine for the block of this template, see `BlockHeader::pow_header`.
+    pub fn pow_header(&self, difficulty: U256, version: PowVersion) -> Vec<u8> {
+        BlockHeader::new_with_auth(
+            self.parent_hash,
+            self.parent_block_accumulator_root,
+            self.timestamp,
+            self.number,
+            self.author,
+            self.auth_key_prefix.clone(),
+            self.accumulator_root,
+            self.state_root,
+            self.gas_used,
+            self.gas_limit,
+            difficulty,
+            vec![],
+        )
+        .pow_header(version)
+    }
+
     pub fn into_block_header<H>(self, consensus_header: H, difficulty: U256) -> BlockHeader
     where
         H: Into<Vec<u8>>,
@@ -607,3 +671,38 @@ pub enum BlockState {
     Executed,
     Verified,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mining_header_blob() {
+        let header = BlockHeader::random();
+        let blob = header.pow_header(PowVersion::HeaderBlob);
+        assert_eq!(
+            &blob[blob.len() - POW_NONCE_LENGTH..],
+            &[0u8; POW_NONCE_LENGTH]
+        );
+
+        // the consensus header is not committed.
+        let mut mined = header.clone();
+        mined.consensus_header = vec![1, 2, 3];
+        assert_eq!(blob, mined.pow_header(PowVersion::HeaderBlob));
+
+        // any other field is committed.
+        let mut changed = header.clone();
+        changed.state_root = HashValue::random();
+        assert_ne!(blob, changed.pow_header(PowVersion::HeaderBlob));
+        assert_eq!(
+            header.pow_header(PowVersion::ParentHash),
+            changed.pow_header(PowVersion::ParentHash)
+        );
+
+        let template = BlockTemplate::from_block(Block::new(header.clone(), BlockBody::default()));
+        assert_eq!(
+            blob,
+            template.pow_header(header.difficulty(), PowVersion::HeaderBlob)
+        );
+    }
+}
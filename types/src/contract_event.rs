@@ -0,0 +1,36 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block::BlockNumber;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+pub use starcoin_vm_types::contract_event::*;
+
+/// A contract event with the location where it was emitted.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ContractEventInfo {
+    pub block_hash: HashValue,
+    pub block_number: BlockNumber,
+    pub transaction_hash: HashValue,
+    /// txn index in block
+    pub transaction_index: u64,
+    pub event: ContractEvent,
+}
+
+impl ContractEventInfo {
+    pub fn new(
+        block_hash: HashValue,
+        block_number: BlockNumber,
+        transaction_hash: HashValue,
+        transaction_index: u64,
+        event: ContractEvent,
+    ) -> Self {
+        Self {
+            block_hash,
+            block_number,
+            transaction_hash,
+            transaction_index,
+            event,
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub mod account_config {
 }
 
 pub mod account_state;
+pub mod account_transaction;
 pub mod accumulator_info;
 pub mod block;
 pub mod cmpact_block;
@@ -21,9 +22,7 @@ pub mod block_metadata {
     pub use starcoin_vm_types::block_metadata::BlockMetadata;
 }
 
-pub mod contract_event {
-    pub use starcoin_vm_types::contract_event::*;
-}
+pub mod contract_event;
 
 pub mod error;
 
@@ -32,6 +31,11 @@ pub mod event {
 }
 
 pub mod filter;
+
+pub mod move_resource {
+    pub use starcoin_vm_types::move_resource::MoveResource;
+}
+
 pub mod peer_info;
 pub mod proof;
 pub mod startup_info;
@@ -0,0 +1,38 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block::BlockNumber;
+use crate::transaction::TransactionInfo;
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::AccumulatorProof;
+use starcoin_crypto::HashValue;
+
+/// Proof of a `TransactionInfo` in the txn accumulator, against the `accumulator_root`
+/// of the header of block `block_id`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionProof {
+    pub block_id: HashValue,
+    /// Index of the transaction in the block.
+    pub txn_index: u64,
+    /// Index of the transaction info in the txn accumulator.
+    pub leaf_index: u64,
+    /// Root of the txn accumulator the proofs are against.
+    pub accumulator_root: HashValue,
+    pub txn_info: TransactionInfo,
+    pub proof: AccumulatorProof,
+    /// The first transaction info of the block, at leaf `leaf_index - txn_index`.
+    /// It is the info of the block metadata txn, which binds the leaf range to the block.
+    pub first_txn_info: TransactionInfo,
+    pub first_txn_proof: AccumulatorProof,
+}
+
+/// Proof of a block id in the block accumulator, against the block accumulator root in the
+/// `BlockInfo` of the later block `anchor_block_id`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockProof {
+    pub block_id: HashValue,
+    pub number: BlockNumber,
+    pub anchor_block_id: HashValue,
+    pub accumulator_root: HashValue,
+    pub proof: AccumulatorProof,
+}
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod account_proof;
+mod chain_proof;
 
 pub use account_proof::AccountProof;
+pub use chain_proof::{BlockProof, TransactionProof};
 
 // reuse libra in memory accumulator.
 pub use libra_types::proof::accumulator::InMemoryAccumulator;
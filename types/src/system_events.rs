@@ -10,6 +10,17 @@ use std::sync::Arc;
 #[rtype(result = "()")]
 pub struct NewHeadBlock(pub Arc<BlockDetail>);
 
+/// The master chain switched to another branch: `retracted` blocks are removed from the master
+/// and `enacted` blocks are added, both in ascending order of block number.
+/// It is broadcast before the `NewHeadBlock` of the new head, the last block of `enacted`.
+/// `enacted` is empty when the master is rewound to an ancestor.
+#[derive(Clone, Debug, Message)]
+#[rtype(result = "()")]
+pub struct ChainReorganized {
+    pub enacted: Arc<Vec<Block>>,
+    pub retracted: Arc<Vec<Block>>,
+}
+
 #[derive(Clone, Debug, Message)]
 #[rtype(result = "()")]
 pub struct MinedBlock(pub Arc<Block>);
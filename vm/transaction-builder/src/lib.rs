@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use starcoin_config::ChainNetwork;
+use starcoin_time_service::{RealTimeService, TimeService};
 use starcoin_vm_types::account_address::AccountAddress;
 use starcoin_vm_types::account_config;
 use starcoin_vm_types::account_config::{
@@ -21,9 +22,15 @@ use stdlib::init_scripts::InitScript;
 pub use stdlib::transaction_scripts::{CompiledBytes, StdlibScript};
 pub use stdlib::{stdlib_modules, StdLibOptions};
 
+/// How many seconds a transaction built by this module stays alive.
 pub const DEFAULT_EXPIRATION_TIME: u64 = 40_000;
 pub const TXN_RESERVED: u64 = 2_000_000;
 
+/// The expiration time of a transaction built now.
+pub fn default_expiration_time() -> Duration {
+    Duration::from_secs(RealTimeService::new().now_secs() + DEFAULT_EXPIRATION_TIME)
+}
+
 pub fn build_transfer_from_association(
     addr: AccountAddress,
     auth_key_prefix: Vec<u8>,
@@ -116,7 +123,7 @@ pub fn raw_peer_to_peer_txn(
         )),
         max_gas,
         gas_price,
-        Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+        default_expiration_time(),
     )
 }
 
@@ -137,7 +144,7 @@ pub fn raw_accept_coin_txn(
         )),
         max_gas,
         gas_price,
-        Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+        default_expiration_time(),
     )
 }
 
@@ -205,8 +212,7 @@ pub fn create_signed_txn_with_association_account(
             payload,
             max_gas_amount,
             gas_unit_price,
-            // TTL is 86400s. Initial time was set to 0.
-            Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+            default_expiration_time(),
         ))
         .expect("Sign txn should work.")
 }
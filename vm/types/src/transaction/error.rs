@@ -68,6 +68,13 @@ pub enum Error {
     /// Invalid RLP encoding
     InvalidRlp(String),
     CallErr(CallError),
+    /// Transaction is expired.
+    Expired {
+        /// Transaction expiration time in seconds
+        expiration: u64,
+        /// Current time in seconds
+        now: u64,
+    },
 }
 
 impl fmt::Display for Error {
@@ -106,6 +113,9 @@ impl fmt::Display for Error {
             TooBig => "Transaction too big".into(),
             InvalidRlp(err) => format!("Transaction has invalid RLP structure: {}.", err),
             CallErr(call_err) => format!("Call txn err: {}.", call_err),
+            Expired { expiration, now } => {
+                format!("Transaction expired at {}, now is {}.", expiration, now)
+            }
         };
 
         f.write_fmt(format_args!("Transaction error ({})", msg))
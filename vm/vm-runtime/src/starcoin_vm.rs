@@ -13,8 +13,8 @@ use starcoin_types::{
     account_config,
     block_metadata::BlockMetadata,
     transaction::{
-        ChangeSet, SignatureCheckedTransaction, SignedUserTransaction, Transaction,
-        TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
+        ChangeSet, SignedUserTransaction, Transaction, TransactionArgument, TransactionOutput,
+        TransactionPayload, TransactionStatus,
     },
     vm_error::{sub_status, StatusCode, VMStatus},
     write_set::WriteSet,
@@ -32,7 +32,8 @@ use starcoin_vm_types::{
     errors,
     errors::{convert_prologue_runtime_error, VMResult},
     gas_schedule::{self, AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, GasUnits},
-    language_storage::TypeTag,
+    identifier::IdentStr,
+    language_storage::{ModuleId, TypeTag},
     on_chain_config::{OnChainConfig, VMConfig, Version},
     state_view::StateView,
     transaction_metadata::TransactionMetadata,
@@ -65,6 +66,13 @@ pub struct StarcoinVM {
     version: Option<Version>,
 }
 
+/// Result of a read-only function call, the write set and events are always discarded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReadonlyFunctionOutput {
+    pub status: VMStatus,
+    pub gas_used: u64,
+}
+
 //TODO define as argument.
 pub static DEFAULT_CURRENCY_TY: Lazy<TypeTag> = Lazy::new(stc_type_tag);
 
@@ -279,7 +287,7 @@ impl StarcoinVM {
 
     fn verify_transaction_impl(
         &mut self,
-        transaction: &SignatureCheckedTransaction,
+        transaction: &SignedUserTransaction,
         remote_cache: &dyn RemoteCache,
         txn_data: &TransactionMetadata,
     ) -> Result<VerifiedTransactionPayload, VMStatus> {
@@ -605,23 +613,159 @@ impl StarcoinVM {
         txn: SignedUserTransaction,
         remote_cache: &mut StateViewCache<'_>,
     ) -> TransactionOutput {
-        let txn_data = TransactionMetadata::new(&txn);
-
         // check signature
-        let signature_checked_txn = match txn.check_signature() {
-            Ok(t) => Ok(t),
-            Err(_) => Err(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
-        };
+        match txn.check_signature() {
+            Ok(txn) => self.execute_user_transaction_impl(&txn, remote_cache),
+            Err(_) => discard_error_output(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
+        }
+    }
+
+    fn execute_user_transaction_impl(
+        &mut self,
+        txn: &SignedUserTransaction,
+        remote_cache: &mut StateViewCache<'_>,
+    ) -> TransactionOutput {
+        let txn_data = TransactionMetadata::new(txn);
+        match self.verify_transaction_impl(txn, remote_cache, &txn_data) {
+            Ok(payload) => self.execute_verified_payload(remote_cache, &txn_data, payload),
+            Err(e) => discard_error_output(e),
+        }
+    }
+
+    /// Execute a user transaction against `state_view` without committing the output,
+    /// the signature check is skipped if `check_signature` is false.
+    pub fn dry_run_transaction(
+        &mut self,
+        state_view: &dyn StateView,
+        txn: SignedUserTransaction,
+        check_signature: bool,
+    ) -> TransactionOutput {
+        let mut data_cache = StateViewCache::new(state_view);
+        self.load_configs_impl(&data_cache);
+        if check_signature {
+            self.execute_user_transaction(txn, &mut data_cache)
+        } else {
+            self.execute_user_transaction_impl(&txn, &mut data_cache)
+        }
+    }
+
+    /// Call a public function of `module` by `sender` in a session over `state_view`, nothing is
+    /// written back. Non-public functions are rejected before the call.
+    /// TODO return the values of the function, the MoveVM of current libra rev only returns `()`.
+    pub fn execute_readonly_function(
+        &mut self,
+        state_view: &dyn StateView,
+        sender: AccountAddress,
+        module: &ModuleId,
+        function_name: &IdentStr,
+        type_args: Vec<TypeTag>,
+        args: &[TransactionArgument],
+        max_gas_amount: u64,
+    ) -> ReadonlyFunctionOutput {
+        let data_cache = StateViewCache::new(state_view);
+        self.load_configs_impl(&data_cache);
+        self.execute_readonly(
+            &data_cache,
+            max_gas_amount,
+            |vm, data_store, cost_strategy| {
+                Self::check_public_function(data_store, module, function_name)?;
+                vm.move_vm.execute_function(
+                    module,
+                    function_name,
+                    type_args,
+                    convert_txn_args(args),
+                    sender,
+                    data_store,
+                    cost_strategy,
+                )
+            },
+        )
+    }
 
-        match signature_checked_txn {
-            Ok(txn) => {
-                let verified_payload = self.verify_transaction_impl(&txn, remote_cache, &txn_data);
-                match verified_payload {
-                    Ok(payload) => self.execute_verified_payload(remote_cache, &txn_data, payload),
-                    Err(e) => discard_error_output(e),
+    /// Execute `script` by `sender` in a session over `state_view`, nothing is written back.
+    /// Only scripts allowed by the on chain publishing option can be executed.
+    /// TODO return the values of the script, the MoveVM of current libra rev only returns `()`.
+    pub fn execute_readonly_script(
+        &mut self,
+        state_view: &dyn StateView,
+        sender: AccountAddress,
+        script: &Script,
+        max_gas_amount: u64,
+    ) -> ReadonlyFunctionOutput {
+        let data_cache = StateViewCache::new(state_view);
+        self.load_configs_impl(&data_cache);
+        self.execute_readonly(
+            &data_cache,
+            max_gas_amount,
+            |vm, data_store, cost_strategy| {
+                if !vm
+                    .vm_config()?
+                    .publishing_option
+                    .is_allowed_script(&script.code())
+                {
+                    warn!("[VM] Custom scripts not allowed: {:?}", &script.code());
+                    return Err(VMStatus::new(StatusCode::UNKNOWN_SCRIPT));
+                }
+                vm.move_vm.execute_script(
+                    script.code().to_vec(),
+                    script.ty_args().to_vec(),
+                    convert_txn_args(script.args()),
+                    sender,
+                    data_store,
+                    cost_strategy,
+                )
+            },
+        )
+    }
+
+    fn execute_readonly<F>(
+        &self,
+        remote_cache: &dyn RemoteCache,
+        max_gas_amount: u64,
+        execute: F,
+    ) -> ReadonlyFunctionOutput
+    where
+        F: FnOnce(&Self, &mut TransactionDataCache<'_>, &mut CostStrategy) -> VMResult<()>,
+    {
+        let gas_schedule = match self.get_gas_schedule() {
+            Ok(s) => s,
+            Err(status) => {
+                return ReadonlyFunctionOutput {
+                    status,
+                    gas_used: 0,
                 }
             }
-            Err(e) => discard_error_output(e),
+        };
+        let max_gas_amount = GasUnits::new(max_gas_amount);
+        let mut cost_strategy = CostStrategy::transaction(gas_schedule, max_gas_amount);
+        let mut data_store = TransactionDataCache::new(remote_cache);
+        let status = match execute(self, &mut data_store, &mut cost_strategy) {
+            Ok(()) => VMStatus::new(StatusCode::EXECUTED),
+            Err(e) => e,
+        };
+        ReadonlyFunctionOutput {
+            status,
+            gas_used: max_gas_amount.sub(cost_strategy.remaining_gas()).get(),
+        }
+    }
+
+    fn check_public_function(
+        data_store: &mut dyn DataStore,
+        module: &ModuleId,
+        function_name: &IdentStr,
+    ) -> VMResult<()> {
+        let code = data_store.load_module(module)?;
+        let compiled_module = CompiledModule::deserialize(code.as_slice())?;
+        let function_def = compiled_module.function_defs().iter().find(|def| {
+            let handle = compiled_module.function_handle_at(def.function);
+            compiled_module.identifier_at(handle.name) == function_name
+        });
+        match function_def {
+            Some(def) if def.is_public() => Ok(()),
+            Some(_) => Err(VMStatus::new(StatusCode::VISIBILITY_MISMATCH)
+                .with_message(format!("function {} is not public", function_name))),
+            None => Err(VMStatus::new(StatusCode::FUNCTION_RESOLUTION_FAILURE)
+                .with_message(format!("function {} not found", function_name))),
         }
     }
 